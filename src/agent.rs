@@ -0,0 +1,256 @@
+use crate::{MystikoCliError, SecretString, WALLET_PASSWORD_REQUIRED};
+use anyhow::anyhow;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::time::Instant;
+use zeroize::Zeroize;
+
+pub const AGENT_SOCKET_FILE: &str = "agent.sock";
+pub const AGENT_REQUEST_TIMEOUT_MS: u64 = 5000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum AgentRequest {
+    Unlock {
+        password: AgentSecret,
+        ttl_secs: u64,
+    },
+    Password,
+    Lock,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentResponse {
+    pub unlocked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<AgentSecret>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentSecret(pub SecretString);
+
+impl Serialize for AgentSecret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.expose_secret())
+    }
+}
+
+impl<'de> Deserialize<'de> for AgentSecret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SecretString::deserialize(deserializer).map(AgentSecret)
+    }
+}
+
+struct LockedPassword {
+    buffer: Vec<u8>,
+    locked: bool,
+}
+
+impl LockedPassword {
+    fn new(password: &SecretString) -> Self {
+        let password = password.expose_secret().as_bytes();
+        let mut buffer = Vec::with_capacity(password.len());
+        let locked = buffer.capacity() == 0
+            || unsafe { libc::mlock(buffer.as_ptr() as *const libc::c_void, buffer.capacity()) }
+                == 0;
+        if !locked {
+            log::warn!("failed to lock the memory of the agent password");
+        }
+        buffer.extend_from_slice(password);
+        LockedPassword { buffer, locked }
+    }
+
+    fn password(&self) -> SecretString {
+        SecretString::from(String::from_utf8_lossy(&self.buffer).to_string())
+    }
+}
+
+impl Drop for LockedPassword {
+    fn drop(&mut self) {
+        self.buffer.zeroize();
+        if self.locked && self.buffer.capacity() > 0 {
+            unsafe {
+                libc::munlock(
+                    self.buffer.as_ptr() as *const libc::c_void,
+                    self.buffer.capacity(),
+                );
+            }
+        }
+    }
+}
+
+struct AgentSession {
+    password: LockedPassword,
+    expires_at: Instant,
+    expires_at_secs: u64,
+}
+
+pub fn default_agent_socket_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or(PathBuf::from(""))
+        .join(".mystiko")
+        .join(AGENT_SOCKET_FILE)
+}
+
+pub async fn run_agent<P: AsRef<Path>>(socket_path: P) -> Result<(), MystikoCliError> {
+    let socket_path = socket_path.as_ref();
+    if tokio::fs::try_exists(socket_path).await? {
+        if UnixStream::connect(socket_path).await.is_ok() {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "An agent is already listening on {}",
+                socket_path.to_string_lossy()
+            )));
+        }
+        tokio::fs::remove_file(socket_path).await?;
+    }
+    if let Some(parent) = socket_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let listener = UnixListener::bind(socket_path)?;
+    tokio::fs::set_permissions(
+        socket_path,
+        std::os::unix::fs::PermissionsExt::from_mode(0o600),
+    )
+    .await?;
+    let mut session: Option<AgentSession> = None;
+    loop {
+        let expires_at = session.as_ref().map(|session| session.expires_at);
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, _) = accepted?;
+                if let Err(err) = handle_agent_connection(stream, &mut session).await {
+                    log::warn!("failed to handle agent request: {}", err);
+                }
+            }
+            _ = sleep_until_expired(expires_at) => {
+                session = None;
+            }
+        }
+    }
+}
+
+pub async fn send_agent_request<P: AsRef<Path>>(
+    socket_path: P,
+    request: &AgentRequest,
+) -> Result<AgentResponse, MystikoCliError> {
+    let stream = UnixStream::connect(socket_path).await?;
+    let (reader, mut writer) = stream.into_split();
+    let mut request = serde_json::to_string(request)?;
+    request.push('\n');
+    let written = writer.write_all(request.as_bytes()).await;
+    request.zeroize();
+    written?;
+    let mut line = String::new();
+    let timeout = Duration::from_millis(AGENT_REQUEST_TIMEOUT_MS);
+    tokio::time::timeout(timeout, BufReader::new(reader).read_line(&mut line))
+        .await
+        .map_err(|_| MystikoCliError::AnyhowError(anyhow!("Agent request timed out")))??;
+    let response: Result<AgentResponse, _> = serde_json::from_str(&line);
+    line.zeroize();
+    let response = response?;
+    match response.error {
+        Some(error) => Err(MystikoCliError::AnyhowError(anyhow!(error))),
+        None => Ok(response),
+    }
+}
+
+pub async fn request_agent_password<P: AsRef<Path>>(
+    socket_path: P,
+) -> Result<SecretString, MystikoCliError> {
+    send_agent_request(socket_path, &AgentRequest::Password)
+        .await
+        .ok()
+        .and_then(|response| response.password)
+        .map(|password| password.0)
+        .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!(WALLET_PASSWORD_REQUIRED)))
+}
+
+async fn handle_agent_connection(
+    stream: UnixStream,
+    session: &mut Option<AgentSession>,
+) -> Result<(), MystikoCliError> {
+    let peer = stream.peer_cred()?;
+    let (reader, mut writer) = stream.into_split();
+    let response = if peer.uid() != unsafe { libc::geteuid() } {
+        AgentResponse {
+            error: Some(String::from("Agent refused a connection from another user")),
+            ..AgentResponse::default()
+        }
+    } else {
+        let mut line = String::new();
+        let timeout = Duration::from_millis(AGENT_REQUEST_TIMEOUT_MS);
+        tokio::time::timeout(timeout, BufReader::new(reader).read_line(&mut line))
+            .await
+            .map_err(|_| MystikoCliError::AnyhowError(anyhow!("Agent request timed out")))??;
+        let request: Result<AgentRequest, _> = serde_json::from_str(&line);
+        line.zeroize();
+        match request {
+            Ok(request) => handle_agent_request(request, session),
+            Err(err) => AgentResponse {
+                error: Some(format!("Invalid agent request: {}", err)),
+                ..AgentResponse::default()
+            },
+        }
+    };
+    let mut response = serde_json::to_string(&response)?;
+    response.push('\n');
+    let written = writer.write_all(response.as_bytes()).await;
+    response.zeroize();
+    Ok(written?)
+}
+
+fn handle_agent_request(
+    request: AgentRequest,
+    session: &mut Option<AgentSession>,
+) -> AgentResponse {
+    if session
+        .as_ref()
+        .is_some_and(|session| session.expires_at <= Instant::now())
+    {
+        *session = None;
+    }
+    let with_password = matches!(request, AgentRequest::Password);
+    match request {
+        AgentRequest::Unlock { password, ttl_secs } => {
+            let ttl = Duration::from_secs(ttl_secs);
+            let expires_at_secs = SystemTime::now()
+                .checked_add(ttl)
+                .unwrap_or(SystemTime::now())
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default();
+            *session = Some(AgentSession {
+                password: LockedPassword::new(&password.0),
+                expires_at: Instant::now().checked_add(ttl).unwrap_or_else(Instant::now),
+                expires_at_secs,
+            });
+        }
+        AgentRequest::Lock => {
+            *session = None;
+        }
+        AgentRequest::Password => {}
+    }
+    match session.as_ref() {
+        Some(session) => AgentResponse {
+            unlocked: true,
+            expires_at: Some(session.expires_at_secs),
+            password: with_password.then(|| AgentSecret(session.password.password())),
+            ..AgentResponse::default()
+        },
+        None => AgentResponse::default(),
+    }
+}
+
+async fn sleep_until_expired(expires_at: Option<Instant>) {
+    match expires_at {
+        Some(expires_at) => tokio::time::sleep_until(expires_at).await,
+        None => std::future::pending().await,
+    }
+}
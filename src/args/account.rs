@@ -1,4 +1,7 @@
-use clap::{Args, Subcommand};
+use crate::{expose_wallet_password, MystikoCliError, SecretString};
+use clap::{ArgGroup, Args, Subcommand, ValueEnum};
+use mystiko_protos::core::handler::v1::UpdateAccountOptions;
+use mystiko_protos::core::v1::AccountStatus;
 
 #[derive(Debug, Clone, Args)]
 pub struct AccountCommand {
@@ -10,36 +13,209 @@ pub struct AccountCommand {
 pub enum AccountCommands {
     #[command(about = "create a new account")]
     Create(AccountCreateCommand),
+    #[command(about = "recreate the account at the given derivation index")]
+    Derive(AccountDeriveCommand),
     #[command(about = "import an account")]
     Import(AccountImportCommand),
-    #[command(about = "get an account")]
+    #[command(about = "export the secret key of an account")]
     ExportSecretKey(AccountExportSecretKeyCommand),
+    #[command(about = "export an account into an encrypted file")]
+    Export(AccountExportCommand),
+    #[command(about = "update an account")]
+    Update(AccountUpdateCommand),
+    #[command(about = "get an account")]
+    Get(AccountGetCommand),
     #[command(about = "list all accounts")]
-    List,
+    List(AccountListCommand),
 }
 
 #[derive(Debug, Clone, Args)]
 pub struct AccountCreateCommand {
-    #[arg(long, help = "password for wallet")]
-    pub password: String,
+    #[arg(
+        long,
+        help = "password for wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
+    #[arg(long, conflicts_with = "name_prefix", help = "name for this account")]
+    pub name: Option<String>,
+    #[arg(
+        long,
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "number of accounts to create"
+    )]
+    pub count: u32,
+    #[arg(
+        long,
+        help = "name prefix for the created accounts, followed by the derivation index"
+    )]
+    pub name_prefix: Option<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct AccountDeriveCommand {
+    #[arg(
+        long,
+        help = "password for wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
+    #[arg(long, help = "derivation index of the account")]
+    pub index: u32,
     #[arg(long, help = "name for this account")]
     pub name: Option<String>,
 }
 
 #[derive(Debug, Clone, Args)]
 pub struct AccountImportCommand {
-    #[arg(long, help = "password for wallet")]
-    pub password: String,
-    #[arg(long, help = "secret key for this account")]
-    pub secret_key: String,
+    #[arg(
+        long,
+        help = "password for wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
+    #[arg(
+        long,
+        required_unless_present = "keystore",
+        conflicts_with = "keystore",
+        help = "secret key for this account"
+    )]
+    pub secret_key: Option<SecretString>,
+    #[arg(
+        long,
+        requires = "export_passphrase",
+        help = "path to the encrypted keystore file of this account"
+    )]
+    pub keystore: Option<String>,
+    #[arg(long, help = "passphrase for decrypting the keystore file")]
+    pub export_passphrase: Option<SecretString>,
     #[arg(long, help = "name for this account")]
     pub name: Option<String>,
 }
 
 #[derive(Debug, Clone, Args)]
 pub struct AccountExportSecretKeyCommand {
-    #[arg(long, help = "password for wallet")]
-    pub password: String,
+    #[arg(
+        long,
+        help = "password for wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
     #[arg(long, help = "shielded address of the account")]
     pub shielded_address: String,
 }
+
+#[derive(Debug, Clone, Args)]
+pub struct AccountExportCommand {
+    #[arg(
+        long,
+        help = "password for wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
+    #[arg(long, help = "shielded address of the account")]
+    pub shielded_address: String,
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = AccountExportFormat::Keystore,
+        help = "format of the exported file"
+    )]
+    pub format: AccountExportFormat,
+    #[arg(
+        long,
+        help = "passphrase for encrypting the exported file, separate from the wallet password"
+    )]
+    pub export_passphrase: SecretString,
+    #[arg(long, help = "path to the exported file")]
+    pub out: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AccountExportFormat {
+    Keystore,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct AccountUpdateCommand {
+    #[arg(
+        long,
+        help = "password for wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
+    #[arg(long, help = "shielded address of the account")]
+    pub shielded_address: String,
+    #[arg(long, help = "new name for this account")]
+    pub name: Option<String>,
+    #[arg(long, help = "new scan size for this account")]
+    pub scan_size: Option<u32>,
+    #[arg(
+        long,
+        value_parser = parse_account_status_value,
+        help = "new status for this account, e.g. created, scanning or scanned"
+    )]
+    pub status: Option<AccountStatus>,
+}
+
+#[derive(Debug, Clone, Args)]
+#[command(group(
+    ArgGroup::new("account")
+        .required(true)
+        .args(["shielded_address", "public_key", "name"]),
+))]
+pub struct AccountGetCommand {
+    #[arg(long, help = "shielded address of the account")]
+    pub shielded_address: Option<String>,
+    #[arg(long, help = "public key of the account")]
+    pub public_key: Option<String>,
+    #[arg(long, help = "name of the account")]
+    pub name: Option<String>,
+    #[arg(long, help = "include the private balance of the account")]
+    pub with_balance: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct AccountListCommand {
+    #[arg(long, help = "listing account(s) whose name contains the given text")]
+    pub name: Option<String>,
+    #[arg(
+        long,
+        value_parser = parse_account_status_value,
+        help = "listing account(s) with the given status(es)"
+    )]
+    pub status: Option<Vec<AccountStatus>>,
+    #[arg(long, help = "listing account(s) with the given wallet_id(s)")]
+    pub wallet_id: Option<Vec<String>>,
+    #[arg(long, help = "include the private balance of each account")]
+    pub with_balance: bool,
+    #[arg(
+        long,
+        help = "limiting the number of account(s), all accounts are listed if not set"
+    )]
+    pub limit: Option<u64>,
+    #[arg(
+        long,
+        requires = "limit",
+        help = "the page index of the listing account(s), default value is 1"
+    )]
+    pub page: Option<u64>,
+}
+
+impl TryFrom<AccountUpdateCommand> for UpdateAccountOptions {
+    type Error = MystikoCliError;
+
+    fn try_from(args: AccountUpdateCommand) -> Result<Self, Self::Error> {
+        Ok(UpdateAccountOptions::builder()
+            .wallet_password(expose_wallet_password(&args.password)?)
+            .name(args.name)
+            .scan_size(args.scan_size)
+            .status(args.status.map(|status| status as i32))
+            .build())
+    }
+}
+
+pub(crate) fn parse_account_status_value(status: &str) -> Result<AccountStatus, String> {
+    let status_str = format!("ACCOUNT_STATUS_{}", status.to_ascii_uppercase());
+    AccountStatus::from_str_name(&status_str).ok_or_else(|| {
+        format!(
+            "invalid account status {}, expected created, scanning or scanned",
+            status
+        )
+    })
+}
@@ -0,0 +1,16 @@
+use crate::SecretString;
+use clap::Args;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Args)]
+pub struct UnlockCommand {
+    #[arg(long, help = "password for wallet")]
+    pub password: SecretString,
+    #[arg(
+        long,
+        default_value = "15m",
+        value_parser = humantime::parse_duration,
+        help = "how long the agent keeps the wallet unlocked, e.g. 15m or 1h"
+    )]
+    pub ttl: Duration,
+}
@@ -0,0 +1,28 @@
+use clap::{Args, Subcommand};
+
+#[derive(Debug, Clone, Args)]
+pub struct AuditCommand {
+    #[command(subcommand)]
+    pub commands: AuditCommands,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum AuditCommands {
+    #[command(about = "list the recorded sensitive operations")]
+    List(AuditListCommand),
+    #[command(about = "verify the hash chain of the audit log")]
+    Verify,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct AuditListCommand {
+    #[arg(
+        long,
+        help = "only list the entries of the given command, e.g. \"spend create\""
+    )]
+    pub command: Option<String>,
+    #[arg(long, default_value_t = 10, help = "limit of the entries to list")]
+    pub limit: u64,
+    #[arg(long, default_value_t = 1, help = "page of the entries to list")]
+    pub page: u64,
+}
@@ -0,0 +1,29 @@
+use clap::{Args, Subcommand};
+
+#[derive(Debug, Clone, Args)]
+pub struct ConfigCommand {
+    #[command(subcommand)]
+    pub commands: ConfigCommands,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ConfigCommands {
+    #[command(about = "validate a custom config file")]
+    Validate(ConfigValidateCommand),
+    #[command(about = "show the differences between two config files")]
+    Diff(ConfigDiffCommand),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ConfigValidateCommand {
+    #[arg(help = "path to the config file to be validated")]
+    pub file: String,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ConfigDiffCommand {
+    #[arg(help = "path to the original config file")]
+    pub a: String,
+    #[arg(help = "path to the updated config file")]
+    pub b: String,
+}
@@ -1,7 +1,10 @@
-use clap::{Args, Subcommand};
+use crate::{DepositManifestRow, SecretString, TransactionReplaceCommand};
+use clap::{Args, Subcommand, ValueEnum};
 use mystiko_protos::common::v1::BridgeType;
 use mystiko_protos::core::handler::v1::{CreateDepositOptions, QuoteDepositOptions};
 use mystiko_protos::core::v1::DepositStatus;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Args)]
 pub struct DepositCommand {
@@ -15,6 +18,18 @@ pub enum DepositCommands {
     Quote(DepositQuoteCommand),
     #[command(about = "create a new deposit")]
     Create(DepositCreateCommand),
+    #[command(about = "create and send deposits listed in a CSV or JSON manifest")]
+    Batch(DepositBatchCommand),
+    #[command(about = "prepare unsigned deposit transaction(s) for offline signing")]
+    Prepare(DepositPrepareCommand),
+    #[command(about = "replace the pending deposit transaction with higher fees")]
+    SpeedUp(TransactionReplaceCommand),
+    #[command(about = "cancel the pending deposit transaction with a zero-value self-transfer")]
+    Cancel(TransactionReplaceCommand),
+    #[command(about = "check and advance deposits left in an intermediate status")]
+    Recover(DepositRecoverCommand),
+    #[command(about = "wait until the deposit reaches the given status")]
+    Wait(DepositWaitCommand),
     #[command(about = "list all deposits")]
     List(DepositListCommand),
 }
@@ -45,10 +60,23 @@ pub struct DepositCreateCommand {
     pub shielded_address: String,
     #[arg(long, help = "rollup_fee_amount for the deposit")]
     pub rollup_fee: Option<f64>,
-    #[arg(long, help = "private key for signing the transaction")]
-    pub private_key: String,
+    #[arg(
+        long,
+        required_unless_present = "signer_command",
+        help = "private key for signing the transaction"
+    )]
+    pub private_key: Option<SecretString>,
+    #[arg(
+        long,
+        conflicts_with = "private_key",
+        requires = "signer_provider",
+        help = "external program for signing the transaction"
+    )]
+    pub signer_command: Option<String>,
     #[arg(long, help = "provider for signing and sending the transaction")]
     pub signer_provider: Option<String>,
+    #[arg(long, help = "timeout in milliseconds for the signer command")]
+    pub signer_timeout_ms: Option<u64>,
     #[arg(long, help = "dst_chain_id for the cross-chain deposit")]
     pub dst_chain_id: Option<u64>,
     #[arg(long, help = "bridge_fee_amount for the cross-chain deposit")]
@@ -80,6 +108,206 @@ pub struct DepositCreateCommand {
         help = "interval in milliseconds for polling a transaction receipt"
     )]
     pub tx_wait_interval_ms: Option<u64>,
+    #[arg(long, help = "skip the confirmation prompt")]
+    pub yes: bool,
+    #[arg(
+        long,
+        help = "build and estimate the deposit transaction(s) without broadcasting them"
+    )]
+    pub dry_run: bool,
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with = "dry_run",
+        requires = "split_plan",
+        help = "split the amount into the pool's recommended amounts, one deposit per piece"
+    )]
+    pub split: Option<DepositSplitStrategy>,
+    #[arg(
+        long,
+        requires = "split",
+        help = "path of the split plan to write or resume, \
+        its progress is kept in <split-plan>.results.json"
+    )]
+    pub split_plan: Option<String>,
+    #[arg(
+        long,
+        requires = "split",
+        value_parser = humantime::parse_duration,
+        help = "spread the split deposits with random delays of up to this duration, e.g. 6h"
+    )]
+    pub split_max_delay: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DepositSplitStrategy {
+    Standard,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DepositPrepareCommand {
+    #[arg(
+        long,
+        help = "continue preparing the existing deposit with the given id"
+    )]
+    pub deposit_id: Option<String>,
+    #[arg(long, default_value_t = 1, help = "chain_id for the deposit")]
+    pub chain_id: u64,
+    #[arg(long, default_value = "ETH", help = "asset_symbol for the deposit")]
+    pub asset_symbol: String,
+    #[arg(
+        long,
+        required_unless_present = "deposit_id",
+        help = "amount of tokens to deposit"
+    )]
+    pub amount: Option<f64>,
+    #[arg(
+        long,
+        required_unless_present = "deposit_id",
+        help = "shielded_address for the deposit"
+    )]
+    pub shielded_address: Option<String>,
+    #[arg(long, help = "rollup_fee_amount for the deposit")]
+    pub rollup_fee: Option<f64>,
+    #[arg(long, help = "dst_chain_id for the cross-chain deposit")]
+    pub dst_chain_id: Option<u64>,
+    #[arg(long, help = "bridge_fee_amount for the cross-chain deposit")]
+    pub bridge_fee: Option<f64>,
+    #[arg(long, help = "executor_fee_amount for the cross-chain deposit")]
+    pub executor_fee: Option<f64>,
+    #[arg(long, help = "bridge_type for the cross-chain deposit")]
+    pub bridge_type: Option<String>,
+    #[arg(long, help = "address of the offline signing key")]
+    pub from: String,
+    #[arg(
+        long,
+        help = "nonce of the first transaction, queried from the provider if not given"
+    )]
+    pub nonce: Option<u64>,
+    #[arg(
+        long,
+        help = "provider for estimating the transactions and querying the nonce of the signing address"
+    )]
+    pub signer_provider: String,
+    #[arg(
+        long,
+        help = "gas limit of the deposit transaction when it can not be estimated before the approve"
+    )]
+    pub deposit_gas_limit: Option<u64>,
+    #[arg(long, help = "timeout in milliseconds for the querying provider")]
+    pub query_timeout_ms: Option<u64>,
+    #[arg(long, help = "path of the unsigned transactions file")]
+    pub out: String,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DepositBatchCommand {
+    #[arg(
+        long,
+        help = "path of the CSV or JSON manifest with columns chain, asset, amount, \
+        shielded_address, bridge, dst_chain, rollup_fee, bridge_fee and executor_fee"
+    )]
+    pub manifest: String,
+    #[arg(
+        long,
+        help = "path of the results file for resuming, defaults to <manifest>.results.json"
+    )]
+    pub results: Option<String>,
+    #[arg(
+        long,
+        required_unless_present = "signer_command",
+        help = "private key for signing the transactions"
+    )]
+    pub private_key: Option<SecretString>,
+    #[arg(
+        long,
+        conflicts_with = "private_key",
+        help = "external program for signing the transactions"
+    )]
+    pub signer_command: Option<String>,
+    #[arg(long, help = "timeout in milliseconds for the signer command")]
+    pub signer_timeout_ms: Option<u64>,
+    #[arg(
+        long,
+        help = "provider for checking balances and sending the transactions"
+    )]
+    pub signer_provider: String,
+    #[arg(long, help = "message for the screening")]
+    pub screening_message: Option<String>,
+    #[arg(long, help = "timeout in milliseconds for the querying provider")]
+    pub query_timeout_ms: Option<u64>,
+    #[arg(
+        long,
+        help = "number of confirmations for the asset approve transaction"
+    )]
+    pub asset_approve_confirmations: Option<u64>,
+    #[arg(long, help = "number of confirmations for the deposit transaction")]
+    pub deposit_confirmations: Option<u64>,
+    #[arg(long, help = "skip the confirmation prompt")]
+    pub yes: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DepositRecoverCommand {
+    #[arg(long, help = "chain_id of the deposits to recover")]
+    pub chain_id: u64,
+    #[arg(long, help = "provider for querying the recorded transactions")]
+    pub provider: String,
+    #[arg(long, help = "recover only the deposit(s) with the given id(s)")]
+    pub id: Option<Vec<String>>,
+    #[arg(
+        long,
+        help = "private key for sending the deposit after a confirmed approve"
+    )]
+    pub private_key: Option<SecretString>,
+    #[arg(
+        long,
+        conflicts_with = "private_key",
+        help = "external program for signing the deposit after a confirmed approve"
+    )]
+    pub signer_command: Option<String>,
+    #[arg(long, help = "timeout in milliseconds for the signer command")]
+    pub signer_timeout_ms: Option<u64>,
+    #[arg(long, help = "number of confirmations for the deposit transaction")]
+    pub deposit_confirmations: Option<u64>,
+    #[arg(long, help = "timeout in milliseconds for the querying provider")]
+    pub query_timeout_ms: Option<u64>,
+    #[arg(long, help = "skip the confirmation prompt")]
+    pub yes: bool,
+    #[arg(skip)]
+    pub replacements_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct DepositWaitCommand {
+    #[arg(long, help = "id of the deposit to wait for")]
+    pub id: String,
+    #[arg(
+        long,
+        default_value = "executed",
+        value_parser = parse_deposit_status_value,
+        help = "status to wait for, e.g. queued, included or executed"
+    )]
+    pub until: DepositStatus,
+    #[arg(
+        long,
+        default_value = "2h",
+        value_parser = humantime::parse_duration,
+        help = "how long to wait before giving up, e.g. 30m or 2h"
+    )]
+    pub timeout: Duration,
+    #[arg(
+        long,
+        default_value = "30s",
+        value_parser = humantime::parse_duration,
+        help = "how long to sleep between polling rounds"
+    )]
+    pub interval: Duration,
+    #[arg(
+        long,
+        help = "password of the current wallet for syncing the scanner, read from the session agent if unlocked, scanner sync is skipped without it"
+    )]
+    pub password: Option<SecretString>,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -141,6 +369,19 @@ impl From<DepositQuoteCommand> for QuoteDepositOptions {
     }
 }
 
+impl From<DepositCreateCommand> for QuoteDepositOptions {
+    fn from(args: DepositCreateCommand) -> Self {
+        let bridge_type = parse_bridge_type(args.bridge_type);
+        QuoteDepositOptions::builder()
+            .chain_id(args.chain_id)
+            .asset_symbol(args.asset_symbol)
+            .dst_chain_id(args.dst_chain_id)
+            .bridge_type(bridge_type)
+            .query_timeout_ms(args.query_timeout_ms)
+            .build()
+    }
+}
+
 impl From<DepositCreateCommand> for CreateDepositOptions {
     fn from(args: DepositCreateCommand) -> Self {
         let bridge_type = parse_bridge_type(args.bridge_type);
@@ -159,6 +400,53 @@ impl From<DepositCreateCommand> for CreateDepositOptions {
     }
 }
 
+impl From<DepositManifestRow> for QuoteDepositOptions {
+    fn from(row: DepositManifestRow) -> Self {
+        let bridge_type = parse_bridge_type(row.bridge_type);
+        QuoteDepositOptions::builder()
+            .chain_id(row.chain_id)
+            .asset_symbol(row.asset_symbol)
+            .dst_chain_id(row.dst_chain_id)
+            .bridge_type(bridge_type)
+            .build()
+    }
+}
+
+impl From<DepositManifestRow> for CreateDepositOptions {
+    fn from(row: DepositManifestRow) -> Self {
+        let bridge_type = parse_bridge_type(row.bridge_type);
+        CreateDepositOptions::builder()
+            .chain_id(row.chain_id)
+            .asset_symbol(row.asset_symbol)
+            .amount(row.amount)
+            .rollup_fee_amount(row.rollup_fee)
+            .shielded_address(row.shielded_address)
+            .dst_chain_id(row.dst_chain_id)
+            .bridge_type(bridge_type)
+            .bridge_fee_amount(row.bridge_fee)
+            .executor_fee_amount(row.executor_fee)
+            .build()
+    }
+}
+
+impl From<DepositPrepareCommand> for CreateDepositOptions {
+    fn from(args: DepositPrepareCommand) -> Self {
+        let bridge_type = parse_bridge_type(args.bridge_type);
+        CreateDepositOptions::builder()
+            .chain_id(args.chain_id)
+            .asset_symbol(args.asset_symbol)
+            .amount(args.amount.unwrap_or_default())
+            .rollup_fee_amount(args.rollup_fee)
+            .shielded_address(args.shielded_address.unwrap_or_default())
+            .dst_chain_id(args.dst_chain_id)
+            .bridge_type(bridge_type)
+            .bridge_fee_amount(args.bridge_fee)
+            .executor_fee_amount(args.executor_fee)
+            .query_timeout_ms(args.query_timeout_ms)
+            .build()
+    }
+}
+
 pub(crate) fn parse_bridge_types(bridge_types: &[String]) -> Vec<i32> {
     bridge_types
         .iter()
@@ -184,3 +472,13 @@ pub(crate) fn parse_deposit_status(status: &str) -> Option<i32> {
     let status_str = format!("DEPOSIT_STATUS_{}", status.to_ascii_uppercase());
     DepositStatus::from_str_name(&status_str).map(|status| status as i32)
 }
+
+pub(crate) fn parse_deposit_status_value(status: &str) -> Result<DepositStatus, String> {
+    let status_str = format!("DEPOSIT_STATUS_{}", status.to_ascii_uppercase());
+    DepositStatus::from_str_name(&status_str).ok_or_else(|| {
+        format!(
+            "invalid deposit status {}, expected e.g. queued, included or executed",
+            status
+        )
+    })
+}
@@ -0,0 +1,66 @@
+use crate::{SecretString, SpendJobStatus};
+use clap::{Args, Subcommand};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Args)]
+pub struct JobsCommand {
+    #[command(subcommand)]
+    pub commands: JobsCommands,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum JobsCommands {
+    #[command(about = "run the loop executing the scheduled spends once they are due")]
+    Run(JobsRunCommand),
+    #[command(about = "list the scheduled spends")]
+    List(JobsListCommand),
+    #[command(about = "cancel a scheduled spend before it is executed")]
+    Cancel(JobsCancelCommand),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct JobsRunCommand {
+    #[arg(
+        long,
+        help = "password for the current wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
+    #[arg(long, help = "private key for signing the transactions")]
+    pub private_key: Option<SecretString>,
+    #[arg(
+        long,
+        conflicts_with = "private_key",
+        requires = "signer_provider",
+        help = "external program for signing the transactions"
+    )]
+    pub signer_command: Option<String>,
+    #[arg(long, help = "provider for signing and sending the transactions")]
+    pub signer_provider: Option<String>,
+    #[arg(long, help = "timeout in milliseconds for the signer command")]
+    pub signer_timeout_ms: Option<u64>,
+    #[arg(
+        long,
+        default_value = "30s",
+        value_parser = humantime::parse_duration,
+        help = "how long to sleep between checking for due spends"
+    )]
+    pub interval: Duration,
+    #[arg(long, help = "execute the spends due now and exit")]
+    pub once: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct JobsListCommand {
+    #[arg(
+        long,
+        value_enum,
+        help = "only list the jobs with the given status(es)"
+    )]
+    pub status: Option<Vec<SpendJobStatus>>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct JobsCancelCommand {
+    #[arg(long, help = "id of the job to cancel")]
+    pub id: u64,
+}
@@ -1,18 +1,32 @@
 mod account;
+mod agent;
+mod audit;
+mod config;
 mod deposit;
+mod jobs;
+mod offline;
 mod scanner;
 mod spend;
 mod synchronizer;
+mod transaction;
 mod wallet;
 
 pub use account::*;
+pub use agent::*;
+pub use audit::*;
+pub use config::*;
 pub use deposit::*;
+pub use jobs::*;
+pub use offline::*;
 pub use scanner::*;
 pub use spend::*;
 pub use synchronizer::*;
+pub use transaction::*;
 pub use wallet::*;
 
+use crate::{default_agent_socket_path, SecretString};
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Parser)]
 #[command(version, about)]
@@ -41,6 +55,8 @@ pub struct MystikoCliArgs {
     pub in_memory: bool,
     #[arg(long, help = "output compact json string")]
     pub compact_json: bool,
+    #[arg(long, help = "path to the unix socket of the session agent")]
+    pub agent_socket: Option<String>,
     #[command(subcommand)]
     pub commands: MystikoCommands,
 }
@@ -57,8 +73,136 @@ pub enum MystikoCommands {
     Deposit(DepositCommand),
     #[command(about = "spend command for managing spends")]
     Spend(SpendCommand),
+    #[command(about = "jobs command for running and managing scheduled spends")]
+    Jobs(JobsCommand),
     #[command(about = "scanner command for scanning the private assets")]
     Scanner(ScannerCommand),
     #[command(about = "synchronizer command for synchronizing data")]
     Synchronizer(SynchronizerCommand),
+    #[command(about = "sign prepared transactions offline")]
+    Sign(SignCommand),
+    #[command(about = "broadcast signed transactions")]
+    Broadcast(BroadcastCommand),
+    #[command(about = "reference external signer answering one request from stdin")]
+    ReferenceSigner(ReferenceSignerCommand),
+    #[command(about = "config command for checking custom config files")]
+    Config(ConfigCommand),
+    #[command(about = "run the session agent holding the unlocked wallet password")]
+    Agent,
+    #[command(about = "unlock the wallet in the session agent")]
+    Unlock(UnlockCommand),
+    #[command(about = "lock the wallet in the session agent")]
+    Lock,
+    #[command(about = "audit command for reviewing sensitive operations")]
+    Audit(AuditCommand),
+}
+
+impl MystikoCliArgs {
+    pub fn agent_socket_path(&self) -> PathBuf {
+        self.agent_socket
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(default_agent_socket_path)
+    }
+}
+
+impl MystikoCommands {
+    pub fn wallet_password_mut(&mut self) -> Option<&mut Option<SecretString>> {
+        match self {
+            MystikoCommands::Account(AccountCommand { commands }) => match commands {
+                AccountCommands::Create(args) => Some(&mut args.password),
+                AccountCommands::Derive(args) => Some(&mut args.password),
+                AccountCommands::Import(args) => Some(&mut args.password),
+                AccountCommands::ExportSecretKey(args) => Some(&mut args.password),
+                AccountCommands::Export(args) => Some(&mut args.password),
+                AccountCommands::Update(args) => Some(&mut args.password),
+                _ => None,
+            },
+            MystikoCommands::Scanner(ScannerCommand { commands }) => match commands {
+                ScannerCommands::Sync(args) => Some(&mut args.password),
+                ScannerCommands::Scan(args) => Some(&mut args.password),
+                ScannerCommands::Import(args) => Some(&mut args.password),
+                _ => None,
+            },
+            MystikoCommands::Jobs(JobsCommand {
+                commands: JobsCommands::Run(args),
+            }) => Some(&mut args.password),
+            MystikoCommands::Spend(SpendCommand { commands }) => match commands {
+                SpendCommands::Create(args) => Some(&mut args.password),
+                SpendCommands::Prepare(args) => Some(&mut args.password),
+                SpendCommands::Batch(args) => Some(&mut args.password),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn optional_wallet_password_mut(&mut self) -> Option<&mut Option<SecretString>> {
+        match self {
+            MystikoCommands::Deposit(DepositCommand {
+                commands: DepositCommands::Wait(args),
+            }) => Some(&mut args.password),
+            MystikoCommands::Spend(SpendCommand {
+                commands: SpendCommands::Wait(args),
+            }) => Some(&mut args.password),
+            _ => None,
+        }
+    }
+
+    pub fn replacements_path_mut(&mut self) -> Option<&mut Option<PathBuf>> {
+        match self {
+            MystikoCommands::Deposit(DepositCommand { commands }) => match commands {
+                DepositCommands::SpeedUp(args) => Some(&mut args.replacements_path),
+                DepositCommands::Cancel(args) => Some(&mut args.replacements_path),
+                DepositCommands::Recover(args) => Some(&mut args.replacements_path),
+                _ => None,
+            },
+            MystikoCommands::Spend(SpendCommand { commands }) => match commands {
+                SpendCommands::SpeedUp(args) => Some(&mut args.replacements_path),
+                SpendCommands::Cancel(args) => Some(&mut args.replacements_path),
+                SpendCommands::Recover(args) => Some(&mut args.replacements_path),
+                SpendCommands::Batch(args) => Some(&mut args.replacements_path),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn jobs_path_mut(&mut self) -> Option<&mut Option<PathBuf>> {
+        match self {
+            MystikoCommands::Spend(SpendCommand {
+                commands: SpendCommands::Schedule(args),
+            }) => Some(&mut args.jobs_path),
+            _ => None,
+        }
+    }
+
+    pub fn is_preview(&self) -> bool {
+        matches!(
+            self,
+            MystikoCommands::Wallet(WalletCommand {
+                commands: WalletCommands::Import(WalletImportCommand { preview: true, .. })
+            })
+        )
+    }
+
+    pub fn is_password_update(&self) -> bool {
+        matches!(
+            self,
+            MystikoCommands::Wallet(WalletCommand {
+                commands: WalletCommands::UpdatePassword(_)
+            })
+        )
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        matches!(
+            self,
+            MystikoCommands::Deposit(DepositCommand {
+                commands: DepositCommands::Create(DepositCreateCommand { dry_run: true, .. })
+            }) | MystikoCommands::Spend(SpendCommand {
+                commands: SpendCommands::Create(SpendCreateCommand { dry_run: true, .. })
+            })
+        )
+    }
 }
@@ -0,0 +1,35 @@
+use crate::SecretString;
+use clap::Args;
+
+#[derive(Debug, Clone, Args)]
+pub struct SignCommand {
+    #[arg(help = "path of the prepared transactions file")]
+    pub file: String,
+    #[arg(long, help = "private key for signing the transactions")]
+    pub private_key: SecretString,
+    #[arg(
+        long,
+        help = "path of the signed transactions file, defaults to the input file"
+    )]
+    pub out: Option<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct BroadcastCommand {
+    #[arg(help = "path of the signed transactions file")]
+    pub file: String,
+    #[arg(long, help = "provider for broadcasting the transactions")]
+    pub provider: String,
+    #[arg(long, help = "skip the confirmation prompt")]
+    pub yes: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ReferenceSignerCommand {
+    #[arg(
+        long,
+        default_value = "MYSTIKO_SIGNER_PRIVATE_KEY",
+        help = "environment variable holding the private key of the reference signer"
+    )]
+    pub private_key_env: String,
+}
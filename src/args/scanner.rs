@@ -1,4 +1,5 @@
 use crate::args::parse_bridge_types;
+use crate::{expose_wallet_password, MystikoCliError, SecretString};
 use clap::{Args, Subcommand};
 use mystiko_protos::core::scanner::v1::{
     AssetChainImportOptions, AssetImportOptions, AssetsOptions, BalanceOptions,
@@ -29,8 +30,11 @@ pub enum ScannerCommands {
 
 #[derive(Debug, Clone, Args)]
 pub struct ScannerSyncCommand {
-    #[arg(long, help = "password of the current wallet")]
-    pub password: String,
+    #[arg(
+        long,
+        help = "password of the current wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
     #[arg(
         long,
         default_value_t = 1,
@@ -41,8 +45,11 @@ pub struct ScannerSyncCommand {
 
 #[derive(Debug, Clone, Args)]
 pub struct ScannerScanCommand {
-    #[arg(long, help = "password of the current wallet")]
-    pub password: String,
+    #[arg(
+        long,
+        help = "password of the current wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
     #[arg(
         long,
         default_value_t = 10000,
@@ -65,12 +72,17 @@ pub struct ScannerResetCommand {
     pub to_id: Option<String>,
     #[arg(long, help = "the shielded address(es) to be reset")]
     pub shielded_address: Option<Vec<String>>,
+    #[arg(long, help = "skip the confirmation prompt")]
+    pub yes: bool,
 }
 
 #[derive(Debug, Clone, Args)]
 pub struct ScannerImportCommand {
-    #[arg(long, help = "password of the current wallet")]
-    pub password: String,
+    #[arg(
+        long,
+        help = "password of the current wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
     #[arg(long, help = "the chain id to be imported")]
     pub chain_id: u64,
     #[arg(long, help = "the transaction hash(es) to be imported")]
@@ -101,23 +113,27 @@ pub struct ScannerAssetsCommand {
     pub shielded_address: Option<Vec<String>>,
 }
 
-impl From<ScannerSyncCommand> for ScannerSyncOptions {
-    fn from(args: ScannerSyncCommand) -> Self {
-        ScannerSyncOptions::builder()
-            .wallet_password(args.password)
+impl TryFrom<ScannerSyncCommand> for ScannerSyncOptions {
+    type Error = MystikoCliError;
+
+    fn try_from(args: ScannerSyncCommand) -> Result<Self, Self::Error> {
+        Ok(ScannerSyncOptions::builder()
+            .wallet_password(expose_wallet_password(&args.password)?)
             .concurrency(args.concurrency)
-            .build()
+            .build())
     }
 }
 
-impl From<ScannerScanCommand> for ScannerScanOptions {
-    fn from(args: ScannerScanCommand) -> Self {
-        ScannerScanOptions::builder()
-            .wallet_password(args.password)
+impl TryFrom<ScannerScanCommand> for ScannerScanOptions {
+    type Error = MystikoCliError;
+
+    fn try_from(args: ScannerScanCommand) -> Result<Self, Self::Error> {
+        Ok(ScannerScanOptions::builder()
+            .wallet_password(expose_wallet_password(&args.password)?)
             .batch_size(args.batch_size)
             .concurrency(args.concurrency)
             .shielded_addresses(args.shielded_address.unwrap_or_default())
-            .build()
+            .build())
     }
 }
 
@@ -130,15 +146,17 @@ impl From<ScannerResetCommand> for ScannerResetOptions {
     }
 }
 
-impl From<ScannerImportCommand> for AssetImportOptions {
-    fn from(args: ScannerImportCommand) -> Self {
-        AssetImportOptions::builder()
-            .wallet_password(args.password)
+impl TryFrom<ScannerImportCommand> for AssetImportOptions {
+    type Error = MystikoCliError;
+
+    fn try_from(args: ScannerImportCommand) -> Result<Self, Self::Error> {
+        Ok(AssetImportOptions::builder()
+            .wallet_password(expose_wallet_password(&args.password)?)
             .chains([AssetChainImportOptions::builder()
                 .chain_id(args.chain_id)
                 .tx_hashes(args.tx_hashes)
                 .build()])
-            .build()
+            .build())
     }
 }
 
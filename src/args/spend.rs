@@ -1,7 +1,12 @@
 use crate::args::parse_bridge_type;
-use clap::{Args, Subcommand};
+use crate::{expose_wallet_password, MystikoCliError, SecretString, TransactionReplaceCommand};
+use clap::{ArgGroup, Args, Subcommand};
 use mystiko_protos::core::handler::v1::{CreateSpendOptions, QuoteSpendOptions};
 use mystiko_protos::core::v1::{SpendStatus, SpendType};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Clone, Args)]
 pub struct SpendCommand {
@@ -15,6 +20,20 @@ pub enum SpendCommands {
     Quote(SpendQuoteCommand),
     #[command(about = "create a new spend")]
     Create(SpendCreateCommand),
+    #[command(about = "create and send spends listed in a CSV or JSON manifest")]
+    Batch(SpendBatchCommand),
+    #[command(about = "schedule a spend to be executed later by jobs run")]
+    Schedule(SpendScheduleCommand),
+    #[command(about = "prepare an unsigned spend transaction for offline signing")]
+    Prepare(SpendPrepareCommand),
+    #[command(about = "replace the pending spend transaction with higher fees")]
+    SpeedUp(TransactionReplaceCommand),
+    #[command(about = "cancel the pending spend transaction with a zero-value self-transfer")]
+    Cancel(TransactionReplaceCommand),
+    #[command(about = "check spends left pending and settle their status")]
+    Recover(SpendRecoverCommand),
+    #[command(about = "wait until the spend reaches the given status")]
+    Wait(SpendWaitCommand),
     #[command(about = "list all spends")]
     List(SpendListCommand),
 }
@@ -57,14 +76,29 @@ pub struct SpendCreateCommand {
     pub version: Option<u32>,
     #[arg(long, help = "recipient address for the spend")]
     pub recipient: String,
-    #[arg(long, help = "password for the current wallet")]
-    pub password: String,
+    #[arg(
+        long,
+        help = "password for the current wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
     #[arg(long, help = "private key for signing the transaction")]
-    pub private_key: Option<String>,
+    pub private_key: Option<SecretString>,
+    #[arg(
+        long,
+        conflicts_with = "private_key",
+        requires = "signer_provider",
+        help = "external program for signing the transaction"
+    )]
+    pub signer_command: Option<String>,
     #[arg(long, help = "provider for signing and sending the transaction")]
     pub signer_provider: Option<String>,
-    #[arg(long, help = "amount of assets to spend")]
-    pub amount: f64,
+    #[arg(long, help = "timeout in milliseconds for the signer command")]
+    pub signer_timeout_ms: Option<u64>,
+    #[arg(
+        long,
+        help = "amount of assets to spend, or max to spend the whole balance net of fees"
+    )]
+    pub amount: SpendAmount,
     #[arg(long, help = "rollup fee for the spend output commitments")]
     pub rollup_fee: Option<f64>,
     #[arg(long, help = "name of relayer to relaying the spend transaction")]
@@ -95,6 +129,218 @@ pub struct SpendCreateCommand {
         help = "timeout in milliseconds for polling a relayer for the spend transaction"
     )]
     pub relayer_wait_timeout_ms: Option<u64>,
+    #[arg(long, help = "skip the confirmation prompt")]
+    pub yes: bool,
+    #[arg(
+        long,
+        help = "build and estimate the spend transaction(s) without broadcasting them"
+    )]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpendAmount {
+    Max,
+    Exact(f64),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct SpendPrepareCommand {
+    #[arg(long, default_value_t = 1, help = "chain_id for the spend")]
+    pub chain_id: u64,
+    #[arg(long, default_value = "ETH", help = "asset_symbol for the spend")]
+    pub asset_symbol: String,
+    #[arg(long, help = "type of the spend, default value is withdraw")]
+    pub spend_type: Option<String>,
+    #[arg(long, help = "bridge_type for spending cross-chain assets")]
+    pub bridge_type: Option<String>,
+    #[arg(long, help = "version number of the assets pool")]
+    pub version: Option<u32>,
+    #[arg(long, help = "recipient address for the spend")]
+    pub recipient: String,
+    #[arg(
+        long,
+        help = "password for the current wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
+    #[arg(long, help = "amount of assets to spend")]
+    pub amount: f64,
+    #[arg(long, help = "rollup fee for the spend output commitments")]
+    pub rollup_fee: Option<f64>,
+    #[arg(long, help = "address of the offline signing key")]
+    pub from: String,
+    #[arg(
+        long,
+        help = "nonce of the transaction, queried from the provider if not given"
+    )]
+    pub nonce: Option<u64>,
+    #[arg(long, help = "provider for querying the nonce of the signing address")]
+    pub signer_provider: Option<String>,
+    #[arg(long, help = "timeout in milliseconds for the querying provider")]
+    pub query_timeout_ms: Option<u64>,
+    #[arg(long, help = "path of the unsigned transactions file")]
+    pub out: String,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct SpendBatchCommand {
+    #[arg(
+        long,
+        help = "path of the CSV or JSON manifest with columns chain, asset, amount, \
+        recipient, type, bridge, version, rollup_fee and key"
+    )]
+    pub manifest: String,
+    #[arg(
+        long,
+        help = "path of the results file for resuming, defaults to <manifest>.results.json"
+    )]
+    pub results: Option<String>,
+    #[arg(
+        long,
+        help = "password for the current wallet, read from the session agent if not given"
+    )]
+    pub password: Option<SecretString>,
+    #[arg(long, help = "private key for signing the transactions")]
+    pub private_key: Option<SecretString>,
+    #[arg(
+        long,
+        conflicts_with = "private_key",
+        requires = "signer_provider",
+        help = "external program for signing the transactions"
+    )]
+    pub signer_command: Option<String>,
+    #[arg(long, help = "provider for signing and sending the transactions")]
+    pub signer_provider: Option<String>,
+    #[arg(long, help = "timeout in milliseconds for the signer command")]
+    pub signer_timeout_ms: Option<u64>,
+    #[arg(long, help = "name of relayer to relaying the spend transactions")]
+    pub relayer: Option<String>,
+    #[arg(
+        long,
+        default_value_t = 1,
+        requires = "relayer",
+        help = "number of spends sent to the relayer in parallel"
+    )]
+    pub concurrency: usize,
+    #[arg(long, help = "timeout in milliseconds for the querying provider")]
+    pub query_timeout_ms: Option<u64>,
+    #[arg(
+        long,
+        help = "number of confirmations to wait for the spend transactions"
+    )]
+    pub confirmations: Option<u64>,
+    #[arg(
+        long,
+        requires = "signer_provider",
+        help = "recreate failed rows that already recorded a spend after checking it on chain"
+    )]
+    pub retry_failed: bool,
+    #[arg(long, help = "skip the confirmation prompt")]
+    pub yes: bool,
+    #[arg(skip)]
+    pub replacements_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct SpendRecoverCommand {
+    #[arg(long, help = "chain_id of the spends to recover")]
+    pub chain_id: u64,
+    #[arg(long, help = "provider for querying the recorded transactions")]
+    pub provider: String,
+    #[arg(long, help = "recover only the spend(s) with the given id(s)")]
+    pub id: Option<Vec<String>>,
+    #[arg(skip)]
+    pub replacements_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+#[command(group(
+    ArgGroup::new("schedule")
+        .required(true)
+        .args(["at", "delay"]),
+))]
+pub struct SpendScheduleCommand {
+    #[arg(long, default_value_t = 1, help = "chain_id for the spend")]
+    pub chain_id: u64,
+    #[arg(long, default_value = "ETH", help = "asset_symbol for the spend")]
+    pub asset_symbol: String,
+    #[arg(long, help = "type of the spend, default value is withdraw")]
+    pub spend_type: Option<String>,
+    #[arg(long, help = "bridge_type for spending cross-chain assets")]
+    pub bridge_type: Option<String>,
+    #[arg(long, help = "version number of the assets pool")]
+    pub version: Option<u32>,
+    #[arg(long, help = "recipient address for the spend")]
+    pub recipient: String,
+    #[arg(
+        long,
+        help = "amount of assets to spend, or max to spend the whole balance net of fees"
+    )]
+    pub amount: SpendAmount,
+    #[arg(long, help = "rollup fee for the spend output commitments")]
+    pub rollup_fee: Option<f64>,
+    #[arg(long, help = "name of relayer to relaying the spend transaction")]
+    pub relayer: Option<String>,
+    #[arg(long, help = "timeout in milliseconds for the querying provider")]
+    pub query_timeout_ms: Option<u64>,
+    #[arg(
+        long,
+        help = "number of confirmations to wait for the spend transaction"
+    )]
+    pub confirmations: Option<u64>,
+    #[arg(
+        long,
+        value_parser = humantime::parse_rfc3339_weak,
+        help = "UTC time to execute the spend at, e.g. 2024-01-01T12:00:00"
+    )]
+    pub at: Option<SystemTime>,
+    #[arg(
+        long,
+        value_parser = humantime::parse_duration,
+        help = "execute the spend after this delay, e.g. 6h"
+    )]
+    pub delay: Option<Duration>,
+    #[arg(
+        long,
+        requires = "delay",
+        value_parser = humantime::parse_duration,
+        help = "pick a random delay between --delay and this duration, e.g. 48h"
+    )]
+    pub delay_max: Option<Duration>,
+    #[arg(skip)]
+    pub jobs_path: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct SpendWaitCommand {
+    #[arg(long, help = "id of the spend to wait for")]
+    pub id: String,
+    #[arg(
+        long,
+        default_value = "succeeded",
+        value_parser = parse_spend_status_value,
+        help = "status to wait for, e.g. succeeded"
+    )]
+    pub until: SpendStatus,
+    #[arg(
+        long,
+        default_value = "2h",
+        value_parser = humantime::parse_duration,
+        help = "how long to wait before giving up, e.g. 30m or 2h"
+    )]
+    pub timeout: Duration,
+    #[arg(
+        long,
+        default_value = "30s",
+        value_parser = humantime::parse_duration,
+        help = "how long to sleep between polling rounds"
+    )]
+    pub interval: Duration,
+    #[arg(
+        long,
+        help = "password of the current wallet for syncing the scanner, read from the session agent if unlocked, scanner sync is skipped without it"
+    )]
+    pub password: Option<SecretString>,
 }
 
 #[derive(Debug, Clone, Args)]
@@ -125,6 +371,40 @@ pub struct SpendListCommand {
     pub page: u64,
 }
 
+impl SpendAmount {
+    pub fn exact(&self) -> Option<f64> {
+        match self {
+            SpendAmount::Max => None,
+            SpendAmount::Exact(amount) => Some(*amount),
+        }
+    }
+}
+
+impl FromStr for SpendAmount {
+    type Err = String;
+
+    fn from_str(amount: &str) -> Result<Self, Self::Err> {
+        if amount.eq_ignore_ascii_case("max") {
+            return Ok(SpendAmount::Max);
+        }
+        amount
+            .parse::<f64>()
+            .ok()
+            .filter(|amount| amount.is_finite())
+            .map(SpendAmount::Exact)
+            .ok_or_else(|| format!("invalid amount {}, expected a number or max", amount))
+    }
+}
+
+impl fmt::Display for SpendAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpendAmount::Max => write!(f, "max"),
+            SpendAmount::Exact(amount) => write!(f, "{}", amount),
+        }
+    }
+}
+
 impl From<SpendQuoteCommand> for QuoteSpendOptions {
     fn from(args: SpendQuoteCommand) -> Self {
         QuoteSpendOptions::builder()
@@ -140,21 +420,57 @@ impl From<SpendQuoteCommand> for QuoteSpendOptions {
     }
 }
 
-impl From<SpendCreateCommand> for CreateSpendOptions {
+impl From<SpendCreateCommand> for QuoteSpendOptions {
     fn from(args: SpendCreateCommand) -> Self {
-        CreateSpendOptions::builder()
+        QuoteSpendOptions::builder()
+            .chain_id(args.chain_id)
+            .asset_symbol(args.asset_symbol)
+            .spend_type(parse_spend_type(args.spend_type))
+            .bridge_type(parse_bridge_type(args.bridge_type))
+            .version(args.version)
+            .amount(args.amount.exact())
+            .query_timeout_ms(args.query_timeout_ms)
+            .use_relayer(args.relayer.is_some())
+            .build()
+    }
+}
+
+impl TryFrom<SpendCreateCommand> for CreateSpendOptions {
+    type Error = MystikoCliError;
+
+    fn try_from(args: SpendCreateCommand) -> Result<Self, Self::Error> {
+        Ok(CreateSpendOptions::builder()
             .chain_id(args.chain_id)
             .asset_symbol(args.asset_symbol)
             .spend_type(parse_spend_type(args.spend_type))
             .bridge_type(parse_bridge_type(args.bridge_type))
             .version(args.version)
             .recipient(args.recipient)
-            .wallet_password(args.password)
-            .amount(args.amount)
+            .wallet_password(expose_wallet_password(&args.password)?)
+            .amount(args.amount.exact().unwrap_or_default())
             .rollup_fee_amount(args.rollup_fee)
             .gas_relayer(args.relayer)
             .query_timeout_ms(args.query_timeout_ms)
-            .build()
+            .build())
+    }
+}
+
+impl TryFrom<SpendPrepareCommand> for CreateSpendOptions {
+    type Error = MystikoCliError;
+
+    fn try_from(args: SpendPrepareCommand) -> Result<Self, Self::Error> {
+        Ok(CreateSpendOptions::builder()
+            .chain_id(args.chain_id)
+            .asset_symbol(args.asset_symbol)
+            .spend_type(parse_spend_type(args.spend_type))
+            .bridge_type(parse_bridge_type(args.bridge_type))
+            .version(args.version)
+            .recipient(args.recipient)
+            .wallet_password(expose_wallet_password(&args.password)?)
+            .amount(args.amount)
+            .rollup_fee_amount(args.rollup_fee)
+            .query_timeout_ms(args.query_timeout_ms)
+            .build())
     }
 }
 
@@ -185,3 +501,9 @@ pub(crate) fn parse_spend_status(status: &str) -> Option<i32> {
     let status = format!("SPEND_STATUS_{}", status.to_uppercase());
     SpendStatus::from_str_name(&status).map(|status| status as i32)
 }
+
+pub(crate) fn parse_spend_status_value(status: &str) -> Result<SpendStatus, String> {
+    let status_str = format!("SPEND_STATUS_{}", status.to_uppercase());
+    SpendStatus::from_str_name(&status_str)
+        .ok_or_else(|| format!("invalid spend status {}, expected e.g. succeeded", status))
+}
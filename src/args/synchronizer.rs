@@ -107,6 +107,8 @@ pub struct SynchronizerResetCommand {
         if not specified, reset to contract deploy block"
     )]
     pub to: Option<u64>,
+    #[arg(long, help = "skip the confirmation prompt")]
+    pub yes: bool,
 }
 
 impl From<SynchronizerSyncCommand> for SynchronizerSyncOptions {
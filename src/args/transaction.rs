@@ -0,0 +1,38 @@
+use crate::SecretString;
+use clap::Args;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Args)]
+pub struct TransactionReplaceCommand {
+    #[arg(
+        long,
+        help = "id of the document whose pending transaction is replaced"
+    )]
+    pub id: String,
+    #[arg(
+        long,
+        required_unless_present = "signer_command",
+        help = "private key for signing the replacement transaction"
+    )]
+    pub private_key: Option<SecretString>,
+    #[arg(
+        long,
+        conflicts_with = "private_key",
+        help = "external program for signing the replacement transaction"
+    )]
+    pub signer_command: Option<String>,
+    #[arg(long, help = "timeout in milliseconds for the signer command")]
+    pub signer_timeout_ms: Option<u64>,
+    #[arg(long, help = "provider for querying and broadcasting the transactions")]
+    pub provider: String,
+    #[arg(
+        long,
+        default_value_t = 12,
+        help = "percentage to bump the fees of the pending transaction by"
+    )]
+    pub fee_bump_percent: u64,
+    #[arg(long, help = "skip the confirmation prompt")]
+    pub yes: bool,
+    #[arg(skip)]
+    pub replacements_path: Option<PathBuf>,
+}
@@ -1,4 +1,5 @@
-use clap::{Args, Subcommand};
+use crate::SecretString;
+use clap::{Args, Subcommand, ValueEnum};
 
 #[derive(Debug, Clone, Args)]
 pub struct WalletCommand {
@@ -16,32 +17,121 @@ pub enum WalletCommands {
     ExportMnemonic(WalletExportMnemonicPhraseCommand),
     #[command(about = "update the password for the current wallet")]
     UpdatePassword(WalletUpdatePasswordCommand),
+    #[command(about = "show the status and health of the current wallet")]
+    Info(WalletInfoCommand),
+    #[command(about = "verify the password for the current wallet")]
+    VerifyPassword(WalletVerifyPasswordCommand),
+    #[command(about = "back up the current wallet with its accounts and history")]
+    Backup(WalletBackupCommand),
+    #[command(about = "restore a wallet from a backup file")]
+    Restore(WalletRestoreCommand),
 }
 
 #[derive(Debug, Clone, Args)]
 pub struct WalletCreateCommand {
     #[arg(long, help = "password for wallet")]
-    pub password: String,
+    pub password: SecretString,
 }
 
 #[derive(Debug, Clone, Args)]
 pub struct WalletImportCommand {
     #[arg(long, help = "password for wallet")]
-    pub password: String,
-    #[arg(long, help = "mnemonic phrases for wallet")]
-    pub mnemonic: String,
+    pub password: SecretString,
+    #[arg(
+        long,
+        required_unless_present = "shares",
+        conflicts_with = "shares",
+        help = "mnemonic phrases for wallet"
+    )]
+    pub mnemonic: Option<SecretString>,
+    #[arg(
+        long,
+        num_args = 1..,
+        help = "files of the mnemonic shares for reconstructing the mnemonic phrases"
+    )]
+    pub shares: Option<Vec<String>>,
+    #[arg(
+        long,
+        value_enum,
+        help = "type of the mnemonic phrases, guessed from the word count if not set"
+    )]
+    pub mnemonic_type: Option<WalletMnemonicType>,
+    #[arg(
+        long,
+        help = "show the first derived shielded address without writing anything"
+    )]
+    pub preview: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WalletMnemonicType {
+    Web,
+    Rust,
 }
 
 #[derive(Debug, Clone, Args)]
 pub struct WalletExportMnemonicPhraseCommand {
     #[arg(long, help = "password for wallet")]
-    pub password: String,
+    pub password: SecretString,
+    #[arg(
+        long,
+        requires = "threshold",
+        requires = "out_dir",
+        value_parser = clap::value_parser!(u8).range(2..),
+        help = "split the mnemonic phrases into this number of shares"
+    )]
+    pub shares: Option<u8>,
+    #[arg(
+        long,
+        requires = "shares",
+        value_parser = clap::value_parser!(u8).range(2..),
+        help = "number of shares required for reconstructing the mnemonic phrases"
+    )]
+    pub threshold: Option<u8>,
+    #[arg(
+        long,
+        requires = "shares",
+        help = "directory for writing each share into its own file"
+    )]
+    pub out_dir: Option<String>,
 }
 
 #[derive(Debug, Clone, Args)]
 pub struct WalletUpdatePasswordCommand {
     #[arg(long, help = "old password for wallet")]
-    pub old: String,
+    pub old: SecretString,
     #[arg(long, help = "new password for wallet")]
-    pub new: String,
+    pub new: SecretString,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct WalletInfoCommand {
+    #[arg(long, help = "include the synchronizer status of contracts")]
+    pub with_contracts: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct WalletVerifyPasswordCommand {
+    #[arg(long, help = "password for wallet")]
+    pub password: SecretString,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct WalletBackupCommand {
+    #[arg(long, help = "password for wallet")]
+    pub password: SecretString,
+    #[arg(long, help = "passphrase for encrypting the backup file")]
+    pub backup_passphrase: SecretString,
+    #[arg(long, help = "include the scanner progress of accounts")]
+    pub with_scanner_progress: bool,
+    #[arg(long, help = "path to the backup file")]
+    pub out: String,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct WalletRestoreCommand {
+    #[arg(help = "path to the backup file")]
+    pub file: String,
+    #[arg(long, help = "passphrase for decrypting the backup file")]
+    pub backup_passphrase: SecretString,
 }
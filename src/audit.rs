@@ -0,0 +1,371 @@
+use crate::{
+    connect_sqlite, connect_sqlite_memory, AccountCommand, AccountCommands, DepositCommand,
+    DepositCommands, JobsCommand, JobsCommands, MystikoCliError, MystikoCommands, ScannerCommand,
+    ScannerCommands, SpendCommand, SpendCommands, SynchronizerCommand, SynchronizerCommands,
+    WalletCommand, WalletCommands,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const AUDIT_GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+pub const AUDIT_RESULT_STARTED: &str = "started";
+pub const AUDIT_RESULT_SUCCESS: &str = "success";
+pub const AUDIT_RESULT_FAILURE: &str = "failure";
+pub const AUDIT_APPEND_ATTEMPTS: usize = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub timestamp: u64,
+    pub user: String,
+    pub command: String,
+    pub targets: Vec<String>,
+    pub result: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub previous_hash: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditVerifyReport {
+    pub entries: usize,
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broken_at: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    pool: SqlitePool,
+}
+
+impl AuditEntry {
+    pub fn compute_hash(&self) -> Result<String, MystikoCliError> {
+        let content = serde_json::to_string(&(
+            self.sequence,
+            self.timestamp,
+            &self.user,
+            &self.command,
+            &self.targets,
+            &self.result,
+            &self.error,
+            &self.previous_hash,
+        ))?;
+        Ok(hex::encode(Sha256::digest(content.as_bytes())))
+    }
+
+    fn from_row(row: &SqliteRow) -> Result<Self, MystikoCliError> {
+        Ok(AuditEntry {
+            sequence: row.try_get::<i64, _>("sequence")? as u64,
+            timestamp: row.try_get::<i64, _>("timestamp")? as u64,
+            user: row.try_get("user")?,
+            command: row.try_get("command")?,
+            targets: serde_json::from_str(row.try_get("targets")?)?,
+            result: row.try_get("result")?,
+            error: row.try_get("error")?,
+            previous_hash: row.try_get("previous_hash")?,
+            hash: row.try_get("hash")?,
+        })
+    }
+}
+
+impl AuditLog {
+    pub async fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, MystikoCliError> {
+        AuditLog::from_pool(connect_sqlite(path).await?).await
+    }
+
+    pub async fn from_memory() -> Result<Self, MystikoCliError> {
+        AuditLog::from_pool(connect_sqlite_memory().await?).await
+    }
+
+    pub async fn from_pool(pool: SqlitePool) -> Result<Self, MystikoCliError> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS audit_entry (\
+             sequence INTEGER PRIMARY KEY NOT NULL, \
+             timestamp INTEGER NOT NULL, \
+             user TEXT NOT NULL, \
+             command TEXT NOT NULL, \
+             targets TEXT NOT NULL, \
+             result TEXT NOT NULL, \
+             error TEXT, \
+             previous_hash TEXT NOT NULL, \
+             hash TEXT NOT NULL)",
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(
+            "CREATE TRIGGER IF NOT EXISTS audit_entry_no_update BEFORE UPDATE ON audit_entry \
+             BEGIN SELECT RAISE(ABORT, 'audit entries are append-only'); END",
+        )
+        .execute(&pool)
+        .await?;
+        sqlx::query(
+            "CREATE TRIGGER IF NOT EXISTS audit_entry_no_delete BEFORE DELETE ON audit_entry \
+             BEGIN SELECT RAISE(ABORT, 'audit entries are append-only'); END",
+        )
+        .execute(&pool)
+        .await?;
+        Ok(AuditLog { pool })
+    }
+
+    pub async fn entries(&self) -> Result<Vec<AuditEntry>, MystikoCliError> {
+        sqlx::query("SELECT * FROM audit_entry ORDER BY sequence")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(AuditEntry::from_row)
+            .collect()
+    }
+
+    pub async fn append_started(
+        &self,
+        command: &str,
+        targets: Vec<String>,
+    ) -> Result<AuditEntry, MystikoCliError> {
+        self.insert(command, targets, AUDIT_RESULT_STARTED, None)
+            .await
+    }
+
+    pub async fn append(
+        &self,
+        command: &str,
+        targets: Vec<String>,
+        error: Option<String>,
+    ) -> Result<AuditEntry, MystikoCliError> {
+        let result = if error.is_none() {
+            AUDIT_RESULT_SUCCESS
+        } else {
+            AUDIT_RESULT_FAILURE
+        };
+        self.insert(command, targets, result, error).await
+    }
+
+    pub async fn verify(&self) -> Result<AuditVerifyReport, MystikoCliError> {
+        let entries = self.entries().await?;
+        let mut previous_hash = AUDIT_GENESIS_HASH.to_string();
+        for (index, entry) in entries.iter().enumerate() {
+            let error = if entry.sequence != index as u64 + 1 {
+                Some(format!("expected sequence {}", index + 1))
+            } else if entry.previous_hash != previous_hash {
+                Some(String::from("previous hash does not match"))
+            } else if entry.compute_hash()? != entry.hash {
+                Some(String::from("entry hash does not match its content"))
+            } else {
+                None
+            };
+            if error.is_some() {
+                return Ok(AuditVerifyReport {
+                    entries: entries.len(),
+                    valid: false,
+                    broken_at: Some(entry.sequence),
+                    error,
+                });
+            }
+            previous_hash = entry.hash.clone();
+        }
+        Ok(AuditVerifyReport {
+            entries: entries.len(),
+            valid: true,
+            broken_at: None,
+            error: None,
+        })
+    }
+
+    async fn insert(
+        &self,
+        command: &str,
+        targets: Vec<String>,
+        result: &str,
+        error: Option<String>,
+    ) -> Result<AuditEntry, MystikoCliError> {
+        let mut attempts = 0;
+        loop {
+            let last = sqlx::query("SELECT * FROM audit_entry ORDER BY sequence DESC LIMIT 1")
+                .fetch_optional(&self.pool)
+                .await?
+                .as_ref()
+                .map(AuditEntry::from_row)
+                .transpose()?;
+            let mut entry = AuditEntry {
+                sequence: last.as_ref().map(|entry| entry.sequence + 1).unwrap_or(1),
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|duration| duration.as_secs())
+                    .unwrap_or_default(),
+                user: current_user(),
+                command: command.to_string(),
+                targets: targets.clone(),
+                result: result.to_string(),
+                error: error.clone(),
+                previous_hash: last
+                    .map(|entry| entry.hash)
+                    .unwrap_or(AUDIT_GENESIS_HASH.to_string()),
+                hash: String::new(),
+            };
+            entry.hash = entry.compute_hash()?;
+            let inserted = sqlx::query(
+                "INSERT INTO audit_entry (sequence, timestamp, user, command, targets, result, \
+                 error, previous_hash, hash) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(entry.sequence as i64)
+            .bind(entry.timestamp as i64)
+            .bind(&entry.user)
+            .bind(&entry.command)
+            .bind(serde_json::to_string(&entry.targets)?)
+            .bind(&entry.result)
+            .bind(&entry.error)
+            .bind(&entry.previous_hash)
+            .bind(&entry.hash)
+            .execute(&self.pool)
+            .await;
+            match inserted {
+                Ok(_) => return Ok(entry),
+                Err(sqlx::Error::Database(err))
+                    if err.is_unique_violation() && attempts < AUDIT_APPEND_ATTEMPTS =>
+                {
+                    attempts += 1;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+pub fn audit_command(commands: &MystikoCommands) -> Option<(&'static str, Vec<String>)> {
+    match commands {
+        MystikoCommands::Wallet(WalletCommand { commands }) => match commands {
+            WalletCommands::ExportMnemonic(args) => Some((
+                "wallet export-mnemonic",
+                args.shares
+                    .map(|shares| vec![format!("shares={}", shares)])
+                    .unwrap_or_default(),
+            )),
+            WalletCommands::UpdatePassword(_) => Some(("wallet update-password", vec![])),
+            WalletCommands::Backup(args) => {
+                Some(("wallet backup", vec![format!("out={}", args.out)]))
+            }
+            _ => None,
+        },
+        MystikoCommands::Account(AccountCommand { commands }) => match commands {
+            AccountCommands::ExportSecretKey(args) => Some((
+                "account export-secret-key",
+                vec![format!("shielded_address={}", args.shielded_address)],
+            )),
+            AccountCommands::Export(args) => Some((
+                "account export",
+                vec![
+                    format!("shielded_address={}", args.shielded_address),
+                    format!("out={}", args.out),
+                ],
+            )),
+            _ => None,
+        },
+        MystikoCommands::Scanner(ScannerCommand {
+            commands: ScannerCommands::Reset(args),
+        }) => {
+            let mut targets = vec![];
+            if let Some(to_id) = &args.to_id {
+                targets.push(format!("to_id={}", to_id));
+            }
+            for shielded_address in args.shielded_address.iter().flatten() {
+                targets.push(format!("shielded_address={}", shielded_address));
+            }
+            Some(("scanner reset", targets))
+        }
+        MystikoCommands::Synchronizer(SynchronizerCommand {
+            commands: SynchronizerCommands::Reset(args),
+        }) => {
+            let mut targets = vec![format!("chain_id={}", args.chain_id)];
+            for contract_address in args.contract_address.iter().flatten() {
+                targets.push(format!("contract_address={}", contract_address));
+            }
+            if let Some(to) = args.to {
+                targets.push(format!("to={}", to));
+            }
+            Some(("synchronizer reset", targets))
+        }
+        MystikoCommands::Deposit(DepositCommand {
+            commands: DepositCommands::Batch(args),
+        }) => Some(("deposit batch", vec![format!("manifest={}", args.manifest)])),
+        MystikoCommands::Spend(SpendCommand {
+            commands: SpendCommands::Batch(args),
+        }) => Some(("spend batch", vec![format!("manifest={}", args.manifest)])),
+        MystikoCommands::Deposit(DepositCommand {
+            commands: DepositCommands::Recover(args),
+        }) => Some((
+            "deposit recover",
+            vec![format!("chain_id={}", args.chain_id)],
+        )),
+        MystikoCommands::Deposit(DepositCommand {
+            commands: DepositCommands::SpeedUp(args),
+        }) => Some(("deposit speed-up", vec![format!("id={}", args.id)])),
+        MystikoCommands::Deposit(DepositCommand {
+            commands: DepositCommands::Cancel(args),
+        }) => Some(("deposit cancel", vec![format!("id={}", args.id)])),
+        MystikoCommands::Spend(SpendCommand {
+            commands: SpendCommands::SpeedUp(args),
+        }) => Some(("spend speed-up", vec![format!("id={}", args.id)])),
+        MystikoCommands::Spend(SpendCommand {
+            commands: SpendCommands::Cancel(args),
+        }) => Some(("spend cancel", vec![format!("id={}", args.id)])),
+        MystikoCommands::Deposit(DepositCommand {
+            commands: DepositCommands::Create(args),
+        }) if !args.dry_run => {
+            let mut fields = vec![
+                format!("chain_id={}", args.chain_id),
+                format!("asset_symbol={}", args.asset_symbol),
+                format!("amount={}", args.amount),
+                format!("shielded_address={}", args.shielded_address),
+            ];
+            if args.split.is_some() {
+                fields.push("split=standard".to_string());
+            }
+            Some(("deposit create", fields))
+        }
+        MystikoCommands::Spend(SpendCommand {
+            commands: SpendCommands::Create(args),
+        }) if !args.dry_run => Some((
+            "spend create",
+            vec![
+                format!("chain_id={}", args.chain_id),
+                format!("asset_symbol={}", args.asset_symbol),
+                format!("amount={}", args.amount),
+                format!("recipient={}", args.recipient),
+            ],
+        )),
+        MystikoCommands::Spend(SpendCommand {
+            commands: SpendCommands::Schedule(args),
+        }) => Some((
+            "spend schedule",
+            vec![
+                format!("chain_id={}", args.chain_id),
+                format!("asset_symbol={}", args.asset_symbol),
+                format!("amount={}", args.amount),
+                format!("recipient={}", args.recipient),
+            ],
+        )),
+        MystikoCommands::Jobs(JobsCommand {
+            commands: JobsCommands::Cancel(args),
+        }) => Some(("jobs cancel", vec![format!("job_id={}", args.id)])),
+        MystikoCommands::Broadcast(args) => {
+            Some(("broadcast", vec![format!("file={}", args.file)]))
+        }
+        _ => None,
+    }
+}
+
+fn current_user() -> String {
+    let uid = unsafe { libc::getuid() };
+    match std::env::var("USER") {
+        Ok(user) if !user.is_empty() => format!("{} (uid {})", user, uid),
+        _ => format!("uid {}", uid),
+    }
+}
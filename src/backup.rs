@@ -0,0 +1,93 @@
+use crate::{replace_private_file, write_private_file, MystikoCliError};
+use anyhow::anyhow;
+use mystiko_crypto::crypto::{decrypt_symmetric, encrypt_symmetric};
+use mystiko_protos::core::document::v1::{Account, Deposit, Spend, Wallet};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+pub const WALLET_BACKUP_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletBackup {
+    pub version: u32,
+    pub checksum: String,
+    pub encrypted_payload: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WalletBackupPayload {
+    pub wallet: Wallet,
+    pub accounts: Vec<Account>,
+    pub deposits: Vec<Deposit>,
+    pub spends: Vec<Spend>,
+    pub scanner_progress: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordRotationJournal {
+    pub wallet: Wallet,
+    pub accounts: Vec<Account>,
+}
+
+impl WalletBackup {
+    pub fn encrypt(
+        payload: &WalletBackupPayload,
+        passphrase: &str,
+    ) -> Result<Self, MystikoCliError> {
+        let encrypted_payload = encrypt_symmetric(passphrase, &serde_json::to_string(payload)?)?;
+        Ok(WalletBackup {
+            version: WALLET_BACKUP_VERSION,
+            checksum: backup_checksum(&encrypted_payload),
+            encrypted_payload,
+        })
+    }
+
+    pub fn decrypt(&self, passphrase: &str) -> Result<WalletBackupPayload, MystikoCliError> {
+        if self.version != WALLET_BACKUP_VERSION {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Unsupported backup version {}",
+                self.version
+            )));
+        }
+        if backup_checksum(&self.encrypted_payload) != self.checksum {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Backup checksum mismatch, the file may be corrupted"
+            )));
+        }
+        let payload = decrypt_symmetric(passphrase, &self.encrypted_payload)?;
+        Ok(serde_json::from_str(&payload)?)
+    }
+
+    pub async fn read<P: AsRef<Path>>(path: P) -> Result<Self, MystikoCliError> {
+        let content = tokio::fs::read_to_string(path).await?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub async fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), MystikoCliError> {
+        write_private_file(path, &serde_json::to_string_pretty(self)?).await
+    }
+}
+
+impl PasswordRotationJournal {
+    pub async fn read<P: AsRef<Path>>(path: P) -> Result<Option<Self>, MystikoCliError> {
+        if !tokio::fs::try_exists(&path).await? {
+            return Ok(None);
+        }
+        let content = tokio::fs::read_to_string(path).await?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    pub async fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), MystikoCliError> {
+        replace_private_file(path, &serde_json::to_string(self)?).await
+    }
+
+    pub async fn remove<P: AsRef<Path>>(path: P) -> Result<(), MystikoCliError> {
+        tokio::fs::remove_file(path).await?;
+        Ok(())
+    }
+}
+
+fn backup_checksum(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
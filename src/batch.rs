@@ -0,0 +1,315 @@
+use crate::{replace_private_file, MystikoCliError, OfflineDocumentType};
+use anyhow::anyhow;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+pub const BATCH_RESULTS_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepositManifestRow {
+    #[serde(alias = "chain")]
+    pub chain_id: u64,
+    #[serde(alias = "asset")]
+    pub asset_symbol: String,
+    pub amount: f64,
+    pub shielded_address: String,
+    #[serde(default, alias = "bridge")]
+    pub bridge_type: Option<String>,
+    #[serde(default, alias = "dst_chain")]
+    pub dst_chain_id: Option<u64>,
+    #[serde(default)]
+    pub rollup_fee: Option<f64>,
+    #[serde(default)]
+    pub bridge_fee: Option<f64>,
+    #[serde(default)]
+    pub executor_fee: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpendManifestRow {
+    #[serde(alias = "chain")]
+    pub chain_id: u64,
+    #[serde(alias = "asset")]
+    pub asset_symbol: String,
+    pub amount: f64,
+    pub recipient: String,
+    #[serde(default, alias = "type")]
+    pub spend_type: Option<String>,
+    #[serde(default, alias = "bridge")]
+    pub bridge_type: Option<String>,
+    #[serde(default)]
+    pub version: Option<u32>,
+    #[serde(default)]
+    pub rollup_fee: Option<f64>,
+    #[serde(default, alias = "idempotency_key")]
+    pub key: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchRowStatus {
+    Pending,
+    Created,
+    Sending,
+    Sent,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRowResult {
+    pub row: usize,
+    pub status: BatchRowStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset_approve_transaction_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResults {
+    pub version: u32,
+    pub document_type: OfflineDocumentType,
+    pub manifest: String,
+    pub rows: Vec<BatchRowResult>,
+}
+
+impl BatchRowResult {
+    pub fn new(row: usize) -> Self {
+        BatchRowResult {
+            row,
+            status: BatchRowStatus::Pending,
+            key: None,
+            document_id: None,
+            nonce: None,
+            asset_approve_transaction_hash: None,
+            transaction_hash: None,
+            error: None,
+        }
+    }
+}
+
+impl BatchResults {
+    pub fn new(document_type: OfflineDocumentType, manifest: &str, rows: usize) -> Self {
+        BatchResults {
+            version: BATCH_RESULTS_VERSION,
+            document_type,
+            manifest: manifest.to_string(),
+            rows: (1..=rows).map(BatchRowResult::new).collect(),
+        }
+    }
+
+    pub async fn read_or_create<P: AsRef<Path>>(
+        path: P,
+        document_type: OfflineDocumentType,
+        manifest: &str,
+        rows: usize,
+    ) -> Result<Self, MystikoCliError> {
+        if !path.as_ref().exists() {
+            return Ok(BatchResults::new(document_type, manifest, rows));
+        }
+        let results = BatchResults::read(path, document_type, manifest).await?;
+        if results.rows.len() != rows {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Results file does not match the manifest {}",
+                manifest
+            )));
+        }
+        Ok(results)
+    }
+
+    pub async fn read_or_create_matching<P: AsRef<Path>>(
+        path: P,
+        document_type: OfflineDocumentType,
+        manifest: &str,
+        keys: &[String],
+    ) -> Result<Self, MystikoCliError> {
+        let mut results =
+            BatchResults::read_or_create(path, document_type, manifest, keys.len()).await?;
+        for (row, key) in results.rows.iter_mut().zip(keys) {
+            match &row.key {
+                Some(row_key) if row_key != key => {
+                    return Err(MystikoCliError::AnyhowError(anyhow!(
+                        "Row {} of the results file does not match the manifest {}, \
+                        move the results file away to start a new batch",
+                        row.row,
+                        manifest
+                    )));
+                }
+                Some(_) => {}
+                None if row.status == BatchRowStatus::Pending && row.document_id.is_none() => {
+                    row.key = Some(key.clone());
+                }
+                None => {
+                    return Err(MystikoCliError::AnyhowError(anyhow!(
+                        "Row {} of the results file has no fingerprint to match the manifest {}",
+                        row.row,
+                        manifest
+                    )));
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    pub async fn read_or_create_keyed<P: AsRef<Path>>(
+        path: P,
+        document_type: OfflineDocumentType,
+        manifest: &str,
+        keys: &[String],
+    ) -> Result<Self, MystikoCliError> {
+        let mut previous = if path.as_ref().exists() {
+            BatchResults::read(path, document_type, manifest)
+                .await?
+                .rows
+        } else {
+            vec![]
+        };
+        let mut results = BatchResults::new(document_type, manifest, 0);
+        for (index, key) in keys.iter().enumerate() {
+            let mut row = match previous
+                .iter()
+                .position(|row| row.key.as_ref() == Some(key))
+            {
+                Some(position) => previous.remove(position),
+                None => BatchRowResult::new(index + 1),
+            };
+            row.row = index + 1;
+            row.key = Some(key.clone());
+            results.rows.push(row);
+        }
+        results.rows.extend(previous);
+        Ok(results)
+    }
+
+    async fn read<P: AsRef<Path>>(
+        path: P,
+        document_type: OfflineDocumentType,
+        manifest: &str,
+    ) -> Result<Self, MystikoCliError> {
+        let content = tokio::fs::read_to_string(path).await?;
+        let results: BatchResults = serde_json::from_str(&content)?;
+        if results.version != BATCH_RESULTS_VERSION {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Unsupported results file version {}",
+                results.version
+            )));
+        }
+        if results.document_type != document_type {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Results file does not match the manifest {}",
+                manifest
+            )));
+        }
+        Ok(results)
+    }
+
+    pub async fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), MystikoCliError> {
+        replace_private_file(path, &serde_json::to_string_pretty(self)?).await
+    }
+}
+
+pub fn deposit_row_key(row: &DepositManifestRow) -> String {
+    format!(
+        "{}:{}:{}:{}",
+        row.chain_id,
+        row.asset_symbol.to_ascii_uppercase(),
+        row.amount,
+        row.shielded_address
+    )
+}
+
+pub fn spend_row_key(row: &SpendManifestRow) -> String {
+    format!(
+        "{}:{}:{}:{}:{}",
+        row.chain_id,
+        row.asset_symbol.to_ascii_uppercase(),
+        row.recipient.to_ascii_lowercase(),
+        row.amount,
+        row.key.as_deref().unwrap_or_default()
+    )
+}
+
+pub fn default_results_path(manifest: &str) -> String {
+    format!("{}.results.json", manifest)
+}
+
+pub async fn read_manifest<T: DeserializeOwned, P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<T>, MystikoCliError> {
+    let content = tokio::fs::read_to_string(&path).await?;
+    let is_json = path
+        .as_ref()
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+    if is_json {
+        Ok(serde_json::from_str(&content)?)
+    } else {
+        parse_csv_manifest(&content)
+    }
+}
+
+pub fn parse_csv_manifest<T: DeserializeOwned>(content: &str) -> Result<Vec<T>, MystikoCliError> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let header = match lines.next() {
+        Some((_, header)) => split_csv_line(header),
+        None => return Ok(vec![]),
+    };
+    let mut rows = vec![];
+    for (index, line) in lines {
+        let fields = split_csv_line(line);
+        if fields.len() > header.len() {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Line {} of the manifest has more columns than the header",
+                index + 1
+            )));
+        }
+        let mut object = Map::new();
+        for (column, field) in header.iter().zip(fields) {
+            if !field.is_empty() {
+                let value = serde_json::from_str::<Value>(&field)
+                    .ok()
+                    .filter(Value::is_number)
+                    .unwrap_or(Value::String(field));
+                object.insert(column.to_ascii_lowercase(), value);
+            }
+        }
+        let row = serde_json::from_value(Value::Object(object)).map_err(|err| {
+            MystikoCliError::AnyhowError(anyhow!("Line {} of the manifest: {}", index + 1, err))
+        })?;
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
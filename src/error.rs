@@ -17,10 +17,14 @@ pub enum MystikoCliError {
     #[error(transparent)]
     SynchronizerError(#[from] mystiko_core::SynchronizerError),
     #[error(transparent)]
+    CryptoError(#[from] mystiko_crypto::error::CryptoError),
+    #[error(transparent)]
     ParseLevelError(#[from] log::ParseLevelError),
     #[error(transparent)]
     StorageError(#[from] mystiko_storage::StorageError),
     #[error(transparent)]
+    SqlxError(#[from] sqlx::Error),
+    #[error(transparent)]
     SerdeJsonError(#[from] serde_json::Error),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
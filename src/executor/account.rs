@@ -1,11 +1,47 @@
+use crate::executor::{create_list_filter, create_query_filter};
 use crate::{
-    print_json, AccountCommand, AccountCommands, AccountCreateCommand,
-    AccountExportSecretKeyCommand, AccountImportCommand, MystikoCliError,
+    expose_required_secret, expose_wallet_password, print_json, AccountCommand, AccountCommands,
+    AccountCreateCommand, AccountDeriveCommand, AccountExportCommand, AccountExportFormat,
+    AccountExportSecretKeyCommand, AccountGetCommand, AccountImportCommand, AccountKeystore,
+    AccountListCommand, AccountUpdateCommand, MystikoCliError, SecretString,
 };
-use mystiko_core::{AccountHandler, Mystiko};
-use mystiko_protos::core::document::v1::Account;
-use mystiko_protos::core::handler::v1::{CreateAccountOptions, UpdateAccountOptions};
-use mystiko_storage::{StatementFormatter, Storage};
+use anyhow::anyhow;
+use mystiko_core::{
+    AccountColumn, AccountHandler, Accounts, Database, Mystiko, ScannerHandler, WalletColumn,
+    WalletHandler, Wallets,
+};
+use mystiko_protos::core::document::v1::{Account, Wallet};
+use mystiko_protos::core::handler::v1::{
+    CreateAccountOptions, CreateWalletOptions, MnemonicOptions, UpdateAccountOptions,
+};
+use mystiko_protos::core::scanner::v1::{
+    AssetImportOptions, AssetImportResult, AssetsByChain, AssetsOptions, BalanceOptions,
+    BalanceResult, ScannerResetOptions, ScannerResetResult, ScannerScanOptions, ScannerScanResult,
+    ScannerSyncOptions, ScannerSyncResult,
+};
+use mystiko_protos::core::v1::MnemonicType;
+use mystiko_protos::storage::v1::SubFilter;
+use mystiko_storage::{
+    ColumnValues, DocumentColumn, SqlStatementFormatter, StatementFormatter, Storage,
+};
+use mystiko_storage_sqlite::SqliteStorage;
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountWithIndex {
+    #[serde(flatten)]
+    pub account: Account,
+    pub derivation_index: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountWithBalance {
+    #[serde(flatten)]
+    pub account: Account,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<BalanceResult>,
+}
 
 pub async fn execute_account_command<F, S, W, A, D, X, Y, R>(
     mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
@@ -15,20 +51,49 @@ pub async fn execute_account_command<F, S, W, A, D, X, Y, R>(
 where
     F: StatementFormatter,
     S: Storage,
+    W: WalletHandler<Wallet, CreateWalletOptions>,
     A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
-    MystikoCliError: From<A::Error>,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<W::Error> + From<A::Error> + From<R::Error>,
 {
     match args.commands {
         AccountCommands::Create(args) => {
             execute_account_create_command(mystiko, args, compact_json).await
         }
+        AccountCommands::Derive(args) => {
+            execute_account_derive_command(mystiko, args, compact_json).await
+        }
         AccountCommands::Import(args) => {
             execute_account_import_command(mystiko, args, compact_json).await
         }
         AccountCommands::ExportSecretKey(args) => {
             execute_account_export_secret_key_command(mystiko, args).await
         }
-        AccountCommands::List => execute_account_list_command(mystiko, compact_json).await,
+        AccountCommands::Export(args) => {
+            execute_account_export_command(mystiko, args, compact_json).await
+        }
+        AccountCommands::Update(args) => {
+            execute_account_update_command(mystiko, args, compact_json).await
+        }
+        AccountCommands::Get(args) => {
+            execute_account_get_command(mystiko, args, compact_json).await
+        }
+        AccountCommands::List(args) => {
+            execute_account_list_command(mystiko, args, compact_json).await
+        }
     }
 }
 
@@ -40,14 +105,106 @@ pub async fn execute_account_create_command<F, S, W, A, D, X, Y, R>(
 where
     F: StatementFormatter,
     S: Storage,
+    W: WalletHandler<Wallet, CreateWalletOptions>,
     A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
-    MystikoCliError: From<A::Error>,
+    MystikoCliError: From<W::Error> + From<A::Error>,
 {
+    let wallet = mystiko
+        .wallets
+        .check_password(expose_wallet_password(&args.password)?)
+        .await?;
+    let deriver =
+        account_deriver(mystiko, expose_wallet_password(&args.password)?, &wallet).await?;
+    let mut created = 0;
+    while created < args.count {
+        let derivation_index = mystiko.wallets.check_current().await?.account_nonce;
+        let (derived, _) = deriver.derive(derivation_index).await?;
+        if mystiko
+            .accounts
+            .find_by_shielded_address(&derived.shielded_address)
+            .await?
+            .is_some()
+        {
+            skip_wallet_account_nonce(mystiko, &wallet.id, derivation_index).await?;
+            continue;
+        }
+        let name = args
+            .name_prefix
+            .as_ref()
+            .map(|prefix| format!("{}{}", prefix, derivation_index))
+            .or(args.name.clone());
+        let options = CreateAccountOptions::builder()
+            .wallet_password(expose_wallet_password(&args.password)?)
+            .name(name)
+            .build();
+        let account = mystiko.accounts.create(&options).await?;
+        if account.shielded_address != derived.shielded_address {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Account {} was not derived at index {}",
+                account.shielded_address,
+                derivation_index
+            )));
+        }
+        print_json(
+            &AccountWithIndex {
+                account,
+                derivation_index,
+            },
+            compact_json,
+        )?;
+        created += 1;
+    }
+    Ok(())
+}
+
+pub async fn execute_account_derive_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: AccountDeriveCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    W: WalletHandler<Wallet, CreateWalletOptions>,
+    A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
+    MystikoCliError: From<W::Error> + From<A::Error>,
+{
+    let wallet = mystiko
+        .wallets
+        .check_password(expose_wallet_password(&args.password)?)
+        .await?;
+    let deriver =
+        account_deriver(mystiko, expose_wallet_password(&args.password)?, &wallet).await?;
+    let (derived, secret_key) = deriver.derive(args.index).await?;
+    if mystiko
+        .accounts
+        .find_by_shielded_address(&derived.shielded_address)
+        .await?
+        .is_some()
+    {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Account {} at derivation index {} already exists",
+            derived.shielded_address,
+            args.index
+        )));
+    }
     let options = CreateAccountOptions::builder()
-        .wallet_password(args.password)
+        .wallet_password(expose_wallet_password(&args.password)?)
         .name(args.name)
+        .secret_key(secret_key.expose_secret().to_string())
         .build();
     let account = mystiko.accounts.create(&options).await?;
+    if account.shielded_address != derived.shielded_address {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Account {} was not derived at index {}",
+            account.shielded_address,
+            args.index
+        )));
+    }
+    let account = AccountWithIndex {
+        account,
+        derivation_index: args.index,
+    };
     print_json(&account, compact_json)
 }
 
@@ -62,10 +219,20 @@ where
     A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
     MystikoCliError: From<A::Error>,
 {
+    let (secret_key, name) = if let Some(keystore) = &args.keystore {
+        let keystore = AccountKeystore::read(keystore).await?;
+        let secret_key = SecretString::from(keystore.decrypt(expose_required_secret(
+            &args.export_passphrase,
+            "Export passphrase",
+        )?)?);
+        (secret_key, args.name.or(Some(keystore.name)))
+    } else {
+        (args.secret_key.unwrap_or_default(), args.name)
+    };
     let options = CreateAccountOptions::builder()
-        .wallet_password(args.password)
-        .name(args.name)
-        .secret_key(args.secret_key)
+        .wallet_password(expose_wallet_password(&args.password)?)
+        .name(name)
+        .secret_key(secret_key.expose_secret().to_string())
         .build();
     let account = mystiko.accounts.create(&options).await?;
     print_json(&account, compact_json)
@@ -81,26 +248,341 @@ where
     A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
     MystikoCliError: From<A::Error>,
 {
-    let secret_key = mystiko
+    let secret_key = SecretString::from(
+        mystiko
+            .accounts
+            .export_secret_key_by_shielded_address(
+                expose_wallet_password(&args.password)?,
+                &args.shielded_address,
+            )
+            .await?,
+    );
+    println!("{}", secret_key.expose_secret());
+    Ok(())
+}
+
+pub async fn execute_account_export_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: AccountExportCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
+    MystikoCliError: From<A::Error>,
+{
+    let account = mystiko
+        .accounts
+        .find_by_shielded_address(&args.shielded_address)
+        .await?
+        .ok_or_else(|| anyhow!("Account not found"))?;
+    let secret_key = SecretString::from(
+        mystiko
+            .accounts
+            .export_secret_key_by_shielded_address(
+                expose_wallet_password(&args.password)?,
+                &args.shielded_address,
+            )
+            .await?,
+    );
+    match args.format {
+        AccountExportFormat::Keystore => {
+            let keystore = AccountKeystore::encrypt(
+                &account,
+                secret_key.expose_secret(),
+                args.export_passphrase.expose_secret(),
+            )?;
+            keystore.write(&args.out).await?;
+        }
+    }
+    print_json(&account, compact_json)
+}
+
+pub async fn execute_account_update_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: AccountUpdateCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
+    MystikoCliError: From<A::Error>,
+{
+    let shielded_address = args.shielded_address.clone();
+    let account = mystiko
         .accounts
-        .export_secret_key_by_shielded_address(&args.password, &args.shielded_address)
+        .update_by_shielded_address(&shielded_address, &UpdateAccountOptions::try_from(args)?)
         .await?;
-    println!("{}", secret_key);
+    print_json(&account, compact_json)
+}
+
+pub async fn execute_account_get_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: AccountGetCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<A::Error> + From<R::Error>,
+{
+    let accounts = if let Some(shielded_address) = &args.shielded_address {
+        mystiko
+            .accounts
+            .find_by_shielded_address(shielded_address)
+            .await?
+            .into_iter()
+            .collect()
+    } else if let Some(public_key) = &args.public_key {
+        mystiko
+            .accounts
+            .find_by_public_key(public_key)
+            .await?
+            .into_iter()
+            .collect()
+    } else if let Some(name) = args.name {
+        mystiko
+            .accounts
+            .find(SubFilter::equal(AccountColumn::Name, name))
+            .await?
+    } else {
+        vec![]
+    };
+    if accounts.is_empty() {
+        return Err(MystikoCliError::AnyhowError(anyhow!("Account not found")));
+    }
+    for account in accounts.into_iter() {
+        let account = account_with_balance(mystiko, account, args.with_balance).await?;
+        print_json(&account, compact_json)?;
+    }
     Ok(())
 }
 
 pub async fn execute_account_list_command<F, S, W, A, D, X, Y, R>(
     mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: AccountListCommand,
     compact_json: bool,
 ) -> Result<(), MystikoCliError>
 where
     F: StatementFormatter,
     S: Storage,
     A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
-    MystikoCliError: From<A::Error>,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<A::Error> + From<R::Error>,
 {
-    for account in mystiko.accounts.find_all().await?.into_iter() {
+    let mut sub_filters = vec![];
+    if let Some(statuses) = args.status {
+        if !statuses.is_empty() {
+            sub_filters.push(SubFilter::in_list(
+                AccountColumn::Status,
+                statuses
+                    .into_iter()
+                    .map(|status| status as i32)
+                    .collect::<Vec<_>>(),
+            ));
+        }
+    }
+    if let Some(wallet_ids) = args.wallet_id {
+        if !wallet_ids.is_empty() {
+            sub_filters.push(SubFilter::in_list(AccountColumn::WalletId, wallet_ids));
+        }
+    }
+    let page = args.page.unwrap_or(1_u64);
+    let accounts = if let Some(name) = args.name {
+        let name = name.to_lowercase();
+        let accounts = mystiko
+            .accounts
+            .find(create_query_filter(sub_filters))
+            .await?
+            .into_iter()
+            .filter(|account| account.name.to_lowercase().contains(&name));
+        match args.limit {
+            Some(limit) => {
+                let limit = limit.max(1_u64) as usize;
+                let page = page.max(1_u64) as usize;
+                accounts.skip((page - 1) * limit).take(limit).collect()
+            }
+            None => accounts.collect(),
+        }
+    } else {
+        let filter = match args.limit {
+            Some(limit) => create_list_filter(sub_filters, limit, page),
+            None => create_query_filter(sub_filters),
+        };
+        mystiko.accounts.find(filter).await?
+    };
+    for account in accounts.into_iter() {
+        let account = account_with_balance(mystiko, account, args.with_balance).await?;
         print_json(&account, compact_json)?;
     }
     Ok(())
 }
+
+pub(crate) struct AccountDeriver {
+    db: Arc<Database<SqlStatementFormatter, SqliteStorage>>,
+    accounts: Accounts<SqlStatementFormatter, SqliteStorage>,
+    wallet_id: String,
+    password: SecretString,
+}
+
+impl AccountDeriver {
+    pub(crate) async fn new(
+        password: &str,
+        mnemonic: MnemonicOptions,
+    ) -> Result<Self, MystikoCliError> {
+        let db = Arc::new(Database::new(
+            SqlStatementFormatter::sqlite(),
+            SqliteStorage::from_memory().await?,
+        ));
+        db.migrate().await?;
+        let options = CreateWalletOptions::builder()
+            .password(password)
+            .mnemonic(mnemonic)
+            .build();
+        let wallet = Wallets::new(db.clone()).create(&options).await?;
+        Ok(AccountDeriver {
+            accounts: Accounts::new(db.clone()),
+            db,
+            wallet_id: wallet.id,
+            password: SecretString::from(password.to_string()),
+        })
+    }
+
+    pub(crate) async fn derive(
+        &self,
+        derivation_index: u32,
+    ) -> Result<(Account, SecretString), MystikoCliError> {
+        let column_values =
+            ColumnValues::new().append_value(WalletColumn::AccountNonce, derivation_index);
+        self.db
+            .wallets
+            .update_by_filter(
+                column_values,
+                SubFilter::equal(DocumentColumn::Id, self.wallet_id.clone()),
+            )
+            .await?;
+        let options = CreateAccountOptions::builder()
+            .wallet_password(self.password.expose_secret())
+            .build();
+        let account = self.accounts.create(&options).await?;
+        let secret_key = SecretString::from(
+            self.accounts
+                .export_secret_key_by_shielded_address(
+                    self.password.expose_secret(),
+                    &account.shielded_address,
+                )
+                .await?,
+        );
+        Ok((account, secret_key))
+    }
+}
+
+async fn account_deriver<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    password: &str,
+    wallet: &Wallet,
+) -> Result<AccountDeriver, MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    W: WalletHandler<Wallet, CreateWalletOptions>,
+    MystikoCliError: From<W::Error>,
+{
+    let mnemonic_phrase =
+        SecretString::from(mystiko.wallets.export_mnemonic_phrase(password).await?);
+    let mnemonic = MnemonicOptions::builder()
+        .mnemonic_phrase(mnemonic_phrase.expose_secret().to_string())
+        .mnemonic_type(MnemonicType::from_i32(wallet.mnemonic_type).unwrap_or_default())
+        .build();
+    AccountDeriver::new(password, mnemonic).await
+}
+
+async fn skip_wallet_account_nonce<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    wallet_id: &str,
+    account_nonce: u32,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+{
+    let column_values =
+        ColumnValues::new().append_value(WalletColumn::AccountNonce, account_nonce + 1);
+    mystiko
+        .db
+        .wallets
+        .update_by_filter(
+            column_values,
+            vec![
+                SubFilter::equal(DocumentColumn::Id, wallet_id.to_string()),
+                SubFilter::equal(WalletColumn::AccountNonce, account_nonce),
+            ],
+        )
+        .await?;
+    Ok(())
+}
+
+async fn account_with_balance<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    account: Account,
+    with_balance: bool,
+) -> Result<AccountWithBalance, MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<R::Error>,
+{
+    let balance = if with_balance {
+        let options = BalanceOptions::builder()
+            .shielded_addresses(vec![account.shielded_address.clone()])
+            .build();
+        Some(mystiko.scanner.balance(options).await?)
+    } else {
+        None
+    };
+    Ok(AccountWithBalance { account, balance })
+}
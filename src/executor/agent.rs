@@ -0,0 +1,46 @@
+use crate::{
+    print_json, run_agent, send_agent_request, AgentRequest, AgentSecret, MystikoCliError,
+    UnlockCommand,
+};
+use mystiko_core::{Mystiko, WalletHandler};
+use mystiko_protos::core::document::v1::Wallet;
+use mystiko_protos::core::handler::v1::CreateWalletOptions;
+use mystiko_storage::{StatementFormatter, Storage};
+use std::path::Path;
+
+pub async fn execute_agent_command<P: AsRef<Path>>(socket_path: P) -> Result<(), MystikoCliError> {
+    run_agent(socket_path).await
+}
+
+pub async fn execute_unlock_command<F, S, W, A, D, X, Y, R, P>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: UnlockCommand,
+    socket_path: P,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    W: WalletHandler<Wallet, CreateWalletOptions>,
+    MystikoCliError: From<W::Error>,
+    P: AsRef<Path>,
+{
+    mystiko
+        .wallets
+        .check_password(args.password.expose_secret())
+        .await?;
+    let request = AgentRequest::Unlock {
+        password: AgentSecret(args.password),
+        ttl_secs: args.ttl.as_secs(),
+    };
+    let response = send_agent_request(socket_path, &request).await?;
+    print_json(&response, compact_json)
+}
+
+pub async fn execute_lock_command<P: AsRef<Path>>(
+    socket_path: P,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    let response = send_agent_request(socket_path, &AgentRequest::Lock).await?;
+    print_json(&response, compact_json)
+}
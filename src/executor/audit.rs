@@ -0,0 +1,57 @@
+use crate::{print_json, AuditCommand, AuditCommands, AuditListCommand, AuditLog, MystikoCliError};
+use anyhow::anyhow;
+
+pub async fn execute_audit_command(
+    args: AuditCommand,
+    audit_log: &AuditLog,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    match args.commands {
+        AuditCommands::List(args) => {
+            execute_audit_list_command(args, audit_log, compact_json).await
+        }
+        AuditCommands::Verify => execute_audit_verify_command(audit_log, compact_json).await,
+    }
+}
+
+pub async fn execute_audit_list_command(
+    args: AuditListCommand,
+    audit_log: &AuditLog,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    let limit = args.limit.max(1_u64) as usize;
+    let page = args.page.max(1_u64) as usize;
+    let entries = audit_log
+        .entries()
+        .await?
+        .into_iter()
+        .rev()
+        .filter(|entry| {
+            args.command
+                .as_ref()
+                .map(|command| &entry.command == command)
+                .unwrap_or(true)
+        })
+        .skip((page - 1) * limit)
+        .take(limit);
+    for entry in entries {
+        print_json(&entry, compact_json)?;
+    }
+    Ok(())
+}
+
+pub async fn execute_audit_verify_command(
+    audit_log: &AuditLog,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    let report = audit_log.verify().await?;
+    print_json(&report, compact_json)?;
+    if report.valid {
+        Ok(())
+    } else {
+        Err(MystikoCliError::AnyhowError(anyhow!(
+            "Audit log is broken at entry {}",
+            report.broken_at.unwrap_or_default()
+        )))
+    }
+}
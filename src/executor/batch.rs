@@ -0,0 +1,856 @@
+use crate::args::{parse_bridge_type, parse_spend_type};
+use crate::executor::{
+    check_quote_amount, check_transaction, create_default_transaction, create_nonce_transaction,
+    create_recover_provider, parse_address, TransactionOutcome,
+};
+use crate::{
+    confirm_execution, default_results_path, deposit_row_key, expose_wallet_password,
+    parse_private_key, print_json, read_manifest, request_signer_address, spend_row_key,
+    BatchResults, BatchRowResult, BatchRowStatus, CommandSigner, DepositBatchCommand,
+    DepositManifestRow, MystikoCliError, OfflineDocumentType, ReplacementStore, SpendBatchCommand,
+    SpendManifestRow, TransactionReplacementRecord,
+};
+use anyhow::anyhow;
+use ethers_core::abi::{encode, Token};
+use ethers_core::types::{Address, BlockNumber, Bytes, TransactionRequest, U256};
+use ethers_core::utils::{format_units, parse_units};
+use ethers_providers::{Http, Middleware, Provider};
+use ethers_signers::Signer;
+use futures::StreamExt;
+use mystiko_config::MystikoConfig;
+use mystiko_core::{DepositHandler, Mystiko, ScannerHandler, SpendHandler};
+use mystiko_protos::core::document::v1::{Deposit, Spend};
+use mystiko_protos::core::handler::v1::{
+    CreateDepositOptions, CreateSpendOptions, DepositQuote, DepositSummary,
+    FixDepositStatusOptions, FixSpendStatusOptions, QuoteDepositOptions, QuoteSpendOptions,
+    SendDepositOptions, SendSpendOptions, SpendQuote, SpendSummary,
+};
+use mystiko_protos::core::scanner::v1::{
+    AssetImportOptions, AssetImportResult, AssetsByChain, AssetsOptions, BalanceOptions,
+    BalanceResult, ScannerResetOptions, ScannerResetResult, ScannerScanOptions, ScannerScanResult,
+    ScannerSyncOptions, ScannerSyncResult,
+};
+use mystiko_protos::core::v1::{DepositStatus, SpendStatus};
+use mystiko_storage::{StatementFormatter, Storage};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+const BALANCE_OF_SELECTOR: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DepositBatchConfirmation {
+    pub manifest: String,
+    pub signer: Address,
+    pub deposits: usize,
+    pub required: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendBatchBalance {
+    pub chain_id: u64,
+    pub asset_symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bridge_type: Option<i32>,
+    pub required: f64,
+    pub available: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendBatchConfirmation {
+    pub manifest: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub relayer: Option<String>,
+    pub spends: usize,
+    pub balances: Vec<SpendBatchBalance>,
+}
+
+pub async fn execute_deposit_batch_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: DepositBatchCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    MystikoCliError: From<D::Error>,
+{
+    let rows: Vec<DepositManifestRow> = read_manifest(&args.manifest).await?;
+    if rows.is_empty() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Manifest {} has no rows",
+            args.manifest
+        )));
+    }
+    let keys = rows.iter().map(deposit_row_key).collect::<Vec<_>>();
+    let results_path = args
+        .results
+        .clone()
+        .unwrap_or_else(|| default_results_path(&args.manifest));
+    let mut results = BatchResults::read_or_create_matching(
+        &results_path,
+        OfflineDocumentType::Deposit,
+        &args.manifest,
+        &keys,
+    )
+    .await?;
+    let provider = Provider::<Http>::try_from(args.signer_provider.as_str())
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    let chain_id = provider
+        .get_chainid()
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+        .as_u64();
+    let signer_address = match (&args.private_key, &args.signer_command) {
+        (Some(private_key), _) => parse_private_key(private_key)?.address(),
+        (None, Some(signer_command)) => {
+            request_signer_address(signer_command, args.signer_timeout_ms).await?
+        }
+        (None, None) => {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Pass --private-key or --signer-command to sign the deposits"
+            )))
+        }
+    };
+
+    let mut pending = vec![];
+    let mut to_create = vec![];
+    for (index, row) in rows.iter().enumerate() {
+        if row.chain_id != chain_id {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Row {} is on chain {} but the signer provider is on chain {}",
+                index + 1,
+                row.chain_id,
+                chain_id
+            )));
+        }
+        let existing = match &results.rows[index].document_id {
+            Some(deposit_id) => mystiko.deposits.find_by_id(deposit_id.clone()).await?,
+            None => None,
+        };
+        if let Some(deposit) = &existing {
+            update_row_result(&mut results.rows[index], deposit);
+        }
+        match (existing, results.rows[index].status) {
+            (Some(deposit), BatchRowStatus::Created) => pending.push((index, deposit)),
+            (Some(_), BatchRowStatus::Sending | BatchRowStatus::Sent) => {}
+            (None, BatchRowStatus::Sending | BatchRowStatus::Sent) => {
+                return Err(MystikoCliError::AnyhowError(anyhow!(
+                    "Row {} was already sent as deposit {} which is missing from the database, \
+                    check it on chain before resuming the batch",
+                    index + 1,
+                    results.rows[index].document_id.clone().unwrap_or_default()
+                )))
+            }
+            _ => to_create.push(index),
+        }
+    }
+    results.write(&results_path).await?;
+
+    for index in to_create.iter() {
+        let row = &rows[*index];
+        let quote = mystiko
+            .deposits
+            .quote(row.clone().into())
+            .await
+            .map_err(|err| {
+                MystikoCliError::AnyhowError(anyhow!(
+                    "Row {}: {}",
+                    index + 1,
+                    MystikoCliError::from(err)
+                ))
+            })?;
+        check_quote_amount(
+            row.amount,
+            quote.min_amount,
+            quote.max_amount,
+            &quote.asset_symbol,
+        )
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!("Row {}: {}", index + 1, err)))?;
+    }
+    let mut required: BTreeMap<String, f64> = BTreeMap::new();
+    for index in to_create
+        .iter()
+        .chain(pending.iter().map(|(index, _)| index))
+    {
+        let summary = mystiko
+            .deposits
+            .summary(rows[*index].clone().into())
+            .await?;
+        for (asset_symbol, amount) in summary.total_amounts.into_iter() {
+            *required.entry(asset_symbol).or_default() += amount;
+        }
+    }
+    check_batch_balances(
+        &provider,
+        &mystiko.config,
+        chain_id,
+        signer_address,
+        &required,
+    )
+    .await?;
+    let deposits = to_create.len() + pending.len();
+    if !args.yes && deposits > 0 {
+        confirm_execution(&DepositBatchConfirmation {
+            manifest: args.manifest.clone(),
+            signer: signer_address,
+            deposits,
+            required: required.clone(),
+        })?;
+    }
+    let mut nonce = if deposits > 0 {
+        check_signer_idle(&provider, signer_address).await?
+    } else {
+        U256::zero()
+    };
+
+    for index in to_create.into_iter() {
+        let key = results.rows[index].key.take();
+        results.rows[index] = BatchRowResult::new(index + 1);
+        results.rows[index].key = key;
+        match mystiko.deposits.create(rows[index].clone().into()).await {
+            Ok(deposit) => {
+                update_row_result(&mut results.rows[index], &deposit);
+                results.write(&results_path).await?;
+                pending.push((index, deposit));
+            }
+            Err(err) => {
+                let err = MystikoCliError::from(err);
+                results.rows[index].status = BatchRowStatus::Failed;
+                results.rows[index].error = Some(err.to_string());
+                results.write(&results_path).await?;
+                return Err(err);
+            }
+        }
+    }
+    pending.sort_by_key(|(index, _)| *index);
+
+    let signer = match &args.signer_command {
+        Some(signer_command) => Some(Arc::new(
+            CommandSigner::new(
+                signer_command,
+                &args.signer_provider,
+                args.signer_timeout_ms,
+            )
+            .await?,
+        )),
+        None => None,
+    };
+    for (index, deposit) in pending.into_iter() {
+        let current = signer_nonce(&provider, signer_address, BlockNumber::Pending).await?;
+        if current != nonce {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Nonce of signer {:?} moved from {} to {} outside of the batch, \
+                check its transactions before resuming the batch",
+                signer_address,
+                nonce,
+                current
+            )));
+        }
+        results.rows[index].status = BatchRowStatus::Sending;
+        results.rows[index].nonce = Some(nonce.as_u64());
+        results.write(&results_path).await?;
+        let deposit_tx = match deposit.asset_address {
+            Some(_) => create_default_transaction(deposit.chain_id),
+            None => create_nonce_transaction(deposit.chain_id, nonce),
+        };
+        let options = SendDepositOptions::builder()
+            .deposit_id(deposit.id.clone())
+            .private_key(
+                args.private_key
+                    .as_ref()
+                    .map(|private_key| private_key.expose_secret().to_string()),
+            )
+            .signer_provider(Some(args.signer_provider.clone()))
+            .query_timeout_ms(args.query_timeout_ms)
+            .asset_approve_confirmations(args.asset_approve_confirmations)
+            .deposit_confirmations(args.deposit_confirmations)
+            .screening_message(args.screening_message.clone())
+            .asset_approve_tx(create_nonce_transaction(deposit.chain_id, nonce))
+            .deposit_tx(deposit_tx)
+            .build();
+        let sent = match &signer {
+            Some(signer) => {
+                mystiko
+                    .deposits
+                    .send_with_signer(options, signer.clone())
+                    .await
+            }
+            None => mystiko.deposits.send(options).await,
+        };
+        match sent {
+            Ok(deposit) => {
+                update_row_result(&mut results.rows[index], &deposit);
+                results.write(&results_path).await?;
+            }
+            Err(err) => {
+                let err = MystikoCliError::from(err);
+                if let Some(deposit) = mystiko.deposits.find_by_id(deposit.id.clone()).await? {
+                    update_row_result(&mut results.rows[index], &deposit);
+                }
+                results.rows[index].error = Some(err.to_string());
+                results.write(&results_path).await?;
+                return Err(err);
+            }
+        }
+        let next = signer_nonce(&provider, signer_address, BlockNumber::Pending).await?;
+        if next <= nonce {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Row {} was sent but the nonce of signer {:?} did not advance from {}",
+                index + 1,
+                signer_address,
+                nonce
+            )));
+        }
+        nonce = next;
+    }
+    print_json(&results, compact_json)
+}
+
+async fn check_signer_idle(
+    provider: &Provider<Http>,
+    signer_address: Address,
+) -> Result<U256, MystikoCliError> {
+    let latest = signer_nonce(provider, signer_address, BlockNumber::Latest).await?;
+    let pending = signer_nonce(provider, signer_address, BlockNumber::Pending).await?;
+    if latest != pending {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Signer {:?} has pending transactions from nonce {} to {}, \
+            wait for them before sending the batch",
+            signer_address,
+            latest,
+            pending
+        )));
+    }
+    Ok(pending)
+}
+
+async fn signer_nonce(
+    provider: &Provider<Http>,
+    signer_address: Address,
+    block: BlockNumber,
+) -> Result<U256, MystikoCliError> {
+    provider
+        .get_transaction_count(signer_address, Some(block.into()))
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))
+}
+
+async fn check_batch_balances(
+    provider: &Provider<Http>,
+    config: &MystikoConfig,
+    chain_id: u64,
+    signer_address: Address,
+    required: &BTreeMap<String, f64>,
+) -> Result<(), MystikoCliError> {
+    let chain = config.find_chain(chain_id).ok_or_else(|| {
+        MystikoCliError::AnyhowError(anyhow!("Chain {} is not configured", chain_id))
+    })?;
+    for (asset_symbol, amount) in required.iter() {
+        let (asset_address, decimals) = if chain.asset_symbol() == asset_symbol {
+            (None, chain.asset_decimals())
+        } else {
+            let contract = chain
+                .deposit_contracts()
+                .into_iter()
+                .find(|contract| contract.asset_symbol() == asset_symbol)
+                .ok_or_else(|| {
+                    MystikoCliError::AnyhowError(anyhow!(
+                        "Asset {} is not configured on chain {}",
+                        asset_symbol,
+                        chain_id
+                    ))
+                })?;
+            (
+                Some(contract.asset_address().to_string()),
+                contract.asset_decimals(),
+            )
+        };
+        let required: U256 = parse_units(format!("{:.*}", decimals as usize, amount), decimals)
+            .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+            .into();
+        let available = match &asset_address {
+            Some(asset_address) => {
+                let data = [
+                    BALANCE_OF_SELECTOR.to_vec(),
+                    encode(&[Token::Address(signer_address)]),
+                ]
+                .concat();
+                let request = TransactionRequest::new()
+                    .to(parse_address(asset_address)?)
+                    .data(Bytes::from(data));
+                let output = provider
+                    .call(&request.into(), None)
+                    .await
+                    .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+                U256::from_big_endian(&output[..output.len().min(32)])
+            }
+            None => provider
+                .get_balance(signer_address, None)
+                .await
+                .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?,
+        };
+        if available < required {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Insufficient balance of {} for the batch: required {}, available {}",
+                asset_symbol,
+                format_units(required, decimals)
+                    .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?,
+                format_units(available, decimals)
+                    .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn update_row_result(result: &mut BatchRowResult, deposit: &Deposit) {
+    result.document_id = Some(deposit.id.clone());
+    result.asset_approve_transaction_hash = deposit.asset_approve_transaction_hash.clone();
+    result.transaction_hash = deposit.transaction_hash.clone();
+    result.error = deposit.error_message.clone();
+    result.status = batch_row_status(deposit.status);
+}
+
+pub fn batch_row_status(status: i32) -> BatchRowStatus {
+    match DepositStatus::from_i32(status).unwrap_or_default() {
+        DepositStatus::Unspecified => BatchRowStatus::Created,
+        DepositStatus::Failed => BatchRowStatus::Failed,
+        DepositStatus::AssetApproving
+        | DepositStatus::AssetApproved
+        | DepositStatus::SrcPending => BatchRowStatus::Sending,
+        _ => BatchRowStatus::Sent,
+    }
+}
+
+pub async fn execute_spend_batch_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: SpendBatchCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<X::Error> + From<R::Error>,
+{
+    let rows: Vec<SpendManifestRow> = read_manifest(&args.manifest).await?;
+    if rows.is_empty() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Manifest {} has no rows",
+            args.manifest
+        )));
+    }
+    let keys = rows.iter().map(spend_row_key).collect::<Vec<_>>();
+    for (index, key) in keys.iter().enumerate() {
+        if keys[..index].contains(key) {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Row {} repeats the chain, asset, recipient and amount of an earlier row, \
+                give it a distinct key",
+                index + 1
+            )));
+        }
+    }
+    let results_path = args
+        .results
+        .clone()
+        .unwrap_or_else(|| default_results_path(&args.manifest));
+    let mut results = BatchResults::read_or_create_keyed(
+        &results_path,
+        OfflineDocumentType::Spend,
+        &args.manifest,
+        &keys,
+    )
+    .await?;
+
+    let replacements = match &args.replacements_path {
+        Some(replacements_path) => {
+            ReplacementStore::from_path(replacements_path)
+                .await?
+                .records()
+                .await?
+        }
+        None => vec![],
+    };
+
+    let mut to_create = vec![];
+    let mut to_send = vec![];
+    for (index, row) in rows.iter().enumerate() {
+        let existing = match &results.rows[index].document_id {
+            Some(spend_id) => mystiko.spends.find_by_id(spend_id.clone()).await?,
+            None => None,
+        };
+        if let Some(spend) = &existing {
+            update_spend_row_result(&mut results.rows[index], spend);
+        }
+        match (existing, results.rows[index].status) {
+            (Some(spend), BatchRowStatus::Created) => to_send.push((index, spend)),
+            (Some(_), BatchRowStatus::Sending | BatchRowStatus::Sent) => {}
+            (None, BatchRowStatus::Sending | BatchRowStatus::Sent) => {
+                return Err(MystikoCliError::AnyhowError(anyhow!(
+                    "Row {} was already sent as spend {} which is missing from the database, \
+                    check it on chain before resuming the batch",
+                    index + 1,
+                    results.rows[index].document_id.clone().unwrap_or_default()
+                )))
+            }
+            (spend, _) => {
+                let result = &results.rows[index];
+                if result.document_id.is_some() || result.transaction_hash.is_some() {
+                    check_failed_spend_row(&args, row, result, spend.as_ref(), &replacements)
+                        .await?;
+                }
+                to_create.push(index);
+            }
+        }
+    }
+    results.write(&results_path).await?;
+
+    for index in to_create.iter() {
+        let row = &rows[*index];
+        let quote = mystiko
+            .spends
+            .quote(create_quote_spend_options(row, &args))
+            .await?;
+        if !quote.valid {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Invalid spend quote for row {} with code {}",
+                index + 1,
+                quote.invalid_code
+            )));
+        }
+        check_quote_amount(
+            row.amount,
+            quote.min_amount,
+            quote.max_amount,
+            &quote.asset_symbol,
+        )?;
+    }
+    let balances = check_spend_batch_balances(
+        mystiko,
+        to_create
+            .iter()
+            .chain(to_send.iter().map(|(index, _)| index))
+            .map(|index| &rows[*index]),
+    )
+    .await?;
+    let spends = to_create.len() + to_send.len();
+    if !args.yes && spends > 0 {
+        confirm_execution(&SpendBatchConfirmation {
+            manifest: args.manifest.clone(),
+            relayer: args.relayer.clone(),
+            spends,
+            balances,
+        })?;
+    }
+
+    for index in to_create.into_iter() {
+        let key = results.rows[index].key.take();
+        results.rows[index] = BatchRowResult::new(index + 1);
+        results.rows[index].key = key;
+        match mystiko
+            .spends
+            .create(create_spend_options(&rows[index], &args)?)
+            .await
+        {
+            Ok(spend) => {
+                update_spend_row_result(&mut results.rows[index], &spend);
+                results.write(&results_path).await?;
+                to_send.push((index, spend));
+            }
+            Err(err) => {
+                let err = MystikoCliError::from(err);
+                results.rows[index].status = BatchRowStatus::Failed;
+                results.rows[index].error = Some(err.to_string());
+                results.write(&results_path).await?;
+                return Err(err);
+            }
+        }
+    }
+
+    for (index, _) in to_send.iter() {
+        results.rows[*index].status = BatchRowStatus::Sending;
+    }
+    results.write(&results_path).await?;
+    let signer = match &args.signer_command {
+        Some(signer_command) => Some(Arc::new(
+            CommandSigner::new(
+                signer_command,
+                args.signer_provider.as_deref().unwrap_or_default(),
+                args.signer_timeout_ms,
+            )
+            .await?,
+        )),
+        None => None,
+    };
+    let wallet_password = expose_wallet_password(&args.password)?;
+    let mut sends = futures::stream::iter(to_send.into_iter().map(|(index, spend)| {
+        let options = SendSpendOptions::builder()
+            .spend_id(spend.id.clone())
+            .wallet_password(wallet_password)
+            .private_key(
+                args.private_key
+                    .as_ref()
+                    .map(|private_key| private_key.expose_secret().to_string()),
+            )
+            .signer_provider(args.signer_provider.clone())
+            .query_timeout_ms(args.query_timeout_ms)
+            .spend_confirmations(args.confirmations)
+            .build();
+        let signer = signer.clone();
+        async move {
+            let sent = match signer {
+                Some(signer) => mystiko.spends.send_with_signer(options, signer).await,
+                None => mystiko.spends.send(options).await,
+            };
+            (index, spend.id, sent)
+        }
+    }))
+    .buffer_unordered(args.concurrency.max(1));
+    let mut failed = 0;
+    let mut errors = vec![];
+    while let Some((index, spend_id, sent)) = sends.next().await {
+        match sent {
+            Ok(spend) => update_spend_row_result(&mut results.rows[index], &spend),
+            Err(err) => {
+                failed += 1;
+                let err = MystikoCliError::from(err);
+                match mystiko.spends.find_by_id(spend_id).await {
+                    Ok(Some(spend)) => update_spend_row_result(&mut results.rows[index], &spend),
+                    Ok(None) => {}
+                    Err(find_err) => errors.push(MystikoCliError::from(find_err)),
+                }
+                results.rows[index].error = Some(err.to_string());
+            }
+        }
+        if let Err(err) = results.write(&results_path).await {
+            errors.push(err);
+        }
+    }
+    if let Some(err) = errors.into_iter().next() {
+        return Err(err);
+    }
+    if failed > 0 {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "{} spend(s) of the batch failed, see {}",
+            failed,
+            results_path
+        )));
+    }
+    print_json(&results, compact_json)
+}
+
+async fn check_failed_spend_row(
+    args: &SpendBatchCommand,
+    row: &SpendManifestRow,
+    result: &BatchRowResult,
+    spend: Option<&Spend>,
+    replacements: &[TransactionReplacementRecord],
+) -> Result<(), MystikoCliError> {
+    let document_id = result.document_id.clone().unwrap_or_default();
+    let transaction_hash = spend
+        .and_then(|spend| spend.transaction_hash.clone())
+        .or_else(|| result.transaction_hash.clone());
+    if !args.retry_failed {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Row {} already recorded spend {} with transaction {}, \
+            pass --retry-failed to check it on chain and create the spend again",
+            result.row,
+            document_id,
+            transaction_hash.unwrap_or_default()
+        )));
+    }
+    let outcome = match &transaction_hash {
+        Some(_) => {
+            let provider = create_recover_provider(
+                args.signer_provider.as_deref().unwrap_or_default(),
+                row.chain_id,
+            )
+            .await?;
+            check_transaction(&provider, transaction_hash.as_deref(), replacements).await?
+        }
+        None => {
+            let direct = spend.is_some_and(|spend| {
+                spend.gas_relayer_url.is_none() && spend.gas_relayer_address.is_none()
+            });
+            if !direct {
+                return Err(MystikoCliError::AnyhowError(anyhow!(
+                    "Row {} recorded spend {} without a transaction hash to check on chain, \
+                    it is not retried",
+                    result.row,
+                    document_id
+                )));
+            }
+            TransactionOutcome::Missing
+        }
+    };
+    match outcome {
+        TransactionOutcome::Missing
+        | TransactionOutcome::Dropped
+        | TransactionOutcome::Reverted
+        | TransactionOutcome::Cancelled => Ok(()),
+        TransactionOutcome::Succeeded => Err(MystikoCliError::AnyhowError(anyhow!(
+            "Row {} spend transaction {} succeeded on chain, it is not retried",
+            result.row,
+            transaction_hash.unwrap_or_default()
+        ))),
+        TransactionOutcome::Pending | TransactionOutcome::Unknown => {
+            Err(MystikoCliError::AnyhowError(anyhow!(
+                "Row {} spend transaction {} is still pending or unknown on chain, \
+                it is not retried",
+                result.row,
+                transaction_hash.unwrap_or_default()
+            )))
+        }
+    }
+}
+
+async fn check_spend_batch_balances<'a, F, S, W, A, D, X, Y, R, I>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    rows: I,
+) -> Result<Vec<SpendBatchBalance>, MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<R::Error>,
+    I: Iterator<Item = &'a SpendManifestRow>,
+{
+    let mut required: BTreeMap<(u64, String, Option<i32>), f64> = BTreeMap::new();
+    for row in rows {
+        let bridge_type = parse_bridge_type(row.bridge_type.clone());
+        *required
+            .entry((row.chain_id, row.asset_symbol.clone(), bridge_type))
+            .or_default() += row.amount;
+    }
+    let mut balances = vec![];
+    for ((chain_id, asset_symbol, bridge_type), required) in required.into_iter() {
+        let options = BalanceOptions::builder()
+            .chain_ids(vec![chain_id])
+            .asset_symbols(vec![asset_symbol.clone()])
+            .bridge_types(bridge_type.into_iter().collect::<Vec<_>>())
+            .build();
+        let available = mystiko
+            .scanner
+            .balance(options)
+            .await?
+            .balances
+            .iter()
+            .filter(|balance| balance.asset_symbol == asset_symbol)
+            .map(|balance| balance.unspent)
+            .sum::<f64>();
+        if available < required {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Insufficient private balance of {} on chain {} for the batch: \
+                required {}, available {}",
+                asset_symbol,
+                chain_id,
+                required,
+                available
+            )));
+        }
+        balances.push(SpendBatchBalance {
+            chain_id,
+            asset_symbol,
+            bridge_type,
+            required,
+            available,
+        });
+    }
+    Ok(balances)
+}
+
+fn create_quote_spend_options(
+    row: &SpendManifestRow,
+    args: &SpendBatchCommand,
+) -> QuoteSpendOptions {
+    QuoteSpendOptions::builder()
+        .chain_id(row.chain_id)
+        .asset_symbol(row.asset_symbol.clone())
+        .spend_type(parse_spend_type(row.spend_type.clone()))
+        .bridge_type(parse_bridge_type(row.bridge_type.clone()))
+        .version(row.version)
+        .amount(row.amount)
+        .query_timeout_ms(args.query_timeout_ms)
+        .use_relayer(args.relayer.is_some())
+        .build()
+}
+
+fn create_spend_options(
+    row: &SpendManifestRow,
+    args: &SpendBatchCommand,
+) -> Result<CreateSpendOptions, MystikoCliError> {
+    Ok(CreateSpendOptions::builder()
+        .chain_id(row.chain_id)
+        .asset_symbol(row.asset_symbol.clone())
+        .spend_type(parse_spend_type(row.spend_type.clone()))
+        .bridge_type(parse_bridge_type(row.bridge_type.clone()))
+        .version(row.version)
+        .recipient(row.recipient.clone())
+        .wallet_password(expose_wallet_password(&args.password)?)
+        .amount(row.amount)
+        .rollup_fee_amount(row.rollup_fee)
+        .gas_relayer(args.relayer.clone())
+        .query_timeout_ms(args.query_timeout_ms)
+        .build())
+}
+
+fn update_spend_row_result(result: &mut BatchRowResult, spend: &Spend) {
+    result.document_id = Some(spend.id.clone());
+    result.transaction_hash = spend.transaction_hash.clone();
+    result.error = spend.error_message.clone();
+    result.status = spend_batch_row_status(spend.status);
+}
+
+pub fn spend_batch_row_status(status: i32) -> BatchRowStatus {
+    match SpendStatus::from_i32(status).unwrap_or_default() {
+        SpendStatus::Unspecified => BatchRowStatus::Created,
+        SpendStatus::Pending => BatchRowStatus::Sending,
+        SpendStatus::Failed => BatchRowStatus::Failed,
+        _ => BatchRowStatus::Sent,
+    }
+}
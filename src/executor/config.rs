@@ -0,0 +1,395 @@
+use crate::{
+    print_json, ConfigCommand, ConfigCommands, ConfigDiffCommand, ConfigValidateCommand,
+    MystikoCliError,
+};
+use anyhow::anyhow;
+use mystiko_config::MystikoConfig;
+use mystiko_protos::common::v1::ConfigOptions;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+const DEPOSIT_CONTRACTS: &str = "depositContracts";
+const POOL_CONTRACTS: &str = "poolContracts";
+const LOOP_BRIDGE_TYPE: &str = "loop";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigValidateReport {
+    pub file: String,
+    pub version: Option<String>,
+    pub valid: bool,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigDiffReport {
+    pub added_chains: Vec<u64>,
+    pub removed_chains: Vec<u64>,
+    pub added_contracts: Vec<ConfigContract>,
+    pub removed_contracts: Vec<ConfigContract>,
+    pub changed_parameters: Vec<ConfigParameterChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigContract {
+    pub chain_id: u64,
+    pub contract_type: String,
+    pub address: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigParameterChange {
+    pub path: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+pub async fn execute_config_command(
+    args: ConfigCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    match args.commands {
+        ConfigCommands::Validate(args) => execute_config_validate_command(args, compact_json).await,
+        ConfigCommands::Diff(args) => execute_config_diff_command(args, compact_json).await,
+    }
+}
+
+pub async fn execute_config_validate_command(
+    args: ConfigValidateCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    let report = validate_config_file(&args.file).await?;
+    print_json(&report, compact_json)?;
+    if report.valid {
+        Ok(())
+    } else {
+        Err(MystikoCliError::ConfigError(anyhow!(
+            "config file {} is invalid",
+            args.file
+        )))
+    }
+}
+
+pub async fn execute_config_diff_command(
+    args: ConfigDiffCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    let report = diff_config_files(&args.a, &args.b).await?;
+    print_json(&report, compact_json)
+}
+
+pub async fn validate_config_file(file: &str) -> Result<ConfigValidateReport, MystikoCliError> {
+    let mut errors = vec![];
+    let options = ConfigOptions::builder().file_path(file.to_string()).build();
+    let version = match MystikoConfig::from_options(options).await {
+        Ok(config) => Some(config.version().to_string()),
+        Err(err) => {
+            errors.push(err.to_string());
+            None
+        }
+    };
+    let json = read_config_json(file).await?;
+    errors.extend(check_config_json(&json));
+    Ok(ConfigValidateReport {
+        file: file.to_string(),
+        version,
+        valid: errors.is_empty(),
+        errors,
+    })
+}
+
+pub async fn diff_config_files(a: &str, b: &str) -> Result<ConfigDiffReport, MystikoCliError> {
+    let old_json = read_config_json(a).await?;
+    let new_json = read_config_json(b).await?;
+    Ok(diff_config_json(&old_json, &new_json))
+}
+
+async fn read_config_json(file: &str) -> Result<Value, MystikoCliError> {
+    let content = tokio::fs::read_to_string(file).await?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn check_config_json(json: &Value) -> Vec<String> {
+    let mut errors = vec![];
+    let chains = json_array(json, "chains");
+    if chains.is_empty() {
+        errors.push("no chains are configured".to_string());
+    }
+    let bridge_types = json_array(json, "bridges")
+        .into_iter()
+        .filter_map(|bridge| json_str(bridge, "type"))
+        .collect::<HashSet<_>>();
+    let mut chain_ids = HashSet::new();
+    let mut chains_by_id = BTreeMap::new();
+    for chain in chains.iter() {
+        let Some(chain_id) = chain.get("chainId").and_then(Value::as_u64) else {
+            errors.push("chain is missing chainId".to_string());
+            continue;
+        };
+        if !chain_ids.insert(chain_id) {
+            errors.push(format!("duplicate chain {}", chain_id));
+        }
+        chains_by_id.insert(chain_id, *chain);
+    }
+    for (chain_id, chain) in chains_by_id.iter() {
+        if json_array(chain, "providers").is_empty() {
+            errors.push(format!("chain {} has no providers", chain_id));
+        }
+        let pool_addresses = contract_addresses(chain, POOL_CONTRACTS);
+        let mut addresses = HashSet::new();
+        for address in contract_addresses(chain, DEPOSIT_CONTRACTS)
+            .into_iter()
+            .chain(pool_addresses.iter().cloned())
+        {
+            if !addresses.insert(address.clone()) {
+                errors.push(format!(
+                    "chain {} has duplicate contract {}",
+                    chain_id, address
+                ));
+            }
+        }
+        for deposit in json_array(chain, DEPOSIT_CONTRACTS) {
+            let address = contract_address(deposit).unwrap_or_default();
+            if let Some(pool_address) = json_str(deposit, "poolAddress") {
+                if !pool_addresses.contains(&pool_address.to_lowercase()) {
+                    errors.push(format!(
+                        "deposit contract {} on chain {} references unknown pool contract {}",
+                        address, chain_id, pool_address
+                    ));
+                }
+            }
+            let bridge_type = json_str(deposit, "bridgeType").unwrap_or(LOOP_BRIDGE_TYPE);
+            if bridge_type != LOOP_BRIDGE_TYPE && !bridge_types.contains(bridge_type) {
+                errors.push(format!(
+                    "deposit contract {} on chain {} uses unconfigured bridge {}",
+                    address, chain_id, bridge_type
+                ));
+            }
+            errors.extend(check_bridge_pair(
+                *chain_id,
+                &address,
+                bridge_type,
+                deposit,
+                &chains_by_id,
+            ));
+        }
+    }
+    errors
+}
+
+fn check_bridge_pair(
+    chain_id: u64,
+    address: &str,
+    bridge_type: &str,
+    deposit: &Value,
+    chains_by_id: &BTreeMap<u64, &Value>,
+) -> Vec<String> {
+    let peer_chain_id = deposit.get("peerChainId").and_then(Value::as_u64);
+    let peer_address = json_str(deposit, "peerContractAddress");
+    let (peer_chain_id, peer_address) = match (peer_chain_id, peer_address) {
+        (None, None) => return vec![],
+        (Some(peer_chain_id), Some(peer_address)) => (peer_chain_id, peer_address.to_lowercase()),
+        _ => {
+            return vec![format!(
+                "deposit contract {} on chain {} must set both peerChainId and peerContractAddress",
+                address, chain_id
+            )]
+        }
+    };
+    let Some(peer_chain) = chains_by_id.get(&peer_chain_id) else {
+        return vec![format!(
+            "deposit contract {} on chain {} references unknown peer chain {}",
+            address, chain_id, peer_chain_id
+        )];
+    };
+    let Some(peer_deposit) = json_array(peer_chain, DEPOSIT_CONTRACTS)
+        .into_iter()
+        .find(|peer| contract_address(peer).as_deref() == Some(peer_address.as_str()))
+    else {
+        return vec![format!(
+            "deposit contract {} on chain {} references unknown peer contract {} on chain {}",
+            address, chain_id, peer_address, peer_chain_id
+        )];
+    };
+    let mut errors = vec![];
+    let points_back = peer_deposit.get("peerChainId").and_then(Value::as_u64) == Some(chain_id)
+        && json_str(peer_deposit, "peerContractAddress").map(str::to_lowercase)
+            == Some(address.to_string());
+    if !points_back {
+        errors.push(format!(
+            "peer contract {} on chain {} does not point back to deposit contract {} on chain {}",
+            peer_address, peer_chain_id, address, chain_id
+        ));
+    }
+    if json_str(peer_deposit, "bridgeType").unwrap_or(LOOP_BRIDGE_TYPE) != bridge_type {
+        errors.push(format!(
+            "bridge type of deposit contract {} on chain {} differs from its peer contract {} on chain {}",
+            address, chain_id, peer_address, peer_chain_id
+        ));
+    }
+    errors
+}
+
+fn diff_config_json(old_json: &Value, new_json: &Value) -> ConfigDiffReport {
+    let mut report = ConfigDiffReport::default();
+    diff_objects(
+        "",
+        old_json,
+        new_json,
+        &["chains"],
+        &mut report.changed_parameters,
+    );
+    let old_chains = chains_by_id(old_json);
+    let new_chains = chains_by_id(new_json);
+    report.removed_chains = old_chains
+        .keys()
+        .filter(|chain_id| !new_chains.contains_key(chain_id))
+        .copied()
+        .collect();
+    report.added_chains = new_chains
+        .keys()
+        .filter(|chain_id| !old_chains.contains_key(chain_id))
+        .copied()
+        .collect();
+    for (chain_id, old_chain) in old_chains.iter() {
+        let Some(new_chain) = new_chains.get(chain_id) else {
+            continue;
+        };
+        let path = format!("chains[{}]", chain_id);
+        diff_objects(
+            &path,
+            old_chain,
+            new_chain,
+            &[DEPOSIT_CONTRACTS, POOL_CONTRACTS],
+            &mut report.changed_parameters,
+        );
+        for contract_type in [DEPOSIT_CONTRACTS, POOL_CONTRACTS] {
+            let old_contracts = contracts_by_address(old_chain, contract_type);
+            let new_contracts = contracts_by_address(new_chain, contract_type);
+            let to_contract = |address: &String| ConfigContract {
+                chain_id: *chain_id,
+                contract_type: contract_type.to_string(),
+                address: address.clone(),
+            };
+            report.removed_contracts.extend(
+                old_contracts
+                    .keys()
+                    .filter(|address| !new_contracts.contains_key(*address))
+                    .map(to_contract),
+            );
+            report.added_contracts.extend(
+                new_contracts
+                    .keys()
+                    .filter(|address| !old_contracts.contains_key(*address))
+                    .map(to_contract),
+            );
+            for (address, old_contract) in old_contracts.iter() {
+                if let Some(new_contract) = new_contracts.get(address) {
+                    diff_values(
+                        format!("{}.{}[{}]", path, contract_type, address),
+                        old_contract,
+                        new_contract,
+                        &mut report.changed_parameters,
+                    );
+                }
+            }
+        }
+    }
+    report
+}
+
+fn diff_objects(
+    path: &str,
+    old_value: &Value,
+    new_value: &Value,
+    skipped_keys: &[&str],
+    changes: &mut Vec<ConfigParameterChange>,
+) {
+    let keys = object_keys(old_value)
+        .into_iter()
+        .chain(object_keys(new_value))
+        .filter(|key| !skipped_keys.contains(&key.as_str()))
+        .collect::<BTreeSet<_>>();
+    for key in keys {
+        diff_values(
+            join_path(path, &key),
+            old_value.get(&key).unwrap_or(&Value::Null),
+            new_value.get(&key).unwrap_or(&Value::Null),
+            changes,
+        );
+    }
+}
+
+fn diff_values(
+    path: String,
+    old_value: &Value,
+    new_value: &Value,
+    changes: &mut Vec<ConfigParameterChange>,
+) {
+    if old_value.is_object() && new_value.is_object() {
+        diff_objects(&path, old_value, new_value, &[], changes);
+    } else if old_value != new_value {
+        changes.push(ConfigParameterChange {
+            path,
+            old: old_value.clone(),
+            new: new_value.clone(),
+        });
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn object_keys(value: &Value) -> Vec<String> {
+    value
+        .as_object()
+        .map(|object| object.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn chains_by_id(json: &Value) -> BTreeMap<u64, &Value> {
+    json_array(json, "chains")
+        .into_iter()
+        .filter_map(|chain| {
+            chain
+                .get("chainId")
+                .and_then(Value::as_u64)
+                .map(|chain_id| (chain_id, chain))
+        })
+        .collect()
+}
+
+fn contracts_by_address<'a>(chain: &'a Value, key: &str) -> BTreeMap<String, &'a Value> {
+    json_array(chain, key)
+        .into_iter()
+        .filter_map(|contract| contract_address(contract).map(|address| (address, contract)))
+        .collect()
+}
+
+fn contract_addresses(chain: &Value, key: &str) -> Vec<String> {
+    json_array(chain, key)
+        .into_iter()
+        .filter_map(contract_address)
+        .collect()
+}
+
+fn contract_address(contract: &Value) -> Option<String> {
+    json_str(contract, "address").map(str::to_lowercase)
+}
+
+fn json_array<'a>(value: &'a Value, key: &str) -> Vec<&'a Value> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|values| values.iter().collect())
+        .unwrap_or_default()
+}
+
+fn json_str<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+    value.get(key).and_then(Value::as_str)
+}
@@ -1,20 +1,73 @@
 use crate::args::{parse_bridge_types, parse_deposit_statuses};
+use crate::executor::{
+    check_sent_after, check_transaction, create_recover_provider, execute_deposit_batch_command,
+    replace_transaction, split_deposit_amount, split_trade_offs, status_name, status_reached,
+    sync_chains, update_row_result, wait_for_status, DepositSplitPiece, DepositSplitPlan,
+    DepositSplitReport, RecoveryAction, RecoveryResult, WaitState, DEPOSIT_STATUS_PROGRESSION,
+};
 use crate::{
-    print_json, DepositCommand, DepositCommands, DepositCreateCommand, DepositListCommand,
-    DepositQuoteCommand, MystikoCliError,
+    confirm_execution, create_capturing_signer, default_results_path, print_json, read_manifest,
+    replace_private_file, BatchResults, BatchRowResult, BatchRowStatus, CapturedTransaction,
+    CommandSigner, DepositCommand, DepositCommands, DepositCreateCommand, DepositListCommand,
+    DepositManifestRow, DepositPrepareCommand, DepositQuoteCommand, DepositRecoverCommand,
+    DepositWaitCommand, MystikoCliError, OfflineDocumentType, OfflineTransaction,
+    OfflineTransactionType, OfflineTransactions, ReplacementStore, TransactionReplaceCommand,
+    TransactionReplacementRecord,
 };
-use mystiko_core::{DepositColumn, DepositHandler, Mystiko};
+use anyhow::anyhow;
+use ethers_core::abi::{encode, Token};
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, Bytes, Eip1559TransactionRequest, U256};
+use ethers_core::utils::{id, parse_units};
+use ethers_providers::{Http, Middleware, Provider};
+use mystiko_core::{DepositColumn, DepositHandler, Mystiko, ScannerHandler, SynchronizerHandler};
+use mystiko_protos::common::v1::BridgeType;
 use mystiko_protos::core::document::v1::Deposit;
 use mystiko_protos::core::handler::v1::{
     CreateDepositOptions, DepositQuote, DepositSummary, FixDepositStatusOptions,
     QuoteDepositOptions, SendDepositOptions,
 };
+use mystiko_protos::core::scanner::v1::{
+    AssetImportOptions, AssetImportResult, AssetsByChain, AssetsOptions, BalanceOptions,
+    BalanceResult, ScannerResetOptions, ScannerResetResult, ScannerScanOptions, ScannerScanResult,
+    ScannerSyncOptions, ScannerSyncResult,
+};
+use mystiko_protos::core::synchronizer::v1::{
+    SynchronizerResetOptions, SynchronizerStatus, SynchronizerSyncOptions,
+};
 use mystiko_protos::core::v1::transaction::Transaction as EnumTransaction;
-use mystiko_protos::core::v1::{Eip1559Transaction, Transaction};
+use mystiko_protos::core::v1::{DepositStatus, Eip1559Transaction, Transaction};
 use mystiko_protos::storage::v1::{
     Condition, ConditionOperator, Order, OrderBy, QueryFilter, SubFilter,
 };
 use mystiko_storage::{DocumentColumn, StatementFormatter, Storage};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+const ALLOWANCE_SELECTOR: [u8; 4] = [0xdd, 0x62, 0xed, 0x3e];
+const APPROVE_SELECTOR: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+const DEPOSIT_LOOP_SIGNATURE: &str = "deposit((uint256,uint256,uint256,uint128,bytes,uint256))";
+const DEPOSIT_BRIDGE_SIGNATURE: &str =
+    "deposit((uint256,uint256,uint256,uint128,bytes,uint256,uint256,uint256))";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DepositConfirmation {
+    pub quote: DepositQuote,
+    pub summary: DepositSummary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DepositDryRun {
+    pub quote: DepositQuote,
+    pub summary: DepositSummary,
+    pub contract_address: String,
+    pub pool_address: String,
+    pub transactions: Vec<CapturedTransaction>,
+    pub requires_approve: bool,
+}
 
 pub async fn execute_deposit_command<F, S, W, A, D, X, Y, R>(
     mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
@@ -33,7 +86,22 @@ where
         SendDepositOptions,
         FixDepositStatusOptions,
     >,
-    MystikoCliError: From<D::Error>,
+    Y: SynchronizerHandler<SynchronizerSyncOptions, SynchronizerStatus, SynchronizerResetOptions>,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<D::Error> + From<Y::Error> + From<R::Error>,
 {
     match args.commands {
         DepositCommands::Quote(args) => {
@@ -42,6 +110,22 @@ where
         DepositCommands::Create(args) => {
             execute_deposit_create_command(mystiko, args, compact_json).await
         }
+        DepositCommands::Batch(args) => {
+            execute_deposit_batch_command(mystiko, args, compact_json).await
+        }
+        DepositCommands::Prepare(args) => {
+            execute_deposit_prepare_command(mystiko, args, compact_json).await
+        }
+        DepositCommands::SpeedUp(args) => {
+            execute_deposit_replace_command(mystiko, args, false, compact_json).await
+        }
+        DepositCommands::Cancel(args) => {
+            execute_deposit_replace_command(mystiko, args, true, compact_json).await
+        }
+        DepositCommands::Recover(args) => {
+            execute_deposit_recover_command(mystiko, args, compact_json).await
+        }
+        DepositCommands::Wait(args) => execute_deposit_wait_command(mystiko, args).await,
         DepositCommands::List(args) => {
             execute_deposit_list_command(mystiko, args, compact_json).await
         }
@@ -90,39 +174,860 @@ where
     >,
     MystikoCliError: From<D::Error>,
 {
+    if args.dry_run {
+        return execute_deposit_dry_run(mystiko, args, compact_json).await;
+    }
+    if args.split.is_some() {
+        return execute_deposit_split(mystiko, args, compact_json).await;
+    }
+    let signer = match &args.signer_command {
+        Some(signer_command) => Some(Arc::new(
+            CommandSigner::new(
+                signer_command,
+                args.signer_provider.as_deref().unwrap_or_default(),
+                args.signer_timeout_ms,
+            )
+            .await?,
+        )),
+        None => None,
+    };
+    if !args.yes {
+        let confirmation = DepositConfirmation {
+            quote: mystiko.deposits.quote(args.clone().into()).await?,
+            summary: mystiko.deposits.summary(args.clone().into()).await?,
+        };
+        confirm_execution(&confirmation)?;
+    }
     let deposit = mystiko.deposits.create(args.clone().into()).await?;
-    let tx = if deposit.chain_id == 1 {
-        let tx1 = Eip1559Transaction::builder()
-            .max_priority_fee_per_gas("5000000".to_string())
-            .build();
-        let tx2 = EnumTransaction::Eip1559Transaction(tx1);
-        Some(Transaction::builder().transaction(tx2).build())
-    } else if deposit.chain_id == 137 {
-        let tx1 = Eip1559Transaction::builder()
-            .max_priority_fee_per_gas("30000000000".to_string())
-            .build();
-        let tx2 = EnumTransaction::Eip1559Transaction(tx1);
-        Some(Transaction::builder().transaction(tx2).build())
+    let options = create_send_deposit_options(&args, &deposit);
+    let deposit = match signer {
+        Some(signer) => mystiko.deposits.send_with_signer(options, signer).await?,
+        None => mystiko.deposits.send(options).await?,
+    };
+    print_json(&deposit, compact_json)
+}
+
+async fn execute_deposit_split<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: DepositCreateCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    MystikoCliError: From<D::Error>,
+{
+    let plan_path = args.split_plan.clone().unwrap_or_default();
+    let results_path = default_results_path(&plan_path);
+    let quote = mystiko.deposits.quote(args.clone().into()).await?;
+    let resuming = tokio::fs::try_exists(&plan_path).await?;
+    let rows: Vec<DepositManifestRow> = if resuming {
+        let rows: Vec<DepositManifestRow> = read_manifest(&plan_path).await?;
+        let total_amount = rows.iter().map(|row| row.amount).sum::<f64>();
+        let matches = rows.iter().all(|row| {
+            row.chain_id == args.chain_id
+                && row.asset_symbol == args.asset_symbol
+                && row.shielded_address == args.shielded_address
+        });
+        if !matches || (total_amount - args.amount).abs() > 1e-9 * args.amount.max(1_f64) {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Split plan {} does not match the deposit, remove it to plan again",
+                plan_path
+            )));
+        }
+        rows
+    } else {
+        let mut pieces = split_deposit_amount(args.amount, &quote)?;
+        pieces.shuffle(&mut rand::thread_rng());
+        pieces
+            .into_iter()
+            .map(|piece| DepositManifestRow {
+                chain_id: args.chain_id,
+                asset_symbol: args.asset_symbol.clone(),
+                amount: piece.amount,
+                shielded_address: args.shielded_address.clone(),
+                bridge_type: args.bridge_type.clone(),
+                dst_chain_id: args.dst_chain_id,
+                rollup_fee: args.rollup_fee,
+                bridge_fee: args.bridge_fee,
+                executor_fee: args.executor_fee,
+            })
+            .collect()
+    };
+    let pieces = rows
+        .iter()
+        .map(|row| DepositSplitPiece {
+            amount: row.amount,
+            standard: quote.recommended_amounts.contains(&row.amount),
+        })
+        .collect::<Vec<_>>();
+    let trade_offs = split_trade_offs(&pieces, &quote, args.split_max_delay);
+    if !args.yes {
+        let plan = DepositSplitPlan {
+            quote,
+            total_amount: args.amount,
+            pieces,
+            max_delay_secs: args.split_max_delay.map(|max_delay| max_delay.as_secs()),
+            trade_offs: trade_offs.clone(),
+        };
+        confirm_execution(&plan)?;
+    }
+    if !resuming {
+        replace_private_file(&plan_path, &serde_json::to_string_pretty(&rows)?).await?;
+    }
+    let mut results = BatchResults::read_or_create(
+        &results_path,
+        OfflineDocumentType::Deposit,
+        &plan_path,
+        rows.len(),
+    )
+    .await?;
+    results.write(&results_path).await?;
+
+    let signer = match &args.signer_command {
+        Some(signer_command) => Some(Arc::new(
+            CommandSigner::new(
+                signer_command,
+                args.signer_provider.as_deref().unwrap_or_default(),
+                args.signer_timeout_ms,
+            )
+            .await?,
+        )),
+        None => None,
+    };
+    let mut report = DepositSplitReport {
+        deposits: vec![],
+        trade_offs,
+    };
+    for (index, row) in rows.iter().enumerate() {
+        let existing = match &results.rows[index].document_id {
+            Some(deposit_id) => mystiko.deposits.find_by_id(deposit_id.clone()).await?,
+            None => None,
+        };
+        if let Some(deposit) = &existing {
+            update_row_result(&mut results.rows[index], deposit);
+        }
+        let existing = match (existing, results.rows[index].status) {
+            (Some(deposit), BatchRowStatus::Sending | BatchRowStatus::Sent) => {
+                report.deposits.push(deposit);
+                continue;
+            }
+            (None, BatchRowStatus::Sending | BatchRowStatus::Sent) => {
+                return Err(MystikoCliError::AnyhowError(anyhow!(
+                    "Piece {} was already sent as deposit {} which is missing from the database, \
+                    check it on chain before resuming the split",
+                    index + 1,
+                    results.rows[index].document_id.clone().unwrap_or_default()
+                )))
+            }
+            (Some(deposit), BatchRowStatus::Created) => Some(deposit),
+            _ => None,
+        };
+        if let Some(max_delay) = args.split_max_delay.filter(|_| index > 0) {
+            let delay = rand::thread_rng().gen_range(Duration::ZERO..=max_delay);
+            tokio::time::sleep(delay).await;
+        }
+        let mut piece_args = args.clone();
+        piece_args.amount = row.amount;
+        let deposit = match existing {
+            Some(deposit) => deposit,
+            None => {
+                results.rows[index] = BatchRowResult::new(index + 1);
+                match mystiko.deposits.create(piece_args.clone().into()).await {
+                    Ok(deposit) => deposit,
+                    Err(err) => {
+                        let err = MystikoCliError::from(err);
+                        results.rows[index].status = BatchRowStatus::Failed;
+                        results.rows[index].error = Some(err.to_string());
+                        results.write(&results_path).await?;
+                        print_json(&report, compact_json)?;
+                        return Err(err);
+                    }
+                }
+            }
+        };
+        update_row_result(&mut results.rows[index], &deposit);
+        results.rows[index].status = BatchRowStatus::Sending;
+        results.write(&results_path).await?;
+        let options = create_send_deposit_options(&piece_args, &deposit);
+        let sent = match &signer {
+            Some(signer) => {
+                mystiko
+                    .deposits
+                    .send_with_signer(options, signer.clone())
+                    .await
+            }
+            None => mystiko.deposits.send(options).await,
+        };
+        match sent {
+            Ok(deposit) => {
+                update_row_result(&mut results.rows[index], &deposit);
+                results.write(&results_path).await?;
+                report.deposits.push(deposit);
+            }
+            Err(err) => {
+                let err = MystikoCliError::from(err);
+                if let Some(deposit) = mystiko.deposits.find_by_id(deposit.id.clone()).await? {
+                    update_row_result(&mut results.rows[index], &deposit);
+                }
+                results.rows[index].error = Some(err.to_string());
+                results.write(&results_path).await?;
+                print_json(&report, compact_json)?;
+                return Err(err);
+            }
+        }
+    }
+    print_json(&report, compact_json)
+}
+
+async fn execute_deposit_dry_run<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: DepositCreateCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    MystikoCliError: From<D::Error>,
+{
+    let quote = mystiko.deposits.quote(args.clone().into()).await?;
+    check_quote_amount(
+        args.amount,
+        quote.min_amount,
+        quote.max_amount,
+        &quote.asset_symbol,
+    )?;
+    let summary = mystiko.deposits.summary(args.clone().into()).await?;
+    let deposit = mystiko.deposits.create(args.clone().into()).await?;
+    let signer = Arc::new(
+        create_capturing_signer(
+            args.private_key.as_ref(),
+            args.signer_command.as_deref(),
+            args.signer_timeout_ms,
+        )
+        .await?,
+    );
+    let sent = mystiko
+        .deposits
+        .send_with_signer(create_send_deposit_options(&args, &deposit), signer.clone())
+        .await;
+    let transactions = signer.transactions();
+    if transactions.is_empty() {
+        sent?;
+    }
+    let contract_address = deposit.contract_address.parse::<Address>().ok();
+    let requires_approve = deposit.asset_address.is_some()
+        && !transactions
+            .iter()
+            .any(|captured| captured.transaction.to_addr() == contract_address.as_ref());
+    if requires_approve {
+        eprintln!(
+            "Warning: the deposit needs an asset approve first, only the approve transaction \
+             is estimated and the deposit transaction is not included"
+        );
+    }
+    let dry_run = DepositDryRun {
+        quote,
+        summary,
+        contract_address: deposit.contract_address,
+        pool_address: deposit.pool_address,
+        transactions,
+        requires_approve,
+    };
+    print_json(&dry_run, compact_json)
+}
+
+pub async fn execute_deposit_prepare_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: DepositPrepareCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    MystikoCliError: From<D::Error>,
+{
+    let from = parse_address(&args.from)?;
+    let provider = Provider::<Http>::try_from(args.signer_provider.as_str())
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    let deposit = match &args.deposit_id {
+        Some(deposit_id) => mystiko
+            .deposits
+            .find_by_id(deposit_id.clone())
+            .await?
+            .ok_or_else(|| {
+                MystikoCliError::AnyhowError(anyhow!("Deposit {} not found", deposit_id))
+            })?,
+        None => mystiko.deposits.create(args.clone().into()).await?,
+    };
+    if deposit.transaction_hash.is_some() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Deposit {} has no transaction left to prepare",
+            deposit.id
+        )));
+    }
+    let native_decimals = mystiko
+        .config
+        .find_chain(deposit.chain_id)
+        .map(|chain| chain.asset_decimals())
+        .ok_or_else(|| {
+            MystikoCliError::AnyhowError(anyhow!("Chain {} is not configured", deposit.chain_id))
+        })?;
+    let amounts = DepositAmounts::new(&deposit, native_decimals)?;
+    let contract_address = parse_address(&deposit.contract_address)?;
+    let (max_fee_per_gas, max_priority_fee_per_gas) =
+        estimate_transaction_fees(&provider, deposit.chain_id).await?;
+    let build = |to: Address, data: Bytes, value: U256| {
+        TypedTransaction::Eip1559(
+            Eip1559TransactionRequest::new()
+                .from(from)
+                .to(to)
+                .data(data)
+                .value(value)
+                .chain_id(deposit.chain_id)
+                .max_fee_per_gas(max_fee_per_gas)
+                .max_priority_fee_per_gas(max_priority_fee_per_gas),
+        )
+    };
+
+    let mut transactions = vec![];
+    if let Some(asset_address) = &deposit.asset_address {
+        let asset_address = parse_address(asset_address)?;
+        let data = [
+            ALLOWANCE_SELECTOR.to_vec(),
+            encode(&[Token::Address(from), Token::Address(contract_address)]),
+        ]
+        .concat();
+        let allowance = provider
+            .call(&build(asset_address, data.into(), U256::zero()), None)
+            .await
+            .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+        if U256::from_big_endian(&allowance[..allowance.len().min(32)]) < amounts.asset_total {
+            let data = [
+                APPROVE_SELECTOR.to_vec(),
+                encode(&[
+                    Token::Address(contract_address),
+                    Token::Uint(amounts.asset_total),
+                ]),
+            ]
+            .concat();
+            let mut approve = build(asset_address, data.into(), U256::zero());
+            let gas = provider
+                .estimate_gas(&approve, None)
+                .await
+                .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+            approve.set_gas(gas);
+            transactions.push((OfflineTransactionType::AssetApprove, approve));
+        }
+    }
+    let mut deposit_transaction = build(
+        contract_address,
+        encode_deposit_call(&deposit, &amounts)?,
+        amounts.value,
+    );
+    let gas = if transactions.is_empty() {
+        provider
+            .estimate_gas(&deposit_transaction, None)
+            .await
+            .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+    } else {
+        args.deposit_gas_limit.map(U256::from).ok_or_else(|| {
+            MystikoCliError::AnyhowError(anyhow!(
+                "Deposit {} can only be estimated after its approve is mined, \
+                pass --deposit-gas-limit to prepare both transactions",
+                deposit.id
+            ))
+        })?
+    };
+    deposit_transaction.set_gas(gas);
+    transactions.push((OfflineTransactionType::Deposit, deposit_transaction));
+
+    let transactions = transactions
+        .into_iter()
+        .map(|(transaction_type, transaction)| {
+            OfflineTransaction::new(
+                transaction_type,
+                CapturedTransaction {
+                    chain_id: deposit.chain_id,
+                    from,
+                    transaction,
+                },
+            )
+        })
+        .collect();
+    let mut transactions =
+        OfflineTransactions::new(OfflineDocumentType::Deposit, deposit.id, transactions);
+    transactions
+        .assign_nonces(args.nonce, Some(&args.signer_provider))
+        .await?;
+    transactions.write(&args.out).await?;
+    print_json(&transactions, compact_json)
+}
+
+struct DepositAmounts {
+    amount: U256,
+    rollup_fee: U256,
+    bridge_fee: U256,
+    executor_fee: U256,
+    asset_total: U256,
+    value: U256,
+}
+
+impl DepositAmounts {
+    fn new(deposit: &Deposit, native_decimals: u32) -> Result<Self, MystikoCliError> {
+        let amount = parse_decimal_amount(deposit.amount, deposit.asset_decimals)?;
+        let rollup_fee = parse_decimal_amount(deposit.rollup_fee_amount, deposit.asset_decimals)?;
+        let executor_fee =
+            parse_decimal_amount(deposit.executor_fee_amount, deposit.asset_decimals)?;
+        let bridge_fee = parse_decimal_amount(deposit.bridge_fee_amount, native_decimals)?;
+        let asset_total = amount + rollup_fee + executor_fee;
+        let value = match deposit.asset_address {
+            Some(_) => bridge_fee,
+            None => asset_total + bridge_fee,
+        };
+        Ok(DepositAmounts {
+            amount,
+            rollup_fee,
+            bridge_fee,
+            executor_fee,
+            asset_total,
+            value,
+        })
+    }
+}
+
+fn encode_deposit_call(
+    deposit: &Deposit,
+    amounts: &DepositAmounts,
+) -> Result<Bytes, MystikoCliError> {
+    let mut request = vec![
+        Token::Uint(amounts.amount),
+        Token::Uint(parse_uint(&deposit.commitment_hash, "commitment hash")?),
+        Token::Uint(parse_uint(&deposit.hash_k, "hash k")?),
+        Token::Uint(parse_uint(&deposit.random_s, "random s")?),
+        Token::Bytes(
+            deposit
+                .encrypted_note
+                .parse::<Bytes>()
+                .map_err(|_| {
+                    MystikoCliError::AnyhowError(anyhow!(
+                        "Invalid encrypted note of deposit {}",
+                        deposit.id
+                    ))
+                })?
+                .to_vec(),
+        ),
+    ];
+    let signature = if deposit.bridge_type == BridgeType::Loop as i32 {
+        request.push(Token::Uint(amounts.rollup_fee));
+        DEPOSIT_LOOP_SIGNATURE
+    } else {
+        request.extend([
+            Token::Uint(amounts.bridge_fee),
+            Token::Uint(amounts.executor_fee),
+            Token::Uint(amounts.rollup_fee),
+        ]);
+        DEPOSIT_BRIDGE_SIGNATURE
+    };
+    Ok([id(signature).to_vec(), encode(&[Token::Tuple(request)])]
+        .concat()
+        .into())
+}
+
+async fn estimate_transaction_fees(
+    provider: &Provider<Http>,
+    chain_id: u64,
+) -> Result<(U256, U256), MystikoCliError> {
+    let (max_fee_per_gas, max_priority_fee_per_gas) = provider
+        .estimate_eip1559_fees(None)
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    let max_priority_fee_per_gas = default_max_priority_fee_per_gas(chain_id)
+        .and_then(|fee| U256::from_dec_str(fee).ok())
+        .unwrap_or_default()
+        .max(max_priority_fee_per_gas);
+    Ok((
+        max_fee_per_gas.max(max_priority_fee_per_gas),
+        max_priority_fee_per_gas,
+    ))
+}
+
+pub(crate) fn parse_decimal_amount(amount: f64, decimals: u32) -> Result<U256, MystikoCliError> {
+    Ok(
+        parse_units(format!("{:.*}", decimals as usize, amount), decimals)
+            .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+            .into(),
+    )
+}
+
+fn parse_uint(value: &str, name: &str) -> Result<U256, MystikoCliError> {
+    match value.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None => U256::from_dec_str(value).ok(),
+    }
+    .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!("Invalid {} {}", name, value)))
+}
+
+pub async fn execute_deposit_replace_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: TransactionReplaceCommand,
+    cancel: bool,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    MystikoCliError: From<D::Error>,
+{
+    let mut deposit = mystiko
+        .deposits
+        .find_by_id(args.id.clone())
+        .await?
+        .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!("Deposit {} not found", args.id)))?;
+    let approving = deposit.status == DepositStatus::AssetApproving as i32;
+    let transaction_hash = if approving {
+        deposit.asset_approve_transaction_hash.clone()
+    } else if deposit.status == DepositStatus::SrcPending as i32 {
+        deposit.transaction_hash.clone()
     } else {
         None
+    }
+    .ok_or_else(|| {
+        MystikoCliError::AnyhowError(anyhow!("Deposit {} has no pending transaction", deposit.id))
+    })?;
+    let replacement = replace_transaction(&args, &transaction_hash, cancel).await?;
+    let replaced_by = replacement
+        .transaction_hash
+        .map(|transaction_hash| format!("{:?}", transaction_hash));
+    if !cancel {
+        if approving {
+            deposit.asset_approve_transaction_hash = replaced_by;
+        } else {
+            deposit.transaction_hash = replaced_by;
+        }
+        mystiko.deposits.update(deposit).await?;
+    }
+    print_json(&replacement, compact_json)
+}
+
+pub async fn execute_deposit_recover_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: DepositRecoverCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    MystikoCliError: From<D::Error>,
+{
+    let provider = create_recover_provider(&args.provider, args.chain_id).await?;
+    let replacements = match &args.replacements_path {
+        Some(replacements_path) => {
+            ReplacementStore::from_path(replacements_path)
+                .await?
+                .records()
+                .await?
+        }
+        None => vec![],
     };
+    let statuses = vec![
+        DepositStatus::AssetApproving as i32,
+        DepositStatus::AssetApproved as i32,
+        DepositStatus::SrcPending as i32,
+    ];
+    let mut sub_filters = vec![
+        SubFilter::equal(DepositColumn::ChainId, args.chain_id),
+        SubFilter::in_list(DepositColumn::Status, statuses),
+    ];
+    if let Some(ids) = &args.id {
+        if !ids.is_empty() {
+            sub_filters.push(SubFilter::in_list(DocumentColumn::Id, ids.clone()));
+        }
+    }
+    let deposits = mystiko
+        .deposits
+        .find(create_query_filter(sub_filters))
+        .await?;
+    for deposit in deposits.into_iter() {
+        let replacements = replacements
+            .iter()
+            .filter(|record| record.document_id == deposit.id)
+            .cloned()
+            .collect::<Vec<_>>();
+        let result = recover_deposit(mystiko, &args, &provider, &replacements, deposit).await?;
+        print_json(&result, compact_json)?;
+    }
+    Ok(())
+}
 
+async fn recover_deposit<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: &DepositRecoverCommand,
+    provider: &Provider<Http>,
+    replacements: &[TransactionReplacementRecord],
+    deposit: Deposit,
+) -> Result<RecoveryResult, MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    MystikoCliError: From<D::Error>,
+{
+    let previous_status = deposit_status_name(deposit.status);
+    let mut result = RecoveryResult {
+        id: deposit.id.clone(),
+        previous_status: previous_status.clone(),
+        status: previous_status,
+        action: RecoveryAction::Pending,
+        transaction_hash: None,
+        reason: None,
+    };
+    let (name, transaction_hash) = if deposit.status == DepositStatus::SrcPending as i32 {
+        ("deposit", deposit.transaction_hash.clone())
+    } else {
+        (
+            "asset approve",
+            deposit.asset_approve_transaction_hash.clone(),
+        )
+    };
+    result.transaction_hash = transaction_hash.clone();
+    let mut deposit = deposit;
+    if deposit.status != DepositStatus::AssetApproved as i32 {
+        let outcome =
+            check_transaction(provider, transaction_hash.as_deref(), replacements).await?;
+        if let Some(reason) = outcome.failure_reason(name, transaction_hash.as_deref()) {
+            let deposit = fix_deposit_status(
+                mystiko,
+                &deposit,
+                DepositStatus::Failed,
+                Some(reason.clone()),
+            )
+            .await?;
+            result.status = deposit_status_name(deposit.status);
+            result.action = RecoveryAction::Failed;
+            result.reason = Some(reason);
+            return Ok(result);
+        }
+        if let Some(reason) = outcome.pending_reason(name, transaction_hash.as_deref()) {
+            result.reason = Some(reason);
+            return Ok(result);
+        }
+        if deposit.status == DepositStatus::SrcPending as i32 {
+            let deposit =
+                fix_deposit_status(mystiko, &deposit, DepositStatus::Queued, None).await?;
+            result.status = deposit_status_name(deposit.status);
+            result.action = RecoveryAction::Confirmed;
+            return Ok(result);
+        }
+        deposit = fix_deposit_status(mystiko, &deposit, DepositStatus::AssetApproved, None).await?;
+        result.status = deposit_status_name(deposit.status);
+        result.action = RecoveryAction::Confirmed;
+    }
+    if let Some(reason) = check_sent_after(
+        provider,
+        "asset approve",
+        deposit.asset_approve_transaction_hash.as_deref(),
+    )
+    .await?
+    {
+        result.action = RecoveryAction::Skipped;
+        result.reason = Some(format!(
+            "{}, check the deposit on chain before sending it again",
+            reason
+        ));
+        return Ok(result);
+    }
+    if args.private_key.is_none() && args.signer_command.is_none() {
+        result.action = RecoveryAction::Skipped;
+        result.reason = Some(String::from(
+            "asset approve is confirmed, pass --private-key or --signer-command to send the deposit",
+        ));
+        return Ok(result);
+    }
+    if !args.yes {
+        confirm_execution(&deposit)?;
+    }
     let options = SendDepositOptions::builder()
-        .deposit_id(deposit.id)
-        .private_key(args.private_key)
-        .signer_provider(args.signer_provider)
+        .deposit_id(deposit.id.clone())
+        .private_key(
+            args.private_key
+                .as_ref()
+                .map(|private_key| private_key.expose_secret().to_string()),
+        )
+        .signer_provider(Some(args.provider.clone()))
         .query_timeout_ms(args.query_timeout_ms)
-        .asset_approve_confirmations(args.asset_approve_confirmations)
         .deposit_confirmations(args.deposit_confirmations)
-        .tx_wait_interval_ms(args.tx_wait_interval_ms)
-        .tx_wait_timeout_ms(args.tx_wait_timeout_ms)
-        .tx_send_timeout_ms(args.tx_send_timeout_ms)
-        .screening_message(args.screening_message)
-        .asset_approve_tx(tx.clone())
-        .deposit_tx(tx)
+        .deposit_tx(create_default_transaction(deposit.chain_id))
         .build();
-    let deposit = mystiko.deposits.send(options).await?;
-    print_json(&deposit, compact_json)
+    let deposit = match &args.signer_command {
+        Some(signer_command) => {
+            let signer =
+                CommandSigner::new(signer_command, &args.provider, args.signer_timeout_ms).await?;
+            mystiko
+                .deposits
+                .send_with_signer(options, Arc::new(signer))
+                .await?
+        }
+        None => mystiko.deposits.send(options).await?,
+    };
+    result.status = deposit_status_name(deposit.status);
+    result.action = RecoveryAction::Advanced;
+    result.transaction_hash = deposit.transaction_hash;
+    Ok(result)
+}
+
+async fn fix_deposit_status<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    deposit: &Deposit,
+    status: DepositStatus,
+    error_message: Option<String>,
+) -> Result<Deposit, MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    MystikoCliError: From<D::Error>,
+{
+    let options = FixDepositStatusOptions::builder()
+        .deposit_id(deposit.id.clone())
+        .status(status as i32)
+        .error_message(error_message)
+        .build();
+    Ok(mystiko.deposits.fix_status(options).await?)
+}
+
+fn deposit_status_name(status: i32) -> String {
+    let status = DepositStatus::from_i32(status).unwrap_or_default();
+    status_name(status.as_str_name(), "DEPOSIT_STATUS_")
+}
+
+pub async fn execute_deposit_wait_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: DepositWaitCommand,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    Y: SynchronizerHandler<SynchronizerSyncOptions, SynchronizerStatus, SynchronizerResetOptions>,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<D::Error> + From<Y::Error> + From<R::Error>,
+{
+    let until = args.until as i32;
+    let deposit = mystiko
+        .deposits
+        .find_by_id(args.id.clone())
+        .await?
+        .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!("Deposit {} not found", args.id)))?;
+    let mut chain_ids = vec![deposit.chain_id, deposit.dst_chain_id];
+    chain_ids.dedup();
+    let failed = DepositStatus::Failed as i32;
+    let progression = DEPOSIT_STATUS_PROGRESSION.map(|status| status as i32);
+    let poll = || async {
+        sync_chains(mystiko, chain_ids.clone(), &args.password).await?;
+        let deposit = mystiko
+            .deposits
+            .find_by_id(args.id.clone())
+            .await?
+            .ok_or_else(|| {
+                MystikoCliError::AnyhowError(anyhow!("Deposit {} not found", args.id))
+            })?;
+        Ok(WaitState {
+            status: deposit_status_name(deposit.status),
+            transaction_hash: deposit
+                .rollup_transaction_hash
+                .or(deposit.relay_transaction_hash)
+                .or(deposit.transaction_hash)
+                .or(deposit.asset_approve_transaction_hash),
+            error_message: deposit.error_message,
+            reached: status_reached(deposit.status, until, &progression),
+            failed: deposit.status == failed,
+        })
+    };
+    let until_name = deposit_status_name(until);
+    wait_for_status(&args.id, &until_name, args.timeout, args.interval, poll).await
 }
 
 pub async fn execute_deposit_list_command<F, S, W, A, D, X, Y, R>(
@@ -234,6 +1139,83 @@ where
     Ok(())
 }
 
+pub(crate) fn check_quote_amount(
+    amount: f64,
+    min_amount: f64,
+    max_amount: f64,
+    asset_symbol: &str,
+) -> Result<(), MystikoCliError> {
+    if amount < min_amount || amount > max_amount {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Invalid amount {} {}, expected between {} and {}",
+            amount,
+            asset_symbol,
+            min_amount,
+            max_amount
+        )));
+    }
+    Ok(())
+}
+
+fn create_send_deposit_options(
+    args: &DepositCreateCommand,
+    deposit: &Deposit,
+) -> SendDepositOptions {
+    let tx = create_default_transaction(deposit.chain_id);
+
+    SendDepositOptions::builder()
+        .deposit_id(deposit.id.clone())
+        .private_key(
+            args.private_key
+                .as_ref()
+                .map(|private_key| private_key.expose_secret().to_string()),
+        )
+        .signer_provider(args.signer_provider.clone())
+        .query_timeout_ms(args.query_timeout_ms)
+        .asset_approve_confirmations(args.asset_approve_confirmations)
+        .deposit_confirmations(args.deposit_confirmations)
+        .tx_wait_interval_ms(args.tx_wait_interval_ms)
+        .tx_wait_timeout_ms(args.tx_wait_timeout_ms)
+        .tx_send_timeout_ms(args.tx_send_timeout_ms)
+        .screening_message(args.screening_message.clone())
+        .asset_approve_tx(tx.clone())
+        .deposit_tx(tx)
+        .build()
+}
+
+pub(crate) fn create_default_transaction(chain_id: u64) -> Option<Transaction> {
+    default_max_priority_fee_per_gas(chain_id).map(|fee| {
+        let tx1 = Eip1559Transaction::builder()
+            .max_priority_fee_per_gas(fee.to_string())
+            .build();
+        let tx2 = EnumTransaction::Eip1559Transaction(tx1);
+        Transaction::builder().transaction(tx2).build()
+    })
+}
+
+pub(crate) fn create_nonce_transaction(chain_id: u64, nonce: U256) -> Option<Transaction> {
+    let mut tx1 = Eip1559Transaction::builder()
+        .nonce(nonce.to_string())
+        .build();
+    tx1.max_priority_fee_per_gas = default_max_priority_fee_per_gas(chain_id).map(str::to_string);
+    let tx2 = EnumTransaction::Eip1559Transaction(tx1);
+    Some(Transaction::builder().transaction(tx2).build())
+}
+
+pub(crate) fn default_max_priority_fee_per_gas(chain_id: u64) -> Option<&'static str> {
+    match chain_id {
+        1 => Some("5000000"),
+        137 => Some("30000000000"),
+        _ => None,
+    }
+}
+
+pub(crate) fn parse_address(address: &str) -> Result<Address, MystikoCliError> {
+    address
+        .parse::<Address>()
+        .map_err(|_| MystikoCliError::AnyhowError(anyhow!("Invalid address {}", address)))
+}
+
 pub(crate) fn create_list_filter(
     sub_filters: Vec<SubFilter>,
     limit: u64,
@@ -241,6 +1223,13 @@ pub(crate) fn create_list_filter(
 ) -> QueryFilter {
     let limit = limit.max(1_u64);
     let page = page.max(1_u64);
+    let mut filter = create_query_filter(sub_filters);
+    filter.limit = Some(limit);
+    filter.offset = Some((page - 1) * limit);
+    filter
+}
+
+pub(crate) fn create_query_filter(sub_filters: Vec<SubFilter>) -> QueryFilter {
     let order_by = OrderBy::builder()
         .order(Order::Desc)
         .columns(vec![DocumentColumn::Id.to_string()])
@@ -248,8 +1237,6 @@ pub(crate) fn create_list_filter(
     QueryFilter::builder()
         .conditions(vec![Condition::from(sub_filters)])
         .conditions_operator(ConditionOperator::And)
-        .limit(limit)
-        .offset((page - 1) * limit)
         .order_by(order_by)
         .build()
 }
@@ -0,0 +1,335 @@
+use crate::executor::{check_quote_amount, create_send_spend_options, max_spend_amount};
+use crate::{
+    current_timestamp, print_json, schedule_timestamp, AuditLog, CommandSigner, JobStore,
+    JobsCancelCommand, JobsCommand, JobsCommands, JobsListCommand, JobsRunCommand, MystikoCliError,
+    SpendAmount, SpendCreateCommand, SpendJob, SpendJobStatus, SpendScheduleCommand,
+};
+use anyhow::anyhow;
+use mystiko_core::{Mystiko, SpendHandler};
+use mystiko_protos::core::document::v1::Spend;
+use mystiko_protos::core::handler::v1::{
+    CreateSpendOptions, FixSpendStatusOptions, QuoteSpendOptions, SendSpendOptions, SpendQuote,
+    SpendSummary,
+};
+use mystiko_storage::{StatementFormatter, Storage};
+use std::sync::Arc;
+
+pub async fn execute_jobs_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: JobsCommand,
+    job_store: &JobStore,
+    audit_log: &AuditLog,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    MystikoCliError: From<X::Error>,
+{
+    match args.commands {
+        JobsCommands::Run(args) => {
+            execute_jobs_run_command(mystiko, args, job_store, audit_log).await
+        }
+        JobsCommands::List(args) => execute_jobs_list_command(args, job_store, compact_json).await,
+        JobsCommands::Cancel(args) => {
+            execute_jobs_cancel_command(args, job_store, compact_json).await
+        }
+    }
+}
+
+pub async fn execute_spend_schedule_command(
+    args: SpendScheduleCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    let job_store = match &args.jobs_path {
+        Some(jobs_path) => JobStore::from_path(jobs_path).await?,
+        None => {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Spend schedule needs a database for the job store and can not run in memory"
+            )))
+        }
+    };
+    let now = current_timestamp();
+    let execute_at = schedule_timestamp(now, args.at, args.delay, args.delay_max)?;
+    let job = SpendJob {
+        id: 0,
+        status: SpendJobStatus::Scheduled,
+        owner_pid: None,
+        created_at: now,
+        execute_at,
+        chain_id: args.chain_id,
+        asset_symbol: args.asset_symbol,
+        spend_type: args.spend_type,
+        bridge_type: args.bridge_type,
+        version: args.version,
+        recipient: args.recipient,
+        amount: args.amount.to_string(),
+        rollup_fee: args.rollup_fee,
+        relayer: args.relayer,
+        query_timeout_ms: args.query_timeout_ms,
+        confirmations: args.confirmations,
+        executed_at: None,
+        spend_id: None,
+        transaction_hash: None,
+        error: None,
+    };
+    let job = job_store.add(job).await?;
+    print_json(&job, compact_json)
+}
+
+pub async fn execute_jobs_list_command(
+    args: JobsListCommand,
+    job_store: &JobStore,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    let jobs = job_store
+        .jobs()
+        .await?
+        .into_iter()
+        .filter(|job| {
+            args.status
+                .as_ref()
+                .map(|statuses| statuses.contains(&job.status))
+                .unwrap_or(true)
+        })
+        .collect::<Vec<_>>();
+    print_json(&jobs, compact_json)
+}
+
+pub async fn execute_jobs_cancel_command(
+    args: JobsCancelCommand,
+    job_store: &JobStore,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    let job = job_store
+        .transition(
+            args.id,
+            SpendJobStatus::Scheduled,
+            SpendJobStatus::Cancelled,
+        )
+        .await?
+        .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!("Job {} is not scheduled", args.id)))?;
+    print_json(&job, compact_json)
+}
+
+pub async fn execute_jobs_run_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: JobsRunCommand,
+    job_store: &JobStore,
+    audit_log: &AuditLog,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    MystikoCliError: From<X::Error>,
+{
+    match job_store
+        .fail_abandoned("interrupted while running, check the spend with spend recover")
+        .await
+    {
+        Ok(jobs) => {
+            for job in jobs {
+                print_json(&job, true)?;
+            }
+        }
+        Err(err) => warn_job_store(&err),
+    }
+    loop {
+        let now = current_timestamp();
+        let due = match job_store.jobs().await {
+            Ok(jobs) => jobs
+                .into_iter()
+                .filter(|job| job.status == SpendJobStatus::Scheduled && job.execute_at <= now)
+                .map(|job| job.id)
+                .collect::<Vec<_>>(),
+            Err(err) => {
+                warn_job_store(&err);
+                vec![]
+            }
+        };
+        for id in due {
+            let job = match job_store
+                .transition(id, SpendJobStatus::Scheduled, SpendJobStatus::Running)
+                .await
+            {
+                Ok(Some(job)) => job,
+                Ok(None) => continue,
+                Err(err) => {
+                    warn_job_store(&err);
+                    continue;
+                }
+            };
+            let targets = vec![format!("job_id={}", job.id)];
+            if let Err(err) = audit_log.append_started("jobs run", targets.clone()).await {
+                if let Err(err) = job_store
+                    .transition(id, SpendJobStatus::Running, SpendJobStatus::Scheduled)
+                    .await
+                {
+                    warn_job_store(&err);
+                }
+                return Err(err);
+            }
+            let job = execute_spend_job(mystiko, &args, job_store, job).await;
+            let mut targets = targets;
+            if let Some(spend_id) = &job.spend_id {
+                targets.push(format!("spend_id={}", spend_id));
+            }
+            print_json(&job, true)?;
+            audit_log
+                .append("jobs run", targets, job.error.clone())
+                .await?;
+        }
+        if args.once {
+            return Ok(());
+        }
+        tokio::time::sleep(args.interval).await;
+    }
+}
+
+fn warn_job_store(err: &MystikoCliError) {
+    eprintln!("Warning: failed to access the job store: {}", err);
+}
+
+async fn execute_spend_job<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: &JobsRunCommand,
+    job_store: &JobStore,
+    mut job: SpendJob,
+) -> SpendJob
+where
+    F: StatementFormatter,
+    S: Storage,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    MystikoCliError: From<X::Error>,
+{
+    let sent: Result<Spend, MystikoCliError> = async {
+        let create_args = create_spend_command(&job, args)?;
+        let quote = mystiko.spends.quote(create_args.clone().into()).await?;
+        if !quote.valid {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Invalid spend quote with code {}",
+                quote.invalid_code
+            )));
+        }
+        let amount = match create_args.amount {
+            SpendAmount::Max => max_spend_amount(
+                &quote,
+                create_args.rollup_fee,
+                create_args.relayer.as_deref(),
+            )?,
+            SpendAmount::Exact(amount) => {
+                check_quote_amount(
+                    amount,
+                    quote.min_amount,
+                    quote.max_amount,
+                    &quote.asset_symbol,
+                )?;
+                amount
+            }
+        };
+        let create_args = SpendCreateCommand {
+            amount: SpendAmount::Exact(amount),
+            ..create_args
+        };
+        let signer = match &args.signer_command {
+            Some(signer_command) => Some(Arc::new(
+                CommandSigner::new(
+                    signer_command,
+                    args.signer_provider.as_deref().unwrap_or_default(),
+                    args.signer_timeout_ms,
+                )
+                .await?,
+            )),
+            None => None,
+        };
+        let spend = mystiko
+            .spends
+            .create(CreateSpendOptions::try_from(create_args.clone())?)
+            .await?;
+        job.spend_id = Some(spend.id.clone());
+        if let Err(err) = job_store.update(&job).await {
+            warn_job_store(&err);
+        }
+        let options = create_send_spend_options(&create_args, &spend)?;
+        Ok(match signer {
+            Some(signer) => mystiko.spends.send_with_signer(options, signer).await?,
+            None => mystiko.spends.send(options).await?,
+        })
+    }
+    .await;
+    job.executed_at = Some(current_timestamp());
+    match sent {
+        Ok(spend) => {
+            job.status = SpendJobStatus::Succeeded;
+            job.transaction_hash = spend.transaction_hash;
+        }
+        Err(err) => {
+            job.status = SpendJobStatus::Failed;
+            job.error = Some(err.to_string());
+        }
+    }
+    if let Err(err) = job_store.update(&job).await {
+        warn_job_store(&err);
+    }
+    job
+}
+
+fn create_spend_command(
+    job: &SpendJob,
+    args: &JobsRunCommand,
+) -> Result<SpendCreateCommand, MystikoCliError> {
+    Ok(SpendCreateCommand {
+        chain_id: job.chain_id,
+        asset_symbol: job.asset_symbol.clone(),
+        spend_type: job.spend_type.clone(),
+        bridge_type: job.bridge_type.clone(),
+        version: job.version,
+        recipient: job.recipient.clone(),
+        password: args.password.clone(),
+        private_key: args.private_key.clone(),
+        signer_command: args.signer_command.clone(),
+        signer_provider: args.signer_provider.clone(),
+        signer_timeout_ms: args.signer_timeout_ms,
+        amount: job
+            .amount
+            .parse()
+            .map_err(|err: String| MystikoCliError::AnyhowError(anyhow!(err)))?,
+        rollup_fee: job.rollup_fee,
+        relayer: job.relayer.clone(),
+        query_timeout_ms: job.query_timeout_ms,
+        confirmations: job.confirmations,
+        tx_send_timeout_ms: None,
+        tx_wait_timeout_ms: None,
+        tx_wait_interval_ms: None,
+        relayer_wait_interval_ms: None,
+        relayer_wait_timeout_ms: None,
+        yes: true,
+        dry_run: false,
+    })
+}
@@ -1,13 +1,35 @@
 mod account;
+mod agent;
+mod audit;
+mod batch;
+mod config;
 mod deposit;
+mod jobs;
+mod offline;
+mod recover;
 mod scanner;
+mod signer;
 mod spend;
+mod split;
 mod synchronizer;
+mod transaction;
+mod wait;
 mod wallet;
 
 pub use account::*;
+pub use agent::*;
+pub use audit::*;
+pub use batch::*;
+pub use config::*;
 pub use deposit::*;
+pub use jobs::*;
+pub use offline::*;
+pub use recover::*;
 pub use scanner::*;
+pub use signer::*;
 pub use spend::*;
+pub use split::*;
 pub use synchronizer::*;
+pub use transaction::*;
+pub use wait::*;
 pub use wallet::*;
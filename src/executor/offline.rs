@@ -0,0 +1,146 @@
+use crate::{
+    confirm_execution, parse_private_key, print_json, BroadcastCommand, MystikoCliError,
+    OfflineDocumentType, OfflineTransactionType, OfflineTransactions, SignCommand,
+};
+use anyhow::anyhow;
+use ethers_core::types::TxHash;
+use ethers_core::utils::to_checksum;
+use ethers_providers::{Http, Middleware, Provider};
+use mystiko_core::{DepositHandler, Mystiko, SpendHandler};
+use mystiko_protos::core::document::v1::{Deposit, Spend};
+use mystiko_protos::core::handler::v1::{
+    CreateDepositOptions, CreateSpendOptions, DepositQuote, DepositSummary,
+    FixDepositStatusOptions, FixSpendStatusOptions, QuoteDepositOptions, QuoteSpendOptions,
+    SendDepositOptions, SendSpendOptions, SpendQuote, SpendSummary,
+};
+use mystiko_protos::core::v1::{DepositStatus, SpendStatus};
+use mystiko_storage::{StatementFormatter, Storage};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BroadcastReport {
+    pub document_type: OfflineDocumentType,
+    pub document_id: String,
+    pub transactions: Vec<BroadcastTransaction>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BroadcastTransaction {
+    pub transaction_type: OfflineTransactionType,
+    pub chain_id: u64,
+    pub transaction_hash: TxHash,
+}
+
+pub async fn execute_sign_command(
+    args: SignCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError> {
+    let mut transactions = OfflineTransactions::read(&args.file).await?;
+    let wallet = parse_private_key(&args.private_key)?;
+    transactions.sign(&wallet)?;
+    transactions
+        .write(args.out.as_ref().unwrap_or(&args.file))
+        .await?;
+    print_json(&transactions, compact_json)
+}
+
+pub async fn execute_broadcast_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: BroadcastCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    MystikoCliError: From<D::Error> + From<X::Error>,
+{
+    let transactions = OfflineTransactions::read(&args.file).await?;
+    if !transactions.is_signed() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Transactions in {} are not signed, run mystiko sign first",
+            args.file
+        )));
+    }
+    if !args.yes {
+        confirm_execution(&transactions)?;
+    }
+    let provider = Provider::<Http>::try_from(args.provider.as_str())
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    let mut report = BroadcastReport {
+        document_type: transactions.document_type,
+        document_id: transactions.document_id.clone(),
+        transactions: vec![],
+    };
+    for transaction in transactions.transactions.into_iter() {
+        let raw_transaction = transaction.raw_transaction.unwrap_or_default();
+        let transaction_hash = provider
+            .send_raw_transaction(raw_transaction)
+            .await
+            .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+            .tx_hash();
+        let hash = format!("{:?}", transaction_hash);
+        match transaction.transaction_type {
+            OfflineTransactionType::AssetApprove | OfflineTransactionType::Deposit => {
+                let mut deposit = mystiko
+                    .deposits
+                    .find_by_id(transactions.document_id.clone())
+                    .await?
+                    .ok_or_else(|| {
+                        MystikoCliError::AnyhowError(anyhow!(
+                            "Deposit {} not found",
+                            transactions.document_id
+                        ))
+                    })?;
+                if transaction.transaction_type == OfflineTransactionType::AssetApprove {
+                    deposit.asset_approve_transaction_hash = Some(hash);
+                    deposit.status = DepositStatus::AssetApproving as i32;
+                } else {
+                    deposit.transaction_hash = Some(hash);
+                    deposit.status = DepositStatus::SrcPending as i32;
+                }
+                deposit.from_address = Some(to_checksum(&transaction.from, None));
+                mystiko.deposits.update(deposit).await?;
+            }
+            OfflineTransactionType::Spend => {
+                let mut spend = mystiko
+                    .spends
+                    .find_by_id(transactions.document_id.clone())
+                    .await?
+                    .ok_or_else(|| {
+                        MystikoCliError::AnyhowError(anyhow!(
+                            "Spend {} not found",
+                            transactions.document_id
+                        ))
+                    })?;
+                spend.transaction_hash = Some(hash);
+                spend.status = SpendStatus::Pending as i32;
+                spend.error_message = None;
+                mystiko.spends.update(spend).await?;
+            }
+        }
+        report.transactions.push(BroadcastTransaction {
+            transaction_type: transaction.transaction_type,
+            chain_id: transaction.chain_id,
+            transaction_hash,
+        });
+    }
+    print_json(&report, compact_json)
+}
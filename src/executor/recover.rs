@@ -0,0 +1,243 @@
+use crate::{MystikoCliError, TransactionReplacementRecord};
+use anyhow::anyhow;
+use ethers_core::types::{BlockNumber, TxHash};
+use ethers_providers::{Http, Middleware, Provider};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    Pending,
+    Advanced,
+    Confirmed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveryResult {
+    pub id: String,
+    pub previous_status: String,
+    pub status: String,
+    pub action: RecoveryAction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionOutcome {
+    Missing,
+    Unknown,
+    Dropped,
+    Pending,
+    Succeeded,
+    Reverted,
+    Cancelled,
+}
+
+impl TransactionOutcome {
+    pub fn failure_reason(&self, name: &str, transaction_hash: Option<&str>) -> Option<String> {
+        let transaction_hash = transaction_hash.unwrap_or_default();
+        match self {
+            TransactionOutcome::Missing => Some(format!("{} transaction was never recorded", name)),
+            TransactionOutcome::Dropped => Some(format!(
+                "{} transaction {} was dropped, its nonce was used by another transaction",
+                name, transaction_hash
+            )),
+            TransactionOutcome::Reverted => Some(format!(
+                "{} transaction {} reverted",
+                name, transaction_hash
+            )),
+            TransactionOutcome::Cancelled => Some(format!(
+                "{} transaction {} was cancelled",
+                name, transaction_hash
+            )),
+            TransactionOutcome::Unknown
+            | TransactionOutcome::Pending
+            | TransactionOutcome::Succeeded => None,
+        }
+    }
+
+    pub fn pending_reason(&self, name: &str, transaction_hash: Option<&str>) -> Option<String> {
+        let transaction_hash = transaction_hash.unwrap_or_default();
+        match self {
+            TransactionOutcome::Pending => Some(format!("{} transaction is still pending", name)),
+            TransactionOutcome::Unknown => Some(format!(
+                "{} transaction {} is unknown to the provider and its nonce is not recorded",
+                name, transaction_hash
+            )),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) async fn create_recover_provider(
+    provider: &str,
+    chain_id: u64,
+) -> Result<Provider<Http>, MystikoCliError> {
+    let provider = Provider::<Http>::try_from(provider)
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    let provider_chain_id = provider
+        .get_chainid()
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+        .as_u64();
+    if provider_chain_id != chain_id {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Provider is connected to chain {} instead of {}",
+            provider_chain_id,
+            chain_id
+        )));
+    }
+    Ok(provider)
+}
+
+pub(crate) async fn check_transaction(
+    provider: &Provider<Http>,
+    transaction_hash: Option<&str>,
+    replacements: &[TransactionReplacementRecord],
+) -> Result<TransactionOutcome, MystikoCliError> {
+    let transaction_hash = match transaction_hash {
+        Some(transaction_hash) => transaction_hash.parse::<TxHash>().map_err(|_| {
+            MystikoCliError::AnyhowError(anyhow!("Invalid transaction hash {}", transaction_hash))
+        })?,
+        None => return Ok(TransactionOutcome::Missing),
+    };
+    let replacements = related_replacements(transaction_hash, replacements);
+    let mut candidates = vec![(transaction_hash, false)];
+    for record in replacements.iter() {
+        for candidate in [
+            (record.replaced_transaction_hash, false),
+            (record.transaction_hash, record.cancel),
+        ] {
+            if !candidates.iter().any(|(hash, _)| *hash == candidate.0) {
+                candidates.push(candidate);
+            }
+        }
+    }
+    if let Some(outcome) = mined_outcome(provider, &candidates).await? {
+        return Ok(outcome);
+    }
+    let transaction = provider
+        .get_transaction(transaction_hash)
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    let sender_nonce = match &transaction {
+        Some(transaction) => Some((transaction.from, transaction.nonce)),
+        None => replacements
+            .first()
+            .map(|record| (record.from, record.nonce)),
+    };
+    let (from, nonce) = match sender_nonce {
+        Some(sender_nonce) => sender_nonce,
+        None => return Ok(TransactionOutcome::Unknown),
+    };
+    let confirmed_nonce = provider
+        .get_transaction_count(from, Some(BlockNumber::Latest.into()))
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    if confirmed_nonce <= nonce {
+        return Ok(TransactionOutcome::Pending);
+    }
+    Ok(mined_outcome(provider, &candidates)
+        .await?
+        .unwrap_or(TransactionOutcome::Dropped))
+}
+
+pub(crate) async fn check_sent_after(
+    provider: &Provider<Http>,
+    name: &str,
+    transaction_hash: Option<&str>,
+) -> Result<Option<String>, MystikoCliError> {
+    let transaction_hash = match transaction_hash {
+        Some(transaction_hash) => transaction_hash.parse::<TxHash>().map_err(|_| {
+            MystikoCliError::AnyhowError(anyhow!("Invalid transaction hash {}", transaction_hash))
+        })?,
+        None => {
+            return Ok(Some(format!(
+                "{} transaction is not recorded, the nonce of its sender can not be checked",
+                name
+            )))
+        }
+    };
+    let transaction = provider
+        .get_transaction(transaction_hash)
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    let transaction = match transaction {
+        Some(transaction) => transaction,
+        None => {
+            return Ok(Some(format!(
+                "{} transaction {:?} is unknown to the provider, \
+                 the nonce of its sender can not be checked",
+                name, transaction_hash
+            )))
+        }
+    };
+    let pending_nonce = provider
+        .get_transaction_count(transaction.from, Some(BlockNumber::Pending.into()))
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    if pending_nonce > transaction.nonce + 1 {
+        return Ok(Some(format!(
+            "{:?} has sent {} transaction(s) after the {} transaction {:?}, \
+             the next transaction may already be broadcast",
+            transaction.from,
+            pending_nonce - transaction.nonce - 1,
+            name,
+            transaction_hash
+        )));
+    }
+    Ok(None)
+}
+
+fn related_replacements(
+    transaction_hash: TxHash,
+    replacements: &[TransactionReplacementRecord],
+) -> Vec<TransactionReplacementRecord> {
+    let mut hashes = vec![transaction_hash];
+    let mut related: Vec<TransactionReplacementRecord> = vec![];
+    loop {
+        let found = replacements
+            .iter()
+            .filter(|record| !related.contains(record))
+            .filter(|record| {
+                hashes.contains(&record.replaced_transaction_hash)
+                    || hashes.contains(&record.transaction_hash)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        if found.is_empty() {
+            return related;
+        }
+        for record in found.into_iter() {
+            hashes.push(record.replaced_transaction_hash);
+            hashes.push(record.transaction_hash);
+            related.push(record);
+        }
+    }
+}
+
+async fn mined_outcome(
+    provider: &Provider<Http>,
+    candidates: &[(TxHash, bool)],
+) -> Result<Option<TransactionOutcome>, MystikoCliError> {
+    for (transaction_hash, cancel) in candidates.iter() {
+        let receipt = provider
+            .get_transaction_receipt(*transaction_hash)
+            .await
+            .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+        if let Some(receipt) = receipt {
+            return Ok(Some(if *cancel {
+                TransactionOutcome::Cancelled
+            } else if receipt.status.is_some_and(|status| status.is_zero()) {
+                TransactionOutcome::Reverted
+            } else {
+                TransactionOutcome::Succeeded
+            }));
+        }
+    }
+    Ok(None)
+}
@@ -1,15 +1,32 @@
 use crate::{
-    print_json, MystikoCliError, ScannerAssetsCommand, ScannerBalanceCommand, ScannerCommand,
-    ScannerCommands, ScannerImportCommand, ScannerResetCommand, ScannerScanCommand,
+    confirm_execution, print_json, MystikoCliError, ScannerAssetsCommand, ScannerBalanceCommand,
+    ScannerCommand, ScannerCommands, ScannerImportCommand, ScannerResetCommand, ScannerScanCommand,
     ScannerSyncCommand,
 };
-use mystiko_core::{Mystiko, ScannerHandler};
+use mystiko_core::{AccountHandler, Mystiko, ScannerHandler};
+use mystiko_protos::core::document::v1::Account;
+use mystiko_protos::core::handler::v1::{CreateAccountOptions, UpdateAccountOptions};
 use mystiko_protos::core::scanner::v1::{
     AssetImportOptions, AssetImportResult, AssetsByChain, AssetsOptions, BalanceOptions,
     BalanceResult, ScannerResetOptions, ScannerResetResult, ScannerScanOptions, ScannerScanResult,
     ScannerSyncOptions, ScannerSyncResult,
 };
 use mystiko_storage::{StatementFormatter, Storage};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScannerResetPreview {
+    pub reset_to_id: Option<String>,
+    pub accounts: Vec<ScannerResetAccount>,
+    pub deleted_data: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScannerResetAccount {
+    pub name: String,
+    pub shielded_address: String,
+    pub scanned_to_id: Option<String>,
+}
 
 pub async fn execute_scanner_command<F, S, W, A, D, X, Y, R>(
     mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
@@ -33,7 +50,8 @@ where
         AssetsOptions,
         AssetsByChain,
     >,
-    MystikoCliError: From<R::Error>,
+    A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
+    MystikoCliError: From<R::Error> + From<A::Error>,
 {
     match args.commands {
         ScannerCommands::Sync(args) => {
@@ -81,7 +99,10 @@ where
     >,
     MystikoCliError: From<R::Error>,
 {
-    let result = mystiko.scanner.sync(args.into()).await?;
+    let result = mystiko
+        .scanner
+        .sync(ScannerSyncOptions::try_from(args)?)
+        .await?;
     print_json(&result, compact_json)
 }
 
@@ -109,7 +130,10 @@ where
     >,
     MystikoCliError: From<R::Error>,
 {
-    let result = mystiko.scanner.scan(args.into()).await?;
+    let result = mystiko
+        .scanner
+        .scan(ScannerScanOptions::try_from(args)?)
+        .await?;
     print_json(&result, compact_json)
 }
 
@@ -135,8 +159,40 @@ where
         AssetsOptions,
         AssetsByChain,
     >,
-    MystikoCliError: From<R::Error>,
+    A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
+    MystikoCliError: From<R::Error> + From<A::Error>,
 {
+    if !args.yes {
+        let accounts = mystiko
+            .accounts
+            .find_all()
+            .await?
+            .into_iter()
+            .filter(|account| {
+                args.shielded_address
+                    .as_ref()
+                    .filter(|addresses| !addresses.is_empty())
+                    .is_none_or(|addresses| addresses.contains(&account.shielded_address))
+            })
+            .map(|account| ScannerResetAccount {
+                name: account.name,
+                shielded_address: account.shielded_address,
+                scanned_to_id: account.scanned_to_id,
+            })
+            .collect();
+        let preview = ScannerResetPreview {
+            reset_to_id: args.to_id.clone(),
+            accounts,
+            deleted_data: match &args.to_id {
+                Some(to_id) => format!(
+                    "scanning progress and discovered assets after commitment {}",
+                    to_id
+                ),
+                None => String::from("all scanning progress and discovered assets"),
+            },
+        };
+        confirm_execution(&preview)?;
+    }
     let result = mystiko.scanner.reset(args.into()).await?;
     print_json(&result, compact_json)
 }
@@ -165,7 +221,10 @@ where
     >,
     MystikoCliError: From<R::Error>,
 {
-    let result = mystiko.scanner.import(args.into()).await?;
+    let result = mystiko
+        .scanner
+        .import(AssetImportOptions::try_from(args)?)
+        .await?;
     print_json(&result, compact_json)
 }
 
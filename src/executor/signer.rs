@@ -0,0 +1,29 @@
+use crate::{
+    handle_signer_request, parse_private_key, MystikoCliError, ReferenceSignerCommand,
+    SecretString, SignerRequest, SignerResponse,
+};
+use anyhow::anyhow;
+use std::io::Read;
+
+pub async fn execute_reference_signer_command(
+    args: ReferenceSignerCommand,
+) -> Result<(), MystikoCliError> {
+    let private_key = std::env::var(&args.private_key_env).map_err(|_| {
+        MystikoCliError::AnyhowError(anyhow!(
+            "Environment variable {} is not set",
+            args.private_key_env
+        ))
+    })?;
+    let wallet = parse_private_key(&SecretString::from(private_key))?;
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let response = match serde_json::from_str::<SignerRequest>(input.trim()) {
+        Ok(request) => handle_signer_request(&wallet, request),
+        Err(err) => SignerResponse {
+            error: Some(format!("invalid signer request: {}", err)),
+            ..SignerResponse::default()
+        },
+    };
+    println!("{}", serde_json::to_string(&response)?);
+    Ok(())
+}
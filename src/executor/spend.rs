@@ -1,17 +1,51 @@
 use crate::args::{parse_bridge_types, parse_spend_statuses, parse_spend_types};
-use crate::executor::create_list_filter;
+use crate::executor::{
+    check_quote_amount, check_transaction, create_list_filter, create_query_filter,
+    create_recover_provider, execute_spend_batch_command, execute_spend_schedule_command,
+    parse_address, replace_transaction, status_name, status_reached, sync_chains, wait_for_status,
+    RecoveryAction, RecoveryResult, TransactionOutcome, WaitState, SPEND_STATUS_PROGRESSION,
+};
 use crate::{
-    print_json, MystikoCliError, SpendCommand, SpendCommands, SpendCreateCommand, SpendListCommand,
-    SpendQuoteCommand,
+    confirm_execution, create_capturing_signer, expose_wallet_password, print_json,
+    CapturedTransaction, CapturingSigner, CommandSigner, MystikoCliError, OfflineDocumentType,
+    OfflineTransaction, OfflineTransactionType, OfflineTransactions, ReplacementStore, SpendAmount,
+    SpendCommand, SpendCommands, SpendCreateCommand, SpendListCommand, SpendPrepareCommand,
+    SpendQuoteCommand, SpendRecoverCommand, SpendWaitCommand, TransactionReplaceCommand,
 };
-use mystiko_core::{Mystiko, SpendColumn, SpendHandler};
+use anyhow::anyhow;
+use mystiko_core::{Mystiko, ScannerHandler, SpendColumn, SpendHandler, SynchronizerHandler};
 use mystiko_protos::core::document::v1::Spend;
 use mystiko_protos::core::handler::v1::{
     CreateSpendOptions, FixSpendStatusOptions, QuoteSpendOptions, SendSpendOptions, SpendQuote,
     SpendSummary,
 };
+use mystiko_protos::core::scanner::v1::{
+    AssetImportOptions, AssetImportResult, AssetsByChain, AssetsOptions, BalanceOptions,
+    BalanceResult, ScannerResetOptions, ScannerResetResult, ScannerScanOptions, ScannerScanResult,
+    ScannerSyncOptions, ScannerSyncResult,
+};
+use mystiko_protos::core::synchronizer::v1::{
+    SynchronizerResetOptions, SynchronizerStatus, SynchronizerSyncOptions,
+};
+use mystiko_protos::core::v1::SpendStatus;
 use mystiko_protos::storage::v1::SubFilter;
-use mystiko_storage::{StatementFormatter, Storage};
+use mystiko_storage::{DocumentColumn, StatementFormatter, Storage};
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendConfirmation {
+    pub quote: SpendQuote,
+    pub summary: SpendSummary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendDryRun {
+    pub quote: SpendQuote,
+    pub summary: SpendSummary,
+    pub contract_address: String,
+    pub transactions: Vec<CapturedTransaction>,
+}
 
 pub async fn execute_spend_command<F, S, W, A, D, X, Y, R>(
     mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
@@ -30,7 +64,22 @@ where
         SendSpendOptions,
         FixSpendStatusOptions,
     >,
-    MystikoCliError: From<X::Error>,
+    Y: SynchronizerHandler<SynchronizerSyncOptions, SynchronizerStatus, SynchronizerResetOptions>,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<X::Error> + From<Y::Error> + From<R::Error>,
 {
     match args.commands {
         SpendCommands::Quote(args) => {
@@ -39,6 +88,23 @@ where
         SpendCommands::Create(args) => {
             execute_spend_create_command(mystiko, args, compact_json).await
         }
+        SpendCommands::Batch(args) => {
+            execute_spend_batch_command(mystiko, args, compact_json).await
+        }
+        SpendCommands::Schedule(args) => execute_spend_schedule_command(args, compact_json).await,
+        SpendCommands::Prepare(args) => {
+            execute_spend_prepare_command(mystiko, args, compact_json).await
+        }
+        SpendCommands::SpeedUp(args) => {
+            execute_spend_replace_command(mystiko, args, false, compact_json).await
+        }
+        SpendCommands::Cancel(args) => {
+            execute_spend_replace_command(mystiko, args, true, compact_json).await
+        }
+        SpendCommands::Recover(args) => {
+            execute_spend_recover_command(mystiko, args, compact_json).await
+        }
+        SpendCommands::Wait(args) => execute_spend_wait_command(mystiko, args).await,
         SpendCommands::List(args) => execute_spend_list_command(mystiko, args, compact_json).await,
     }
 }
@@ -85,22 +151,384 @@ where
     >,
     MystikoCliError: From<X::Error>,
 {
-    let spend = mystiko.spends.create(args.clone().into()).await?;
-    let send_options = SendSpendOptions::builder()
-        .spend_id(spend.id)
-        .wallet_password(args.password)
-        .private_key(args.private_key)
-        .signer_provider(args.signer_provider)
+    let args = if args.amount == SpendAmount::Max {
+        let quote = mystiko.spends.quote(args.clone().into()).await?;
+        let amount = max_spend_amount(&quote, args.rollup_fee, args.relayer.as_deref())?;
+        SpendCreateCommand {
+            amount: SpendAmount::Exact(amount),
+            ..args
+        }
+    } else {
+        args
+    };
+    if args.dry_run {
+        return execute_spend_dry_run(mystiko, args, compact_json).await;
+    }
+    let signer = match &args.signer_command {
+        Some(signer_command) => Some(Arc::new(
+            CommandSigner::new(
+                signer_command,
+                args.signer_provider.as_deref().unwrap_or_default(),
+                args.signer_timeout_ms,
+            )
+            .await?,
+        )),
+        None => None,
+    };
+    if !args.yes {
+        let confirmation = SpendConfirmation {
+            quote: mystiko.spends.quote(args.clone().into()).await?,
+            summary: mystiko
+                .spends
+                .summary(CreateSpendOptions::try_from(args.clone())?)
+                .await?,
+        };
+        confirm_execution(&confirmation)?;
+    }
+    let spend = mystiko
+        .spends
+        .create(CreateSpendOptions::try_from(args.clone())?)
+        .await?;
+    let options = create_send_spend_options(&args, &spend)?;
+    let spend = match signer {
+        Some(signer) => mystiko.spends.send_with_signer(options, signer).await?,
+        None => mystiko.spends.send(options).await?,
+    };
+    print_json(&spend, compact_json)
+}
+
+async fn execute_spend_dry_run<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: SpendCreateCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    MystikoCliError: From<X::Error>,
+{
+    if args.relayer.is_some() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Dry run does not support relayed spends, remove --relayer"
+        )));
+    }
+    let quote = mystiko.spends.quote(args.clone().into()).await?;
+    if !quote.valid {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Invalid spend quote with code {}",
+            quote.invalid_code
+        )));
+    }
+    check_quote_amount(
+        args.amount.exact().unwrap_or_default(),
+        quote.min_amount,
+        quote.max_amount,
+        &quote.asset_symbol,
+    )?;
+    let summary = mystiko
+        .spends
+        .summary(CreateSpendOptions::try_from(args.clone())?)
+        .await?;
+    let spend = mystiko
+        .spends
+        .create(CreateSpendOptions::try_from(args.clone())?)
+        .await?;
+    let signer = Arc::new(
+        create_capturing_signer(
+            args.private_key.as_ref(),
+            args.signer_command.as_deref(),
+            args.signer_timeout_ms,
+        )
+        .await?,
+    );
+    let sent = mystiko
+        .spends
+        .send_with_signer(create_send_spend_options(&args, &spend)?, signer.clone())
+        .await;
+    let transactions = signer.transactions();
+    if transactions.is_empty() {
+        sent?;
+    }
+    let dry_run = SpendDryRun {
+        quote,
+        summary,
+        contract_address: spend.contract_address,
+        transactions,
+    };
+    print_json(&dry_run, compact_json)
+}
+
+pub async fn execute_spend_prepare_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: SpendPrepareCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    MystikoCliError: From<X::Error>,
+{
+    let from = parse_address(&args.from)?;
+    let spend = mystiko
+        .spends
+        .create(CreateSpendOptions::try_from(args.clone())?)
+        .await?;
+    let signer = Arc::new(CapturingSigner::new(from));
+    let options = SendSpendOptions::builder()
+        .spend_id(spend.id.clone())
+        .wallet_password(expose_wallet_password(&args.password)?)
         .query_timeout_ms(args.query_timeout_ms)
-        .spend_confirmations(args.confirmations)
-        .tx_wait_timeout_ms(args.tx_wait_timeout_ms)
-        .tx_wait_interval_ms(args.tx_wait_interval_ms)
-        .tx_send_timeout_ms(args.tx_send_timeout_ms)
-        .relayer_wait_interval_ms(args.relayer_wait_interval_ms)
-        .relayer_wait_timeout_ms(args.relayer_wait_timeout_ms)
         .build();
-    let spend = mystiko.spends.send(send_options).await?;
-    print_json(&spend, compact_json)
+    let sent = mystiko
+        .spends
+        .send_with_signer(options, signer.clone())
+        .await;
+    let captured = signer.transactions();
+    if captured.is_empty() {
+        sent?;
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Spend {} has no transaction to prepare",
+            spend.id
+        )));
+    }
+    let transactions = captured
+        .into_iter()
+        .map(|captured| OfflineTransaction::new(OfflineTransactionType::Spend, captured))
+        .collect();
+    let mut transactions =
+        OfflineTransactions::new(OfflineDocumentType::Spend, spend.id, transactions);
+    transactions
+        .assign_nonces(args.nonce, args.signer_provider.as_deref())
+        .await?;
+    transactions.write(&args.out).await?;
+    print_json(&transactions, compact_json)
+}
+
+pub async fn execute_spend_replace_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: TransactionReplaceCommand,
+    cancel: bool,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    MystikoCliError: From<X::Error>,
+{
+    let mut spend = mystiko
+        .spends
+        .find_by_id(args.id.clone())
+        .await?
+        .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!("Spend {} not found", args.id)))?;
+    if spend.gas_relayer_address.is_some() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Spend {} was sent by a relayer and cannot be replaced",
+            spend.id
+        )));
+    }
+    let transaction_hash = spend
+        .transaction_hash
+        .clone()
+        .filter(|_| spend.status == SpendStatus::Pending as i32)
+        .ok_or_else(|| {
+            MystikoCliError::AnyhowError(anyhow!("Spend {} has no pending transaction", spend.id))
+        })?;
+    let replacement = replace_transaction(&args, &transaction_hash, cancel).await?;
+    let replaced_by = replacement
+        .transaction_hash
+        .map(|transaction_hash| format!("{:?}", transaction_hash));
+    if !cancel {
+        spend.transaction_hash = replaced_by;
+        mystiko.spends.update(spend).await?;
+    }
+    print_json(&replacement, compact_json)
+}
+
+pub async fn execute_spend_recover_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: SpendRecoverCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    MystikoCliError: From<X::Error>,
+{
+    let provider = create_recover_provider(&args.provider, args.chain_id).await?;
+    let replacements = match &args.replacements_path {
+        Some(replacements_path) => {
+            ReplacementStore::from_path(replacements_path)
+                .await?
+                .records()
+                .await?
+        }
+        None => vec![],
+    };
+    let mut sub_filters = vec![
+        SubFilter::equal(SpendColumn::ChainId, args.chain_id),
+        SubFilter::equal(SpendColumn::Status, SpendStatus::Pending as i32),
+    ];
+    if let Some(ids) = args.id {
+        if !ids.is_empty() {
+            sub_filters.push(SubFilter::in_list(DocumentColumn::Id, ids));
+        }
+    }
+    let spends = mystiko
+        .spends
+        .find(create_query_filter(sub_filters))
+        .await?;
+    for spend in spends.into_iter() {
+        let previous_status = spend_status_name(spend.status);
+        let mut result = RecoveryResult {
+            id: spend.id.clone(),
+            previous_status: previous_status.clone(),
+            status: previous_status,
+            action: RecoveryAction::Pending,
+            transaction_hash: spend.transaction_hash.clone(),
+            reason: None,
+        };
+        let replacements = replacements
+            .iter()
+            .filter(|record| record.document_id == spend.id)
+            .cloned()
+            .collect::<Vec<_>>();
+        let outcome =
+            check_transaction(&provider, spend.transaction_hash.as_deref(), &replacements).await?;
+        if let (Some(gas_relayer_url), TransactionOutcome::Missing) =
+            (&spend.gas_relayer_url, outcome)
+        {
+            result.reason = Some(format!(
+                "relayer {} has not reported a transaction and the spend records no relayer job to query",
+                gas_relayer_url
+            ));
+            print_json(&result, compact_json)?;
+            continue;
+        }
+        let reason = outcome.failure_reason("spend", spend.transaction_hash.as_deref());
+        let status = match (outcome, &reason) {
+            (_, Some(_)) => Some(SpendStatus::Failed),
+            (TransactionOutcome::Succeeded, None) => Some(SpendStatus::Succeeded),
+            _ => None,
+        };
+        match status {
+            Some(status) => {
+                let options = FixSpendStatusOptions::builder()
+                    .spend_id(spend.id.clone())
+                    .status(status as i32)
+                    .error_message(reason.clone())
+                    .build();
+                let spend = mystiko.spends.fix_status(options).await?;
+                result.status = spend_status_name(spend.status);
+                result.action = if status == SpendStatus::Failed {
+                    RecoveryAction::Failed
+                } else {
+                    RecoveryAction::Confirmed
+                };
+                result.reason = reason;
+            }
+            None => {
+                result.reason = outcome.pending_reason("spend", spend.transaction_hash.as_deref())
+            }
+        }
+        print_json(&result, compact_json)?;
+    }
+    Ok(())
+}
+
+pub async fn execute_spend_wait_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: SpendWaitCommand,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    Y: SynchronizerHandler<SynchronizerSyncOptions, SynchronizerStatus, SynchronizerResetOptions>,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<X::Error> + From<Y::Error> + From<R::Error>,
+{
+    let until = args.until as i32;
+    let spend = mystiko
+        .spends
+        .find_by_id(args.id.clone())
+        .await?
+        .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!("Spend {} not found", args.id)))?;
+    let failed = SpendStatus::Failed as i32;
+    let progression = SPEND_STATUS_PROGRESSION.map(|status| status as i32);
+    let poll = || async {
+        sync_chains(mystiko, vec![spend.chain_id], &args.password).await?;
+        let spend = mystiko
+            .spends
+            .find_by_id(args.id.clone())
+            .await?
+            .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!("Spend {} not found", args.id)))?;
+        Ok(WaitState {
+            status: spend_status_name(spend.status),
+            transaction_hash: spend.transaction_hash,
+            error_message: spend.error_message,
+            reached: status_reached(spend.status, until, &progression),
+            failed: spend.status == failed,
+        })
+    };
+    let until_name = spend_status_name(until);
+    wait_for_status(&args.id, &until_name, args.timeout, args.interval, poll).await
 }
 
 pub async fn execute_spend_list_command<F, S, W, A, D, X, Y, R>(
@@ -184,3 +612,78 @@ where
     }
     Ok(())
 }
+
+pub(crate) fn create_send_spend_options(
+    args: &SpendCreateCommand,
+    spend: &Spend,
+) -> Result<SendSpendOptions, MystikoCliError> {
+    Ok(SendSpendOptions::builder()
+        .spend_id(spend.id.clone())
+        .wallet_password(expose_wallet_password(&args.password)?)
+        .private_key(
+            args.private_key
+                .as_ref()
+                .map(|private_key| private_key.expose_secret().to_string()),
+        )
+        .signer_provider(args.signer_provider.clone())
+        .query_timeout_ms(args.query_timeout_ms)
+        .spend_confirmations(args.confirmations)
+        .tx_wait_timeout_ms(args.tx_wait_timeout_ms)
+        .tx_wait_interval_ms(args.tx_wait_interval_ms)
+        .tx_send_timeout_ms(args.tx_send_timeout_ms)
+        .relayer_wait_interval_ms(args.relayer_wait_interval_ms)
+        .relayer_wait_timeout_ms(args.relayer_wait_timeout_ms)
+        .build())
+}
+
+pub fn max_spend_amount(
+    quote: &SpendQuote,
+    rollup_fee: Option<f64>,
+    relayer: Option<&str>,
+) -> Result<f64, MystikoCliError> {
+    let rollup_fee = if quote.rollup_fee_asset_symbol == quote.asset_symbol {
+        rollup_fee.unwrap_or(quote.min_rollup_fee_amount)
+    } else {
+        0_f64
+    };
+    let relayer_fee = match relayer {
+        Some(relayer) => quote
+            .gas_relayers
+            .iter()
+            .find(|gas_relayer| gas_relayer.name == relayer || gas_relayer.url == relayer)
+            .map(|gas_relayer| gas_relayer.min_gas_fee_amount)
+            .ok_or_else(|| {
+                MystikoCliError::AnyhowError(anyhow!(
+                    "Relayer {} is not offered by the spend quote",
+                    relayer
+                ))
+            })?,
+        None => 0_f64,
+    };
+    let mut amount = (quote.current_balance - rollup_fee - relayer_fee).min(quote.max_amount);
+    if !quote.fixed_amounts.is_empty() {
+        amount = quote
+            .fixed_amounts
+            .iter()
+            .copied()
+            .filter(|fixed_amount| *fixed_amount <= amount)
+            .fold(0_f64, f64::max);
+    }
+    let scale = 10_f64.powi(quote.asset_decimals.min(9) as i32);
+    let amount = (amount * scale).floor() / scale;
+    if amount <= 0_f64 || amount < quote.min_amount {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Balance {} {} is too small to spend after fees of {}, expected at least {}",
+            quote.current_balance,
+            quote.asset_symbol,
+            rollup_fee + relayer_fee,
+            quote.min_amount
+        )));
+    }
+    Ok(amount)
+}
+
+fn spend_status_name(status: i32) -> String {
+    let status = SpendStatus::from_i32(status).unwrap_or_default();
+    status_name(status.as_str_name(), "SPEND_STATUS_")
+}
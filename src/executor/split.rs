@@ -0,0 +1,131 @@
+use crate::MystikoCliError;
+use anyhow::anyhow;
+use mystiko_protos::core::document::v1::Deposit;
+use mystiko_protos::core::handler::v1::DepositQuote;
+use serde::Serialize;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DepositSplitPiece {
+    pub amount: f64,
+    pub standard: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DepositSplitPlan {
+    pub quote: DepositQuote,
+    pub total_amount: f64,
+    pub pieces: Vec<DepositSplitPiece>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_delay_secs: Option<u64>,
+    pub trade_offs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DepositSplitReport {
+    pub deposits: Vec<Deposit>,
+    pub trade_offs: Vec<String>,
+}
+
+pub fn split_deposit_amount(
+    amount: f64,
+    quote: &DepositQuote,
+) -> Result<Vec<DepositSplitPiece>, MystikoCliError> {
+    let scale = 10_f64.powi(quote.asset_decimals.min(9) as i32);
+    let to_units = |value: f64| (value * scale).round() as u64;
+    let mut denominations = quote
+        .recommended_amounts
+        .iter()
+        .filter(|recommended| **recommended >= quote.min_amount)
+        .filter(|recommended| **recommended <= quote.max_amount)
+        .map(|recommended| to_units(*recommended))
+        .filter(|units| *units > 0)
+        .collect::<Vec<_>>();
+    denominations.sort_unstable_by(|a, b| b.cmp(a));
+    denominations.dedup();
+    if denominations.is_empty() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "No recommended amounts between {} and {} {} to split into",
+            quote.min_amount,
+            quote.max_amount,
+            quote.asset_symbol
+        )));
+    }
+    if amount <= 0_f64 {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Invalid amount {} {}",
+            amount,
+            quote.asset_symbol
+        )));
+    }
+    let mut remaining = to_units(amount);
+    let mut pieces = vec![];
+    for denomination in denominations {
+        while remaining >= denomination {
+            pieces.push(DepositSplitPiece {
+                amount: denomination as f64 / scale,
+                standard: true,
+            });
+            remaining -= denomination;
+        }
+    }
+    if remaining > 0 {
+        let remainder = remaining as f64 / scale;
+        if remainder < quote.min_amount {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "The remainder {} {} is below the minimum amount {}, deposit {} instead",
+                remainder,
+                quote.asset_symbol,
+                quote.min_amount,
+                (to_units(amount) - remaining) as f64 / scale
+            )));
+        }
+        pieces.push(DepositSplitPiece {
+            amount: remainder,
+            standard: false,
+        });
+    }
+    Ok(pieces)
+}
+
+pub fn split_trade_offs(
+    pieces: &[DepositSplitPiece],
+    quote: &DepositQuote,
+    max_delay: Option<Duration>,
+) -> Vec<String> {
+    let mut trade_offs = vec![];
+    if pieces.len() > 1 {
+        trade_offs.push(format!(
+            "All {} deposits are sent from the same account and can still be grouped on chain, \
+             splitting only hides the total among common amounts in the pool",
+            pieces.len()
+        ));
+    } else {
+        trade_offs.push("The amount is not split into more than one deposit".to_string());
+    }
+    for piece in pieces.iter().filter(|piece| !piece.standard) {
+        trade_offs.push(format!(
+            "The remainder {} {} is not a recommended amount and stands out in the pool",
+            piece.amount, quote.asset_symbol
+        ));
+    }
+    trade_offs.push(format!(
+        "Each deposit pays its own gas and rollup fee, at least {} {} of rollup fees in total",
+        quote.min_rollup_fee_amount * pieces.len() as f64,
+        quote.rollup_fee_asset_symbol
+    ));
+    match max_delay {
+        Some(max_delay) => trade_offs.push(format!(
+            "The deposits are spread over random delays of up to {} each, \
+             the command keeps running until the last one is sent",
+            humantime::format_duration(max_delay)
+        )),
+        None if pieces.len() > 1 => trade_offs.push(
+            "The deposits are sent back to back and their timing links them, \
+             use --split-max-delay to spread them out"
+                .to_string(),
+        ),
+        None => {}
+    }
+    trade_offs
+}
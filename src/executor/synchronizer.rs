@@ -1,12 +1,21 @@
 use crate::{
-    print_json, MystikoCliError, SynchronizerCommand, SynchronizerCommands,
+    confirm_execution, print_json, MystikoCliError, SynchronizerCommand, SynchronizerCommands,
     SynchronizerResetCommand, SynchronizerStatusCommand, SynchronizerSyncCommand,
 };
 use mystiko_core::{Mystiko, SynchronizerHandler};
 use mystiko_protos::core::synchronizer::v1::{
-    SynchronizerResetOptions, SynchronizerStatus, SynchronizerSyncOptions,
+    ContractStatus, SynchronizerResetOptions, SynchronizerStatus, SynchronizerSyncOptions,
 };
 use mystiko_storage::{StatementFormatter, Storage};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SynchronizerResetPreview {
+    pub chain_id: u64,
+    pub reset_to_block: Option<u64>,
+    pub contracts: Vec<ContractStatus>,
+    pub deleted_data: String,
+}
 
 pub async fn execute_synchronizer<F, S, W, A, D, X, Y, R>(
     mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
@@ -73,6 +82,37 @@ where
     Y: SynchronizerHandler<SynchronizerSyncOptions, SynchronizerStatus, SynchronizerResetOptions>,
     MystikoCliError: From<Y::Error>,
 {
+    if !args.yes {
+        let status = mystiko.synchronizer.status(true).await?;
+        let contracts = status
+            .chains
+            .into_iter()
+            .filter(|chain| chain.chain_id == args.chain_id)
+            .flat_map(|chain| chain.contracts)
+            .filter(|contract| {
+                args.contract_address
+                    .as_ref()
+                    .filter(|addresses| !addresses.is_empty())
+                    .is_none_or(|addresses| {
+                        addresses
+                            .iter()
+                            .any(|address| address.eq_ignore_ascii_case(&contract.contract_address))
+                    })
+            })
+            .collect();
+        let preview = SynchronizerResetPreview {
+            chain_id: args.chain_id,
+            reset_to_block: args.to,
+            contracts,
+            deleted_data: match args.to {
+                Some(to) => format!("synchronized commitments and nullifiers after block {}", to),
+                None => String::from(
+                    "all synchronized commitments and nullifiers since the contract deploy block",
+                ),
+            },
+        };
+        confirm_execution(&preview)?;
+    }
     let options: SynchronizerResetOptions = args.into();
     let with_contracts = options
         .chains
@@ -0,0 +1,195 @@
+use crate::executor::default_max_priority_fee_per_gas;
+use crate::{
+    confirm_execution, parse_private_key, sign_with_command, MystikoCliError, ReplacementStore,
+    TransactionReplaceCommand, TransactionReplacementRecord,
+};
+use anyhow::anyhow;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{
+    Address, Bytes, Eip1559TransactionRequest, TransactionRequest, TxHash, U256,
+};
+use ethers_providers::{Http, Middleware, Provider};
+use ethers_signers::Signer;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionReplacement {
+    pub cancel: bool,
+    pub chain_id: u64,
+    pub from: Address,
+    pub nonce: U256,
+    pub replaced_transaction_hash: TxHash,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<TxHash>,
+}
+
+pub(crate) async fn replace_transaction(
+    args: &TransactionReplaceCommand,
+    transaction_hash: &str,
+    cancel: bool,
+) -> Result<TransactionReplacement, MystikoCliError> {
+    let replaced_transaction_hash = transaction_hash.parse::<TxHash>().map_err(|_| {
+        MystikoCliError::AnyhowError(anyhow!("Invalid transaction hash {}", transaction_hash))
+    })?;
+    let replacements = match &args.replacements_path {
+        Some(replacements_path) => ReplacementStore::from_path(replacements_path).await?,
+        None => {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Replacing a transaction needs a database to record it and can not run in memory"
+            )))
+        }
+    };
+    let provider = Provider::<Http>::try_from(args.provider.as_str())
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    let pending = provider
+        .get_transaction(replaced_transaction_hash)
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+        .ok_or_else(|| {
+            MystikoCliError::AnyhowError(anyhow!("Transaction {} not found", transaction_hash))
+        })?;
+    if pending.block_number.is_some() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Transaction {} is already mined",
+            transaction_hash
+        )));
+    }
+    let chain_id = provider
+        .get_chainid()
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+        .as_u64();
+    let mut replacement = TransactionReplacement {
+        cancel,
+        chain_id,
+        from: pending.from,
+        nonce: pending.nonce,
+        replaced_transaction_hash,
+        max_fee_per_gas: None,
+        max_priority_fee_per_gas: None,
+        gas_price: None,
+        transaction_hash: None,
+    };
+    let mut transaction: TypedTransaction =
+        match (pending.max_fee_per_gas, pending.max_priority_fee_per_gas) {
+            (Some(pending_max_fee), Some(pending_priority_fee)) => {
+                let (current_max_fee, current_priority_fee) = provider
+                    .estimate_eip1559_fees(None)
+                    .await
+                    .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+                let min_priority_fee = default_max_priority_fee_per_gas(chain_id)
+                    .and_then(|fee| U256::from_dec_str(fee).ok())
+                    .unwrap_or_default();
+                let max_priority_fee_per_gas =
+                    bump_fee(pending_priority_fee, args.fee_bump_percent)
+                        .max(current_priority_fee)
+                        .max(min_priority_fee);
+                let max_fee_per_gas = bump_fee(pending_max_fee, args.fee_bump_percent)
+                    .max(current_max_fee)
+                    .max(max_priority_fee_per_gas);
+                replacement.max_fee_per_gas = Some(max_fee_per_gas);
+                replacement.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+                Eip1559TransactionRequest::new()
+                    .from(pending.from)
+                    .nonce(pending.nonce)
+                    .chain_id(chain_id)
+                    .max_fee_per_gas(max_fee_per_gas)
+                    .max_priority_fee_per_gas(max_priority_fee_per_gas)
+                    .into()
+            }
+            _ => {
+                let current_gas_price = provider
+                    .get_gas_price()
+                    .await
+                    .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+                let gas_price =
+                    bump_fee(pending.gas_price.unwrap_or_default(), args.fee_bump_percent)
+                        .max(current_gas_price);
+                replacement.gas_price = Some(gas_price);
+                TransactionRequest::new()
+                    .from(pending.from)
+                    .nonce(pending.nonce)
+                    .chain_id(chain_id)
+                    .gas_price(gas_price)
+                    .into()
+            }
+        };
+    if cancel {
+        transaction.set_to(pending.from);
+        transaction.set_value(U256::zero());
+        transaction.set_data(Bytes::new());
+        transaction.set_gas(21000);
+    } else {
+        transaction.set_value(pending.value);
+        transaction.set_data(pending.input.clone());
+        transaction.set_gas(pending.gas);
+        if let Some(to) = pending.to {
+            transaction.set_to(to);
+        }
+    }
+    if !args.yes {
+        confirm_execution(&replacement)?;
+    }
+
+    let signature = match (&args.private_key, &args.signer_command) {
+        (Some(private_key), _) => {
+            let wallet = parse_private_key(private_key)?.with_chain_id(chain_id);
+            if wallet.address() != pending.from {
+                return Err(MystikoCliError::AnyhowError(anyhow!(
+                    "Private key does not match the sender {:?}",
+                    pending.from
+                )));
+            }
+            wallet
+                .sign_transaction_sync(&transaction)
+                .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+        }
+        (None, Some(signer_command)) => {
+            sign_with_command(
+                signer_command,
+                chain_id,
+                pending.from,
+                &transaction,
+                args.signer_timeout_ms,
+            )
+            .await?
+        }
+        (None, None) => {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Pass --private-key or --signer-command to sign the replacement"
+            )))
+        }
+    };
+    let sent = provider
+        .send_raw_transaction(transaction.rlp_signed(&signature))
+        .await
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+    replacement.transaction_hash = Some(sent.tx_hash());
+    let record = TransactionReplacementRecord {
+        document_id: args.id.clone(),
+        chain_id,
+        from: pending.from,
+        nonce: pending.nonce,
+        replaced_transaction_hash,
+        transaction_hash: sent.tx_hash(),
+        cancel,
+    };
+    replacements.add(record).await.map_err(|err| {
+        MystikoCliError::AnyhowError(anyhow!(
+            "Replacement {:?} is sent but could not be recorded: {}",
+            sent.tx_hash(),
+            err
+        ))
+    })?;
+    Ok(replacement)
+}
+
+pub fn bump_fee(fee: U256, percent: u64) -> U256 {
+    (fee * U256::from(100 + percent) + U256::from(99)) / U256::from(100)
+}
@@ -0,0 +1,187 @@
+use crate::{print_json, MystikoCliError, SecretString};
+use anyhow::anyhow;
+use mystiko_core::{Mystiko, ScannerHandler, SynchronizerHandler};
+use mystiko_protos::core::scanner::v1::{
+    AssetImportOptions, AssetImportResult, AssetsByChain, AssetsOptions, BalanceOptions,
+    BalanceResult, ScannerResetOptions, ScannerResetResult, ScannerScanOptions, ScannerScanResult,
+    ScannerSyncOptions, ScannerSyncResult,
+};
+use mystiko_protos::core::synchronizer::v1::{
+    SynchronizerResetOptions, SynchronizerStatus, SynchronizerSyncOptions,
+};
+use mystiko_protos::core::v1::{DepositStatus, SpendStatus};
+use mystiko_storage::{StatementFormatter, Storage};
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitEventType {
+    Status,
+    Reached,
+    Failed,
+    Timeout,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WaitEvent {
+    pub event: WaitEventType,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    pub elapsed_secs: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaitState {
+    pub status: String,
+    pub transaction_hash: Option<String>,
+    pub error_message: Option<String>,
+    pub reached: bool,
+    pub failed: bool,
+}
+
+pub const DEPOSIT_STATUS_PROGRESSION: [DepositStatus; 6] = [
+    DepositStatus::AssetApproving,
+    DepositStatus::AssetApproved,
+    DepositStatus::SrcPending,
+    DepositStatus::Queued,
+    DepositStatus::Included,
+    DepositStatus::Executed,
+];
+
+pub const SPEND_STATUS_PROGRESSION: [SpendStatus; 2] =
+    [SpendStatus::Pending, SpendStatus::Succeeded];
+
+pub fn status_reached(status: i32, until: i32, progression: &[i32]) -> bool {
+    if status == until {
+        return true;
+    }
+    let position = |target: i32| progression.iter().position(|status| *status == target);
+    match (position(status), position(until)) {
+        (Some(status), Some(until)) => status > until,
+        _ => false,
+    }
+}
+
+pub fn status_name(name: &str, prefix: &str) -> String {
+    name.trim_start_matches(prefix).to_ascii_lowercase()
+}
+
+pub(crate) async fn sync_chains<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    chain_ids: Vec<u64>,
+    password: &Option<SecretString>,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    Y: SynchronizerHandler<SynchronizerSyncOptions, SynchronizerStatus, SynchronizerResetOptions>,
+    R: ScannerHandler<
+        ScannerSyncOptions,
+        ScannerSyncResult,
+        ScannerScanOptions,
+        ScannerScanResult,
+        ScannerResetOptions,
+        ScannerResetResult,
+        AssetImportOptions,
+        AssetImportResult,
+        BalanceOptions,
+        BalanceResult,
+        AssetsOptions,
+        AssetsByChain,
+    >,
+    MystikoCliError: From<Y::Error> + From<R::Error>,
+{
+    let sync_options = SynchronizerSyncOptions::builder()
+        .chain_ids(chain_ids)
+        .build();
+    mystiko.synchronizer.sync(sync_options).await?;
+    if let Some(password) = password {
+        let scanner_options = ScannerSyncOptions::builder()
+            .wallet_password(password.expose_secret().to_string())
+            .build();
+        mystiko.scanner.sync(scanner_options).await?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn wait_for_status<P, Fut>(
+    id: &str,
+    until: &str,
+    timeout: Duration,
+    interval: Duration,
+    mut poll: P,
+) -> Result<(), MystikoCliError>
+where
+    P: FnMut() -> Fut,
+    Fut: Future<Output = Result<WaitState, MystikoCliError>>,
+{
+    let started = Instant::now();
+    let mut last_state: Option<WaitState> = None;
+    loop {
+        let elapsed_secs = started.elapsed().as_secs();
+        let event = |event, state: &WaitState| WaitEvent {
+            event,
+            id: id.to_string(),
+            status: Some(state.status.clone()),
+            transaction_hash: state.transaction_hash.clone(),
+            error_message: state.error_message.clone(),
+            elapsed_secs,
+        };
+        match poll().await {
+            Ok(state) => {
+                if last_state.as_ref() != Some(&state) {
+                    print_json(&event(WaitEventType::Status, &state), true)?;
+                }
+                if state.reached {
+                    return print_json(&event(WaitEventType::Reached, &state), true);
+                }
+                if state.failed {
+                    print_json(&event(WaitEventType::Failed, &state), true)?;
+                    return Err(MystikoCliError::AnyhowError(anyhow!(
+                        "{} failed before reaching {}",
+                        id,
+                        until
+                    )));
+                }
+                last_state = Some(state);
+            }
+            Err(err) => {
+                let event = WaitEvent {
+                    event: WaitEventType::Error,
+                    id: id.to_string(),
+                    status: None,
+                    transaction_hash: None,
+                    error_message: Some(err.to_string()),
+                    elapsed_secs,
+                };
+                print_json(&event, true)?;
+            }
+        }
+        let remaining = timeout.saturating_sub(started.elapsed());
+        if remaining.is_zero() {
+            let event = WaitEvent {
+                event: WaitEventType::Timeout,
+                id: id.to_string(),
+                status: last_state.map(|state| state.status),
+                transaction_hash: None,
+                error_message: None,
+                elapsed_secs: started.elapsed().as_secs(),
+            };
+            print_json(&event, true)?;
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Timed out waiting for {} to reach {}",
+                id,
+                until
+            )));
+        }
+        tokio::time::sleep(interval.min(remaining)).await;
+    }
+}
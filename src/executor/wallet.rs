@@ -1,15 +1,77 @@
+use crate::executor::AccountDeriver;
 use crate::{
-    print_json, MystikoCliError, WalletCommand, WalletCommands, WalletCreateCommand,
-    WalletExportMnemonicPhraseCommand, WalletImportCommand, WalletUpdatePasswordCommand,
+    combine_mnemonic_shares, expose_required_secret, print_json, split_mnemonic_phrase,
+    validate_mnemonic_phrase, write_private_file, MystikoCliError, PasswordRotationJournal,
+    SecretString, WalletBackup, WalletBackupCommand, WalletBackupPayload, WalletCommand,
+    WalletCommands, WalletCreateCommand, WalletExportMnemonicPhraseCommand, WalletImportCommand,
+    WalletInfoCommand, WalletMnemonicType, WalletRestoreCommand, WalletUpdatePasswordCommand,
+    WalletVerifyPasswordCommand,
 };
 use anyhow::anyhow;
-use mystiko_core::{AccountHandler, Mystiko, WalletHandler};
-use mystiko_protos::core::document::v1::{Account, Wallet};
+use mystiko_core::{
+    AccountHandler, DepositHandler, Mystiko, SpendHandler, SynchronizerHandler, WalletHandler,
+};
+use mystiko_protos::core::document::v1::{Account, Deposit, Spend, Wallet};
 use mystiko_protos::core::handler::v1::{
-    CreateAccountOptions, CreateWalletOptions, MnemonicOptions, UpdateAccountOptions,
+    CreateAccountOptions, CreateDepositOptions, CreateSpendOptions, CreateWalletOptions,
+    DepositQuote, DepositSummary, FixDepositStatusOptions, FixSpendStatusOptions, MnemonicOptions,
+    QuoteDepositOptions, QuoteSpendOptions, SendDepositOptions, SendSpendOptions, SpendQuote,
+    SpendSummary, UpdateAccountOptions,
+};
+use mystiko_protos::core::synchronizer::v1::{
+    SynchronizerResetOptions, SynchronizerStatus, SynchronizerSyncOptions,
 };
-use mystiko_protos::core::v1::MnemonicType;
+use mystiko_protos::core::v1::{AccountStatus, MnemonicType};
 use mystiko_storage::{StatementFormatter, Storage};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WalletBackupReport {
+    pub file: String,
+    pub version: u32,
+    pub checksum: String,
+    pub wallet_id: String,
+    pub accounts: usize,
+    pub deposits: usize,
+    pub spends: usize,
+    pub scanner_progress: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WalletImportPreview {
+    pub mnemonic_type: String,
+    pub word_count: usize,
+    pub shielded_address: String,
+    pub public_key: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WalletInfo {
+    pub exists: bool,
+    pub wallet_id: Option<String>,
+    pub mnemonic_type: Option<String>,
+    pub account_nonce: Option<u32>,
+    pub account_count: usize,
+    pub accounts: Vec<WalletAccountInfo>,
+    pub synchronizer: SynchronizerStatus,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WalletAccountInfo {
+    pub name: String,
+    pub shielded_address: String,
+    pub status: String,
+    pub scan_size: u32,
+    pub scanned_to_id: Option<String>,
+    pub updated_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WalletPasswordVerification {
+    pub wallet_id: String,
+}
 
 pub async fn execute_wallet_command<F, S, W, A, D, X, Y, R>(
     mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
@@ -21,7 +83,27 @@ where
     S: Storage,
     W: WalletHandler<Wallet, CreateWalletOptions>,
     A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
-    MystikoCliError: From<W::Error> + From<A::Error>,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    Y: SynchronizerHandler<SynchronizerSyncOptions, SynchronizerStatus, SynchronizerResetOptions>,
+    MystikoCliError:
+        From<W::Error> + From<A::Error> + From<D::Error> + From<X::Error> + From<Y::Error>,
 {
     match args.commands {
         WalletCommands::Create(args) => {
@@ -34,7 +116,19 @@ where
             execute_wallet_export_mnemonic_phrase_command(mystiko, args).await
         }
         WalletCommands::UpdatePassword(args) => {
-            execute_wallet_update_password_command(mystiko, args, compact_json).await
+            execute_wallet_update_password_command(mystiko, args, None, compact_json).await
+        }
+        WalletCommands::Info(args) => {
+            execute_wallet_info_command(mystiko, args, compact_json).await
+        }
+        WalletCommands::VerifyPassword(args) => {
+            execute_wallet_verify_password_command(mystiko, args, compact_json).await
+        }
+        WalletCommands::Backup(args) => {
+            execute_wallet_backup_command(mystiko, args, compact_json).await
+        }
+        WalletCommands::Restore(args) => {
+            execute_wallet_restore_command(mystiko, args, compact_json).await
         }
     }
 }
@@ -51,7 +145,7 @@ where
     MystikoCliError: From<W::Error>,
 {
     let options = CreateWalletOptions::builder()
-        .password(args.password)
+        .password(args.password.expose_secret())
         .build();
     let wallet = mystiko.wallets.create(&options).await?;
     print_json(&wallet, compact_json)
@@ -66,26 +160,53 @@ where
     F: StatementFormatter,
     S: Storage,
     W: WalletHandler<Wallet, CreateWalletOptions>,
-    MystikoCliError: From<W::Error>,
+    A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
+    MystikoCliError: From<W::Error> + From<A::Error>,
 {
-    let words = args.mnemonic.split_whitespace().collect::<Vec<&str>>();
-    let mnemonic = if words.len() == 12 {
-        MnemonicOptions::builder()
-            .mnemonic_phrase(args.mnemonic)
-            .mnemonic_type(MnemonicType::Web)
-            .build()
-    } else if words.len() == 24 {
-        MnemonicOptions::builder()
-            .mnemonic_phrase(args.mnemonic)
-            .mnemonic_type(MnemonicType::Rust)
-            .build()
+    let (mnemonic, share_mnemonic_type) = if let Some(share_files) = &args.shares {
+        let mut shares = vec![];
+        for share_file in share_files.iter() {
+            shares.push(tokio::fs::read_to_string(share_file).await?);
+        }
+        let (mnemonic, mnemonic_type) = combine_mnemonic_shares(&shares)?;
+        (mnemonic, Some(mnemonic_type))
     } else {
+        (
+            validate_mnemonic_phrase(expose_required_secret(&args.mnemonic, "Mnemonic phrase")?)?,
+            None,
+        )
+    };
+    let word_count = mnemonic.word_count();
+    let mnemonic_type = match (args.mnemonic_type, share_mnemonic_type) {
+        (Some(WalletMnemonicType::Web), _) => MnemonicType::Web,
+        (Some(WalletMnemonicType::Rust), _) => MnemonicType::Rust,
+        (None, Some(share_mnemonic_type)) => share_mnemonic_type,
+        (None, None) if word_count == 12 => MnemonicType::Web,
+        (None, None) => MnemonicType::Rust,
+    };
+    if share_mnemonic_type.is_some_and(|share_mnemonic_type| share_mnemonic_type != mnemonic_type) {
         return Err(MystikoCliError::AnyhowError(anyhow!(
-            "Invalid mnemonic phrase"
+            "The mnemonic shares were split from a {} mnemonic phrase",
+            share_mnemonic_type.unwrap_or_default().as_str_name()
         )));
-    };
+    }
+    let mnemonic = MnemonicOptions::builder()
+        .mnemonic_phrase(mnemonic.to_string())
+        .mnemonic_type(mnemonic_type)
+        .build();
+    if args.preview {
+        let deriver = AccountDeriver::new(args.password.expose_secret(), mnemonic).await?;
+        let (account, _) = deriver.derive(0).await?;
+        let preview = WalletImportPreview {
+            mnemonic_type: mnemonic_type.as_str_name().to_string(),
+            word_count,
+            shielded_address: account.shielded_address,
+            public_key: account.public_key,
+        };
+        return print_json(&preview, compact_json);
+    }
     let options = CreateWalletOptions::builder()
-        .password(args.password)
+        .password(args.password.expose_secret())
         .mnemonic(mnemonic)
         .build();
     let wallet = mystiko.wallets.create(&options).await?;
@@ -102,17 +223,41 @@ where
     W: WalletHandler<Wallet, CreateWalletOptions>,
     MystikoCliError: From<W::Error>,
 {
-    let mnemonic_phrase = mystiko
+    let wallet = mystiko
         .wallets
-        .export_mnemonic_phrase(&args.password)
+        .check_password(args.password.expose_secret())
         .await?;
-    println!("{}", mnemonic_phrase);
+    let mnemonic_phrase = SecretString::from(
+        mystiko
+            .wallets
+            .export_mnemonic_phrase(args.password.expose_secret())
+            .await?,
+    );
+    if let (Some(shares), Some(threshold), Some(out_dir)) =
+        (args.shares, args.threshold, args.out_dir)
+    {
+        let shares = split_mnemonic_phrase(
+            mnemonic_phrase.expose_secret(),
+            MnemonicType::from_i32(wallet.mnemonic_type).unwrap_or_default(),
+            shares,
+            threshold,
+        )?;
+        tokio::fs::create_dir_all(&out_dir).await?;
+        for (index, share) in shares.iter().enumerate() {
+            let share_file = Path::new(&out_dir).join(format!("share-{}.txt", index + 1));
+            write_private_file(&share_file, share).await?;
+            println!("{}", share_file.to_string_lossy());
+        }
+    } else {
+        println!("{}", mnemonic_phrase.expose_secret());
+    }
     Ok(())
 }
 
 pub async fn execute_wallet_update_password_command<F, S, W, A, D, X, Y, R>(
     mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
     args: WalletUpdatePasswordCommand,
+    journal_path: Option<&Path>,
     compact_json: bool,
 ) -> Result<(), MystikoCliError>
 where
@@ -121,14 +266,347 @@ where
     W: WalletHandler<Wallet, CreateWalletOptions>,
     A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
     MystikoCliError: From<W::Error> + From<A::Error>,
+{
+    if let Some(journal_path) = journal_path {
+        if let Some(journal) = PasswordRotationJournal::read(journal_path).await? {
+            restore_password_rotation_journal(mystiko, &journal).await?;
+            PasswordRotationJournal::remove(journal_path).await?;
+            eprintln!(
+                "Warning: restored the wallet from the interrupted password rotation in {}, \
+                 the password from before that rotation is in effect",
+                journal_path.display()
+            );
+        }
+    }
+    let journal = PasswordRotationJournal {
+        wallet: mystiko
+            .wallets
+            .check_password(args.old.expose_secret())
+            .await?,
+        accounts: mystiko.accounts.find_all().await?,
+    };
+    if let Some(journal_path) = journal_path {
+        journal.write(journal_path).await?;
+    }
+    match rotate_wallet_password(mystiko, args.old.expose_secret(), args.new.expose_secret()).await
+    {
+        Ok(wallet) => {
+            if let Some(journal_path) = journal_path {
+                PasswordRotationJournal::remove(journal_path)
+                    .await
+                    .map_err(|err| {
+                        anyhow!(
+                            "The password is updated but the rotation journal {} could not be \
+                             removed and would roll it back on the next update-password: {}",
+                            journal_path.display(),
+                            err
+                        )
+                    })?;
+            }
+            print_json(&wallet, compact_json)
+        }
+        Err(err) => match restore_password_rotation_journal(mystiko, &journal).await {
+            Ok(()) => {
+                if let Some(journal_path) = journal_path {
+                    PasswordRotationJournal::remove(journal_path).await.ok();
+                }
+                Err(err)
+            }
+            Err(rollback_err) => Err(MystikoCliError::AnyhowError(match journal_path {
+                Some(journal_path) => anyhow!(
+                    "{}, and rolling back the password rotation failed: {}, \
+                     the previous wallet is kept in {} and restored by the next update-password",
+                    err,
+                    rollback_err,
+                    journal_path.display()
+                ),
+                None => anyhow!(
+                    "{}, and rolling back the password rotation failed: {}",
+                    err,
+                    rollback_err
+                ),
+            })),
+        },
+    }
+}
+
+pub async fn execute_wallet_info_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: WalletInfoCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    W: WalletHandler<Wallet, CreateWalletOptions>,
+    A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
+    Y: SynchronizerHandler<SynchronizerSyncOptions, SynchronizerStatus, SynchronizerResetOptions>,
+    MystikoCliError: From<W::Error> + From<A::Error> + From<Y::Error>,
+{
+    let wallet = mystiko.wallets.current().await?;
+    let accounts = if wallet.is_some() {
+        mystiko.accounts.find_all().await?
+    } else {
+        vec![]
+    };
+    let synchronizer = mystiko.synchronizer.status(args.with_contracts).await?;
+    let mut warnings = vec![];
+    if wallet.is_none() {
+        warnings.push(String::from(
+            "no wallet found, create or import a wallet first",
+        ));
+    } else if accounts.is_empty() {
+        warnings.push(String::from("wallet has no accounts"));
+    }
+    for account in accounts.iter() {
+        if account.scanned_to_id.is_none() {
+            warnings.push(format!(
+                "account {} ({}) has never been scanned",
+                account.name, account.shielded_address
+            ));
+        }
+    }
+    let info = WalletInfo {
+        exists: wallet.is_some(),
+        wallet_id: wallet.as_ref().map(|wallet| wallet.id.clone()),
+        mnemonic_type: wallet.as_ref().map(|wallet| {
+            MnemonicType::from_i32(wallet.mnemonic_type)
+                .unwrap_or_default()
+                .as_str_name()
+                .to_string()
+        }),
+        account_nonce: wallet.as_ref().map(|wallet| wallet.account_nonce),
+        account_count: accounts.len(),
+        accounts: accounts
+            .into_iter()
+            .map(|account| WalletAccountInfo {
+                status: AccountStatus::from_i32(account.status)
+                    .unwrap_or_default()
+                    .as_str_name()
+                    .to_string(),
+                name: account.name,
+                shielded_address: account.shielded_address,
+                scan_size: account.scan_size,
+                scanned_to_id: account.scanned_to_id,
+                updated_at: account.updated_at,
+            })
+            .collect(),
+        synchronizer,
+        warnings,
+    };
+    print_json(&info, compact_json)
+}
+
+pub async fn execute_wallet_verify_password_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: WalletVerifyPasswordCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    W: WalletHandler<Wallet, CreateWalletOptions>,
+    MystikoCliError: From<W::Error>,
 {
     let wallet = mystiko
         .wallets
-        .update_password(&args.old, &args.new)
+        .check_password(args.password.expose_secret())
+        .await?;
+    let verification = WalletPasswordVerification {
+        wallet_id: wallet.id,
+    };
+    print_json(&verification, compact_json)
+}
+
+async fn restore_password_rotation_journal<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    journal: &PasswordRotationJournal,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+{
+    mystiko
+        .db
+        .wallets
+        .update_batch(std::slice::from_ref(&journal.wallet))
+        .await?;
+    if !journal.accounts.is_empty() {
+        mystiko.db.accounts.update_batch(&journal.accounts).await?;
+    }
+    Ok(())
+}
+
+async fn rotate_wallet_password<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    old_password: &str,
+    new_password: &str,
+) -> Result<Wallet, MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    W: WalletHandler<Wallet, CreateWalletOptions>,
+    A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
+    MystikoCliError: From<W::Error> + From<A::Error>,
+{
+    let wallet = mystiko
+        .wallets
+        .update_password(old_password, new_password)
         .await?;
     mystiko
         .accounts
-        .update_encryption(&args.old, &args.new)
+        .update_encryption(old_password, new_password)
         .await?;
-    print_json(&wallet, compact_json)
+    Ok(wallet)
+}
+
+pub async fn execute_wallet_backup_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: WalletBackupCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    W: WalletHandler<Wallet, CreateWalletOptions>,
+    A: AccountHandler<Account, CreateAccountOptions, UpdateAccountOptions>,
+    D: DepositHandler<
+        Deposit,
+        QuoteDepositOptions,
+        DepositQuote,
+        CreateDepositOptions,
+        DepositSummary,
+        SendDepositOptions,
+        FixDepositStatusOptions,
+    >,
+    X: SpendHandler<
+        Spend,
+        QuoteSpendOptions,
+        SpendQuote,
+        CreateSpendOptions,
+        SpendSummary,
+        SendSpendOptions,
+        FixSpendStatusOptions,
+    >,
+    MystikoCliError: From<W::Error> + From<A::Error> + From<D::Error> + From<X::Error>,
+{
+    let wallet = mystiko
+        .wallets
+        .check_password(args.password.expose_secret())
+        .await?;
+    let mut accounts = mystiko.accounts.find_all().await?;
+    if !args.with_scanner_progress {
+        accounts.iter_mut().for_each(|account| {
+            account.scanned_to_id = None;
+            account.status = AccountStatus::Created as i32;
+        });
+    }
+    let payload = WalletBackupPayload {
+        wallet,
+        accounts,
+        deposits: mystiko.deposits.find_all().await?,
+        spends: mystiko.spends.find_all().await?,
+        scanner_progress: args.with_scanner_progress,
+    };
+    let backup = WalletBackup::encrypt(&payload, args.backup_passphrase.expose_secret())?;
+    backup.write(&args.out).await?;
+    print_json(
+        &create_backup_report(args.out, &backup, &payload),
+        compact_json,
+    )
+}
+
+pub async fn execute_wallet_restore_command<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    args: WalletRestoreCommand,
+    compact_json: bool,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+    W: WalletHandler<Wallet, CreateWalletOptions>,
+    MystikoCliError: From<W::Error>,
+{
+    if mystiko.wallets.current().await?.is_some() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "A wallet already exists, restore requires an empty database"
+        )));
+    }
+    let backup = WalletBackup::read(&args.file).await?;
+    let payload = backup.decrypt(args.backup_passphrase.expose_secret())?;
+    if let Err(err) = restore_wallet_backup_payload(mystiko, &payload).await {
+        if let Err(rollback_err) = rollback_wallet_backup_payload(mystiko, &payload).await {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "{}, and rolling back the restored documents failed: {}",
+                err,
+                rollback_err
+            )));
+        }
+        return Err(err);
+    }
+    print_json(
+        &create_backup_report(args.file, &backup, &payload),
+        compact_json,
+    )
+}
+
+async fn restore_wallet_backup_payload<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    payload: &WalletBackupPayload,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+{
+    mystiko
+        .db
+        .wallets
+        .insert_batch(std::slice::from_ref(&payload.wallet))
+        .await?;
+    if !payload.accounts.is_empty() {
+        mystiko.db.accounts.insert_batch(&payload.accounts).await?;
+    }
+    if !payload.deposits.is_empty() {
+        mystiko.db.deposits.insert_batch(&payload.deposits).await?;
+    }
+    if !payload.spends.is_empty() {
+        mystiko.db.spends.insert_batch(&payload.spends).await?;
+    }
+    Ok(())
+}
+
+async fn rollback_wallet_backup_payload<F, S, W, A, D, X, Y, R>(
+    mystiko: &Mystiko<F, S, W, A, D, X, Y, R>,
+    payload: &WalletBackupPayload,
+) -> Result<(), MystikoCliError>
+where
+    F: StatementFormatter,
+    S: Storage,
+{
+    mystiko.db.spends.delete_batch(&payload.spends).await?;
+    mystiko.db.deposits.delete_batch(&payload.deposits).await?;
+    mystiko.db.accounts.delete_batch(&payload.accounts).await?;
+    mystiko
+        .db
+        .wallets
+        .delete_batch(std::slice::from_ref(&payload.wallet))
+        .await?;
+    Ok(())
+}
+
+fn create_backup_report(
+    file: String,
+    backup: &WalletBackup,
+    payload: &WalletBackupPayload,
+) -> WalletBackupReport {
+    WalletBackupReport {
+        file,
+        version: backup.version,
+        checksum: backup.checksum.clone(),
+        wallet_id: payload.wallet.id.clone(),
+        accounts: payload.accounts.len(),
+        deposits: payload.deposits.len(),
+        spends: payload.spends.len(),
+        scanner_progress: payload.scanner_progress,
+    }
 }
@@ -0,0 +1,253 @@
+use crate::MystikoCliError;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, Signature, TxHash, H256};
+use ethers_providers::{Http, Middleware, Provider};
+use ethers_signers::{LocalWallet, Signer};
+use mystiko_core::TransactionSigner;
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::process::Command;
+
+pub const SIGNER_COMMAND_TIMEOUT_MS: u64 = 120_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SignerRequest {
+    Address,
+    SignTransaction {
+        chain_id: u64,
+        from: Address,
+        transaction: Box<TypedTransaction>,
+        digest: H256,
+    },
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SignerResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CommandSigner {
+    command: String,
+    address: Address,
+    provider: Provider<Http>,
+    timeout_ms: Option<u64>,
+}
+
+impl CommandSigner {
+    pub async fn new(
+        command: &str,
+        provider: &str,
+        timeout_ms: Option<u64>,
+    ) -> Result<Self, MystikoCliError> {
+        Ok(CommandSigner {
+            command: command.to_string(),
+            address: request_signer_address(command, timeout_ms).await?,
+            provider: Provider::<Http>::try_from(provider)
+                .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?,
+            timeout_ms,
+        })
+    }
+
+    pub async fn sign_transaction(
+        &self,
+        chain_id: u64,
+        transaction: &TypedTransaction,
+    ) -> Result<Signature, MystikoCliError> {
+        sign_with_command(
+            &self.command,
+            chain_id,
+            self.address,
+            transaction,
+            self.timeout_ms,
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for CommandSigner {
+    async fn address(&self) -> anyhow::Result<Address> {
+        Ok(self.address)
+    }
+
+    async fn send_transaction(
+        &self,
+        chain_id: u64,
+        tx: TypedTransaction,
+    ) -> anyhow::Result<TxHash> {
+        let mut tx = tx;
+        tx.set_chain_id(chain_id);
+        tx.set_from(self.address);
+        self.provider.fill_transaction(&mut tx, None).await?;
+        let signature = self.sign_transaction(chain_id, &tx).await?;
+        let pending = self
+            .provider
+            .send_raw_transaction(tx.rlp_signed(&signature))
+            .await?;
+        Ok(pending.tx_hash())
+    }
+}
+
+pub async fn request_signer_address(
+    command: &str,
+    timeout_ms: Option<u64>,
+) -> Result<Address, MystikoCliError> {
+    send_signer_request(command, &SignerRequest::Address, timeout_ms)
+        .await?
+        .address
+        .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!("Signer command returned no address")))
+}
+
+pub async fn sign_with_command(
+    command: &str,
+    chain_id: u64,
+    from: Address,
+    transaction: &TypedTransaction,
+    timeout_ms: Option<u64>,
+) -> Result<Signature, MystikoCliError> {
+    let mut transaction = transaction.clone();
+    transaction.set_chain_id(chain_id);
+    let digest = transaction.sighash();
+    let request = SignerRequest::SignTransaction {
+        chain_id,
+        from,
+        transaction: Box::new(transaction),
+        digest,
+    };
+    let signature = send_signer_request(command, &request, timeout_ms)
+        .await?
+        .signature
+        .ok_or_else(|| {
+            MystikoCliError::AnyhowError(anyhow!("Signer command returned no signature"))
+        })?
+        .parse::<Signature>()
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!("Invalid signature: {}", err)))?;
+    signature.verify(digest, from).map_err(|_| {
+        MystikoCliError::AnyhowError(anyhow!(
+            "Signature from the signer command does not match {:?}",
+            from
+        ))
+    })?;
+    Ok(signature)
+}
+
+pub async fn send_signer_request(
+    command: &str,
+    request: &SignerRequest,
+    timeout_ms: Option<u64>,
+) -> Result<SignerResponse, MystikoCliError> {
+    send_signer_request_with_timeout(
+        command,
+        request,
+        Duration::from_millis(timeout_ms.unwrap_or(SIGNER_COMMAND_TIMEOUT_MS)),
+    )
+    .await
+}
+
+pub async fn send_signer_request_with_timeout(
+    command: &str,
+    request: &SignerRequest,
+    timeout: Duration,
+) -> Result<SignerResponse, MystikoCliError> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()?;
+    let mut request = serde_json::to_string(request)?;
+    request.push('\n');
+    let mut output = String::new();
+    let exchanged = tokio::time::timeout(timeout, async {
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(request.as_bytes()).await?;
+        }
+        if let Some(mut stdout) = child.stdout.take() {
+            stdout.read_to_string(&mut output).await?;
+        }
+        child.wait().await
+    })
+    .await;
+    let status = match exchanged {
+        Ok(status) => status?,
+        Err(_) => {
+            let _ = child.kill().await;
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Signer command timed out after {} ms",
+                timeout.as_millis()
+            )));
+        }
+    };
+    if !status.success() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Signer command exited with {}",
+            status
+        )));
+    }
+    let response: SignerResponse = serde_json::from_str(output.trim())?;
+    match response.error {
+        Some(error) => Err(MystikoCliError::AnyhowError(anyhow!(
+            "Signer command failed: {}",
+            error
+        ))),
+        None => Ok(response),
+    }
+}
+
+pub fn handle_signer_request(wallet: &LocalWallet, request: SignerRequest) -> SignerResponse {
+    match request {
+        SignerRequest::Address => SignerResponse {
+            address: Some(wallet.address()),
+            ..SignerResponse::default()
+        },
+        SignerRequest::SignTransaction {
+            chain_id,
+            from,
+            transaction,
+            digest,
+        } => {
+            let mut transaction = *transaction;
+            transaction.set_chain_id(chain_id);
+            let error = if from != wallet.address() {
+                Some(format!("unknown signing address {:?}", from))
+            } else if transaction.sighash() != digest {
+                Some(String::from("digest does not match the transaction"))
+            } else {
+                None
+            };
+            if error.is_some() {
+                return SignerResponse {
+                    error,
+                    ..SignerResponse::default()
+                };
+            }
+            match wallet
+                .clone()
+                .with_chain_id(chain_id)
+                .sign_transaction_sync(&transaction)
+            {
+                Ok(signature) => SignerResponse {
+                    signature: Some(format!("0x{}", signature)),
+                    ..SignerResponse::default()
+                },
+                Err(err) => SignerResponse {
+                    error: Some(err.to_string()),
+                    ..SignerResponse::default()
+                },
+            }
+        }
+    }
+}
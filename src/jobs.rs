@@ -0,0 +1,329 @@
+use crate::{connect_sqlite, connect_sqlite_memory, MystikoCliError};
+use anyhow::anyhow;
+use clap::ValueEnum;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendJobStatus {
+    Scheduled,
+    Running,
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpendJob {
+    pub id: u64,
+    pub status: SpendJobStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner_pid: Option<u32>,
+    pub created_at: u64,
+    pub execute_at: u64,
+    pub chain_id: u64,
+    pub asset_symbol: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spend_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bridge_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u32>,
+    pub recipient: String,
+    pub amount: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rollup_fee: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relayer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_timeout_ms: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirmations: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executed_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spend_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct JobStore {
+    pool: SqlitePool,
+}
+
+impl SpendJobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SpendJobStatus::Scheduled => "scheduled",
+            SpendJobStatus::Running => "running",
+            SpendJobStatus::Succeeded => "succeeded",
+            SpendJobStatus::Failed => "failed",
+            SpendJobStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn from_str_name(value: &str) -> Option<Self> {
+        match value {
+            "scheduled" => Some(SpendJobStatus::Scheduled),
+            "running" => Some(SpendJobStatus::Running),
+            "succeeded" => Some(SpendJobStatus::Succeeded),
+            "failed" => Some(SpendJobStatus::Failed),
+            "cancelled" => Some(SpendJobStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+impl SpendJob {
+    fn from_row(row: &SqliteRow) -> Result<Self, MystikoCliError> {
+        let status: String = row.try_get("status")?;
+        Ok(SpendJob {
+            id: row.try_get::<i64, _>("id")? as u64,
+            status: SpendJobStatus::from_str_name(&status).ok_or_else(|| {
+                MystikoCliError::AnyhowError(anyhow!("Unknown job status {}", status))
+            })?,
+            owner_pid: row
+                .try_get::<Option<i64>, _>("owner_pid")?
+                .map(|pid| pid as u32),
+            created_at: row.try_get::<i64, _>("created_at")? as u64,
+            execute_at: row.try_get::<i64, _>("execute_at")? as u64,
+            chain_id: row.try_get::<i64, _>("chain_id")? as u64,
+            asset_symbol: row.try_get("asset_symbol")?,
+            spend_type: row.try_get("spend_type")?,
+            bridge_type: row.try_get("bridge_type")?,
+            version: row
+                .try_get::<Option<i64>, _>("version")?
+                .map(|version| version as u32),
+            recipient: row.try_get("recipient")?,
+            amount: row.try_get("amount")?,
+            rollup_fee: row.try_get("rollup_fee")?,
+            relayer: row.try_get("relayer")?,
+            query_timeout_ms: row
+                .try_get::<Option<i64>, _>("query_timeout_ms")?
+                .map(|timeout| timeout as u64),
+            confirmations: row
+                .try_get::<Option<i64>, _>("confirmations")?
+                .map(|confirmations| confirmations as u64),
+            executed_at: row
+                .try_get::<Option<i64>, _>("executed_at")?
+                .map(|executed_at| executed_at as u64),
+            spend_id: row.try_get("spend_id")?,
+            transaction_hash: row.try_get("transaction_hash")?,
+            error: row.try_get("error")?,
+        })
+    }
+}
+
+impl JobStore {
+    pub async fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, MystikoCliError> {
+        JobStore::from_pool(connect_sqlite(path).await?).await
+    }
+
+    pub async fn from_memory() -> Result<Self, MystikoCliError> {
+        JobStore::from_pool(connect_sqlite_memory().await?).await
+    }
+
+    pub async fn from_pool(pool: SqlitePool) -> Result<Self, MystikoCliError> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS spend_job (\
+             id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL, \
+             status TEXT NOT NULL, \
+             owner_pid INTEGER, \
+             created_at INTEGER NOT NULL, \
+             execute_at INTEGER NOT NULL, \
+             chain_id INTEGER NOT NULL, \
+             asset_symbol TEXT NOT NULL, \
+             spend_type TEXT, \
+             bridge_type TEXT, \
+             version INTEGER, \
+             recipient TEXT NOT NULL, \
+             amount TEXT NOT NULL, \
+             rollup_fee REAL, \
+             relayer TEXT, \
+             query_timeout_ms INTEGER, \
+             confirmations INTEGER, \
+             executed_at INTEGER, \
+             spend_id TEXT, \
+             transaction_hash TEXT, \
+             error TEXT)",
+        )
+        .execute(&pool)
+        .await?;
+        Ok(JobStore { pool })
+    }
+
+    pub async fn jobs(&self) -> Result<Vec<SpendJob>, MystikoCliError> {
+        sqlx::query("SELECT * FROM spend_job ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(SpendJob::from_row)
+            .collect()
+    }
+
+    pub async fn find(&self, id: u64) -> Result<Option<SpendJob>, MystikoCliError> {
+        sqlx::query("SELECT * FROM spend_job WHERE id = ?")
+            .bind(id as i64)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(SpendJob::from_row)
+            .transpose()
+    }
+
+    pub async fn add(&self, mut job: SpendJob) -> Result<SpendJob, MystikoCliError> {
+        let inserted = sqlx::query(
+            "INSERT INTO spend_job (status, owner_pid, created_at, execute_at, chain_id, \
+             asset_symbol, spend_type, bridge_type, version, recipient, amount, rollup_fee, \
+             relayer, query_timeout_ms, confirmations, executed_at, spend_id, transaction_hash, \
+             error) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(job.status.as_str())
+        .bind(job.owner_pid.map(i64::from))
+        .bind(job.created_at as i64)
+        .bind(job.execute_at as i64)
+        .bind(job.chain_id as i64)
+        .bind(&job.asset_symbol)
+        .bind(&job.spend_type)
+        .bind(&job.bridge_type)
+        .bind(job.version.map(i64::from))
+        .bind(&job.recipient)
+        .bind(&job.amount)
+        .bind(job.rollup_fee)
+        .bind(&job.relayer)
+        .bind(job.query_timeout_ms.map(|timeout| timeout as i64))
+        .bind(job.confirmations.map(|confirmations| confirmations as i64))
+        .bind(job.executed_at.map(|executed_at| executed_at as i64))
+        .bind(&job.spend_id)
+        .bind(&job.transaction_hash)
+        .bind(&job.error)
+        .execute(&self.pool)
+        .await?;
+        job.id = inserted.last_insert_rowid() as u64;
+        Ok(job)
+    }
+
+    pub async fn update(&self, job: &SpendJob) -> Result<(), MystikoCliError> {
+        let updated = sqlx::query(
+            "UPDATE spend_job SET status = ?, owner_pid = ?, executed_at = ?, spend_id = ?, \
+             transaction_hash = ?, error = ? WHERE id = ?",
+        )
+        .bind(job.status.as_str())
+        .bind(job.owner_pid.map(i64::from))
+        .bind(job.executed_at.map(|executed_at| executed_at as i64))
+        .bind(&job.spend_id)
+        .bind(&job.transaction_hash)
+        .bind(&job.error)
+        .bind(job.id as i64)
+        .execute(&self.pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Job {} not found",
+                job.id
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn transition(
+        &self,
+        id: u64,
+        from: SpendJobStatus,
+        to: SpendJobStatus,
+    ) -> Result<Option<SpendJob>, MystikoCliError> {
+        let owner_pid = if to == SpendJobStatus::Running {
+            Some(std::process::id() as i64)
+        } else {
+            None
+        };
+        let updated = sqlx::query(
+            "UPDATE spend_job SET status = ?, owner_pid = ? WHERE id = ? AND status = ?",
+        )
+        .bind(to.as_str())
+        .bind(owner_pid)
+        .bind(id as i64)
+        .bind(from.as_str())
+        .execute(&self.pool)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.find(id).await
+    }
+
+    pub async fn fail_abandoned(&self, error: &str) -> Result<Vec<SpendJob>, MystikoCliError> {
+        let mut failed = vec![];
+        for job in self.jobs().await? {
+            if job.status != SpendJobStatus::Running || job.owner_pid.is_some_and(process_alive) {
+                continue;
+            }
+            let updated = sqlx::query(
+                "UPDATE spend_job SET status = ?, error = ? \
+                 WHERE id = ? AND status = ? AND owner_pid IS ?",
+            )
+            .bind(SpendJobStatus::Failed.as_str())
+            .bind(error)
+            .bind(job.id as i64)
+            .bind(SpendJobStatus::Running.as_str())
+            .bind(job.owner_pid.map(i64::from))
+            .execute(&self.pool)
+            .await?;
+            if updated.rows_affected() > 0 {
+                failed.extend(self.find(job.id).await?);
+            }
+        }
+        Ok(failed)
+    }
+}
+
+pub fn process_alive(pid: u32) -> bool {
+    let alive = unsafe { libc::kill(pid as libc::pid_t, 0) } == 0;
+    alive || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+pub fn current_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+pub fn schedule_timestamp(
+    now: u64,
+    at: Option<SystemTime>,
+    delay: Option<Duration>,
+    delay_max: Option<Duration>,
+) -> Result<u64, MystikoCliError> {
+    if let Some(at) = at {
+        let at = at
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        if at < now {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "The scheduled time is in the past"
+            )));
+        }
+        return Ok(at);
+    }
+    let delay = delay.unwrap_or_default();
+    let delay = match delay_max {
+        Some(delay_max) if delay_max < delay => {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "--delay-max must not be shorter than --delay"
+            )));
+        }
+        Some(delay_max) => rand::thread_rng().gen_range(delay..=delay_max),
+        None => delay,
+    };
+    Ok(now + delay.as_secs())
+}
@@ -0,0 +1,90 @@
+use crate::MystikoCliError;
+use anyhow::anyhow;
+use mystiko_crypto::crypto::{decrypt_symmetric, encrypt_symmetric};
+use mystiko_protos::core::document::v1::Account;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+
+pub const ACCOUNT_KEYSTORE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountKeystore {
+    pub version: u32,
+    pub name: String,
+    pub shielded_address: String,
+    pub public_key: String,
+    pub encrypted_secret_key: String,
+}
+
+impl AccountKeystore {
+    pub fn encrypt(
+        account: &Account,
+        secret_key: &str,
+        passphrase: &str,
+    ) -> Result<Self, MystikoCliError> {
+        Ok(AccountKeystore {
+            version: ACCOUNT_KEYSTORE_VERSION,
+            name: account.name.clone(),
+            shielded_address: account.shielded_address.clone(),
+            public_key: account.public_key.clone(),
+            encrypted_secret_key: encrypt_symmetric(passphrase, secret_key)?,
+        })
+    }
+
+    pub fn decrypt(&self, passphrase: &str) -> Result<String, MystikoCliError> {
+        if self.version != ACCOUNT_KEYSTORE_VERSION {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Unsupported keystore version {}",
+                self.version
+            )));
+        }
+        Ok(decrypt_symmetric(passphrase, &self.encrypted_secret_key)?)
+    }
+
+    pub async fn read<P: AsRef<Path>>(path: P) -> Result<Self, MystikoCliError> {
+        let content = tokio::fs::read_to_string(path).await?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub async fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), MystikoCliError> {
+        write_private_file(path, &serde_json::to_string_pretty(self)?).await
+    }
+}
+
+pub(crate) async fn write_private_file<P: AsRef<Path>>(
+    path: P,
+    content: &str,
+) -> Result<(), MystikoCliError> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)
+        .await?;
+    file.write_all(content.as_bytes()).await?;
+    file.flush().await?;
+    Ok(())
+}
+
+pub(crate) async fn replace_private_file<P: AsRef<Path>>(
+    path: P,
+    content: &str,
+) -> Result<(), MystikoCliError> {
+    let path = path.as_ref();
+    let mut temp_file_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_file_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_file_name);
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&temp_path)
+        .await?;
+    file.write_all(content.as_bytes()).await?;
+    file.sync_all().await?;
+    tokio::fs::rename(&temp_path, path).await?;
+    Ok(())
+}
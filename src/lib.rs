@@ -1,13 +1,42 @@
 mod about;
+mod agent;
 mod args;
+mod audit;
+mod backup;
+mod batch;
 mod error;
 mod executor;
+mod external_signer;
+mod jobs;
+mod keystore;
+mod mnemonic;
+mod offline;
+mod prompt;
+mod replacement;
+mod secret;
+mod signer;
+mod sqlite;
 
 pub use about::*;
+pub use agent::*;
 pub use args::*;
+pub use audit::*;
+pub use backup::*;
+pub use batch::*;
 pub use error::*;
 pub use executor::*;
+pub use external_signer::*;
+pub use jobs::*;
+pub use keystore::*;
+pub use mnemonic::*;
+pub use offline::*;
+pub use prompt::*;
+pub use replacement::*;
+pub use secret::*;
+pub use signer::*;
+pub use sqlite::*;
 
+use anyhow::anyhow;
 use log::LevelFilter;
 use mystiko_core::{
     AccountHandler, Database, DepositHandler, Mystiko, MystikoOptions, ScannerHandler,
@@ -36,15 +65,124 @@ use serde::Serialize;
 use std::path::PathBuf;
 
 pub async fn execute(args: MystikoCliArgs) -> Result<(), MystikoCliError> {
-    if let MystikoCommands::About = args.commands {
-        print_json(&AboutInfo::default(), false)
-    } else {
-        let _ = env_logger::builder()
-            .filter_module("", args.extern_logging_level.parse::<LevelFilter>()?)
-            .filter_module("mystiko_core", args.logging_level.parse::<LevelFilter>()?)
-            .try_init();
-        let mystiko = create_mystiko(&args).await?;
-        execute_with_mystiko(&mystiko, args.commands, args.compact_json).await
+    match &args.commands {
+        MystikoCommands::About => print_json(&AboutInfo::default(), false),
+        MystikoCommands::Config(config_args) => {
+            execute_config_command(config_args.clone(), args.compact_json).await
+        }
+        MystikoCommands::Sign(sign_args) => {
+            execute_sign_command(sign_args.clone(), args.compact_json).await
+        }
+        MystikoCommands::ReferenceSigner(signer_args) => {
+            execute_reference_signer_command(signer_args.clone()).await
+        }
+        MystikoCommands::Agent => execute_agent_command(args.agent_socket_path()).await,
+        MystikoCommands::Lock => {
+            execute_lock_command(args.agent_socket_path(), args.compact_json).await
+        }
+        MystikoCommands::Audit(audit_args) => {
+            check_password_rotation(&args).await?;
+            let audit_log = AuditLog::from_path(database_path(&args)).await?;
+            execute_audit_command(audit_args.clone(), &audit_log, args.compact_json).await
+        }
+        _ => {
+            let mut args = args;
+            args.in_memory |= args.commands.is_preview();
+            let agent_socket_path = args.agent_socket_path();
+            if let Some(password) = args.commands.wallet_password_mut() {
+                if password.is_none() {
+                    *password = Some(request_agent_password(&agent_socket_path).await?);
+                }
+            }
+            if let Some(password) = args.commands.optional_wallet_password_mut() {
+                if password.is_none() {
+                    *password = request_agent_password(&agent_socket_path).await.ok();
+                }
+            }
+            if !args.in_memory && !args.commands.is_password_update() {
+                check_password_rotation(&args).await?;
+            }
+            if !args.in_memory {
+                let replacements_path = database_path(&args);
+                if let Some(path) = args.commands.replacements_path_mut() {
+                    *path = Some(replacements_path);
+                }
+                let jobs_path = database_path(&args);
+                if let Some(path) = args.commands.jobs_path_mut() {
+                    *path = Some(jobs_path);
+                }
+            }
+            let _ = env_logger::builder()
+                .filter_module("", args.extern_logging_level.parse::<LevelFilter>()?)
+                .filter_module("mystiko_core", args.logging_level.parse::<LevelFilter>()?)
+                .try_init();
+            let audit = match audit_command(&args.commands) {
+                Some((command, targets)) if !args.in_memory => {
+                    let audit_log = AuditLog::from_path(database_path(&args)).await?;
+                    audit_log.append_started(command, targets.clone()).await?;
+                    Some((audit_log, command, targets))
+                }
+                _ => None,
+            };
+            let password_journal_path = password_journal_path(&args);
+            let _dry_run_database = if args.commands.is_dry_run() && !args.in_memory {
+                Some(create_dry_run_database(&mut args).await?)
+            } else {
+                None
+            };
+            let mystiko = create_mystiko(&args).await?;
+            let db_path = database_path(&args);
+            let result = match args.commands {
+                MystikoCommands::Unlock(unlock_args) => {
+                    execute_unlock_command(
+                        &mystiko,
+                        unlock_args,
+                        agent_socket_path,
+                        args.compact_json,
+                    )
+                    .await
+                }
+                MystikoCommands::Jobs(jobs_args) => {
+                    let (job_store, audit_log) = if args.in_memory {
+                        (
+                            JobStore::from_memory().await?,
+                            AuditLog::from_memory().await?,
+                        )
+                    } else {
+                        (
+                            JobStore::from_path(&db_path).await?,
+                            AuditLog::from_path(&db_path).await?,
+                        )
+                    };
+                    execute_jobs_command(
+                        &mystiko,
+                        jobs_args,
+                        &job_store,
+                        &audit_log,
+                        args.compact_json,
+                    )
+                    .await
+                }
+                MystikoCommands::Wallet(WalletCommand {
+                    commands: WalletCommands::UpdatePassword(update_args),
+                }) if !args.in_memory => {
+                    execute_wallet_update_password_command(
+                        &mystiko,
+                        update_args,
+                        Some(&password_journal_path),
+                        args.compact_json,
+                    )
+                    .await
+                }
+                commands => execute_with_mystiko(&mystiko, commands, args.compact_json).await,
+            };
+            if let Some((audit_log, command, targets)) = audit {
+                let error = result.as_ref().err().map(|err| err.to_string());
+                let appended = audit_log.append(command, targets, error).await;
+                return result.and(appended.map(|_| ()));
+            }
+            result
+        }
     }
 }
 
@@ -118,6 +256,14 @@ where
             execute_scanner_command::<F, S, W, A, D, X, Y, R>(mystiko, scanner_args, compact_json)
                 .await
         }
+        MystikoCommands::Broadcast(broadcast_args) => {
+            execute_broadcast_command::<F, S, W, A, D, X, Y, R>(
+                mystiko,
+                broadcast_args,
+                compact_json,
+            )
+            .await
+        }
         MystikoCommands::Synchronizer(synchronizer_args) => {
             execute_synchronizer::<F, S, W, A, D, X, Y, R>(mystiko, synchronizer_args, compact_json)
                 .await
@@ -136,18 +282,12 @@ pub fn print_json<T: Serialize>(value: &T, compact: bool) -> Result<(), MystikoC
 }
 
 async fn create_database(
-    args: MystikoCliArgs,
+    args: &MystikoCliArgs,
 ) -> Result<Database<SqlStatementFormatter, SqliteStorage>, MystikoCliError> {
     let storage = if args.in_memory {
         SqliteStorage::from_memory().await?
     } else {
-        let mode = if args.testnet { "testnet" } else { "mainnet" };
-        let default_db_path = dirs::home_dir()
-            .unwrap_or(PathBuf::from(""))
-            .join(".mystiko")
-            .join(mode)
-            .join("mystiko.db");
-        let db_path = args.db_path.map(PathBuf::from).unwrap_or(default_db_path);
+        let db_path = database_path(args);
         if let Some(parent) = db_path.parent() {
             if !tokio::fs::try_exists(parent).await? {
                 tokio::fs::create_dir_all(parent).await?;
@@ -158,6 +298,58 @@ async fn create_database(
     Ok(Database::new(SqlStatementFormatter::sqlite(), storage))
 }
 
+async fn create_dry_run_database(
+    args: &mut MystikoCliArgs,
+) -> Result<tempfile::TempDir, MystikoCliError> {
+    let db_path = database_path(args);
+    let scratch = tempfile::tempdir()?;
+    let scratch_db_path = scratch.path().join("mystiko.db");
+    if tokio::fs::try_exists(&db_path).await? {
+        tokio::fs::copy(&db_path, &scratch_db_path).await?;
+        let mut wal_path = db_path.into_os_string();
+        wal_path.push("-wal");
+        if tokio::fs::try_exists(&wal_path).await? {
+            let mut scratch_wal_path = scratch_db_path.clone().into_os_string();
+            scratch_wal_path.push("-wal");
+            tokio::fs::copy(&wal_path, &scratch_wal_path).await?;
+        }
+    }
+    args.db_path = Some(scratch_db_path.to_string_lossy().to_string());
+    Ok(scratch)
+}
+
+fn database_path(args: &MystikoCliArgs) -> PathBuf {
+    let mode = if args.testnet { "testnet" } else { "mainnet" };
+    let default_db_path = dirs::home_dir()
+        .unwrap_or(PathBuf::from(""))
+        .join(".mystiko")
+        .join(mode)
+        .join("mystiko.db");
+    args.db_path
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or(default_db_path)
+}
+
+fn password_journal_path(args: &MystikoCliArgs) -> PathBuf {
+    let db_path = database_path(args);
+    let mut file_name = db_path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".password-rotation.json");
+    db_path.with_file_name(file_name)
+}
+
+async fn check_password_rotation(args: &MystikoCliArgs) -> Result<(), MystikoCliError> {
+    let password_journal_path = password_journal_path(args);
+    if tokio::fs::try_exists(&password_journal_path).await? {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "An interrupted wallet password rotation is pending in {}, \
+             run wallet update-password to recover it first",
+            password_journal_path.display()
+        )));
+    }
+    Ok(())
+}
+
 fn static_cache_path(args: &MystikoCliArgs) -> PathBuf {
     let default_db_path = dirs::home_dir()
         .unwrap_or(PathBuf::from(""))
@@ -172,7 +364,7 @@ fn static_cache_path(args: &MystikoCliArgs) -> PathBuf {
 async fn create_mystiko(
     args: &MystikoCliArgs,
 ) -> Result<Mystiko<SqlStatementFormatter, SqliteStorage>, MystikoCliError> {
-    let database = create_database(args.clone()).await?;
+    let database = create_database(args).await?;
     let static_cache = FileStaticCache::new(static_cache_path(args)).await?;
     let config_options = ConfigOptions::builder()
         .is_testnet(args.testnet)
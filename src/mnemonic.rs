@@ -0,0 +1,187 @@
+use crate::MystikoCliError;
+use anyhow::anyhow;
+use bip39::{Error as Bip39Error, Language, Mnemonic};
+use mystiko_protos::core::v1::MnemonicType;
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha256};
+use sharks::{Share, Sharks};
+use zeroize::Zeroizing;
+
+pub const MNEMONIC_WORD_COUNTS: [usize; 5] = [12, 15, 18, 21, 24];
+pub const MNEMONIC_SHARE_PREFIX: &str = "mystiko-share-v1";
+
+pub fn validate_mnemonic_phrase(phrase: &str) -> Result<Mnemonic, MystikoCliError> {
+    let words = phrase
+        .split_whitespace()
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>();
+    if !MNEMONIC_WORD_COUNTS.contains(&words.len()) {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Invalid mnemonic phrase: expected 12, 15, 18, 21 or 24 words, got {}",
+            words.len()
+        )));
+    }
+    for (index, word) in words.iter().enumerate() {
+        if Language::English.find_word(word).is_none() {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Invalid mnemonic phrase: word {} '{}' not in wordlist, did you mean '{}'",
+                index + 1,
+                word,
+                closest_mnemonic_word(word)
+            )));
+        }
+    }
+    Mnemonic::parse_in_normalized(Language::English, &words.join(" ")).map_err(|err| match err {
+        Bip39Error::InvalidChecksum => MystikoCliError::AnyhowError(anyhow!(
+            "Invalid mnemonic phrase: checksum mismatch, check the order and spelling of the words"
+        )),
+        err => MystikoCliError::AnyhowError(anyhow!("Invalid mnemonic phrase: {}", err)),
+    })
+}
+
+fn closest_mnemonic_word(word: &str) -> &'static str {
+    Language::English
+        .word_list()
+        .iter()
+        .min_by_key(|candidate| strsim::levenshtein(word, candidate))
+        .copied()
+        .unwrap_or_default()
+}
+
+pub fn split_mnemonic_phrase(
+    phrase: &str,
+    mnemonic_type: MnemonicType,
+    shares: u8,
+    threshold: u8,
+) -> Result<Vec<String>, MystikoCliError> {
+    if threshold < 2 || threshold > shares {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Invalid share threshold {}, it should be between 2 and {}",
+            threshold,
+            shares
+        )));
+    }
+    let entropy = Zeroizing::new(validate_mnemonic_phrase(phrase)?.to_entropy());
+    let mut secret = Zeroizing::new(entropy.to_vec());
+    secret.extend_from_slice(&Sha256::digest(entropy.as_slice())[..4]);
+    Ok(Sharks(threshold)
+        .dealer_rng(&secret, &mut OsRng)
+        .take(shares as usize)
+        .map(|share| encode_mnemonic_share(mnemonic_type, threshold, &Vec::from(&share)))
+        .collect())
+}
+
+pub fn combine_mnemonic_shares(
+    shares: &[String],
+) -> Result<(Mnemonic, MnemonicType), MystikoCliError> {
+    let decoded = shares
+        .iter()
+        .map(|share| decode_mnemonic_share(share))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (mnemonic_type, threshold) = decoded
+        .first()
+        .map(|(mnemonic_type, threshold, _)| (*mnemonic_type, *threshold))
+        .unwrap_or((MnemonicType::Rust, 2));
+    if decoded.iter().any(|(t, _, _)| *t != mnemonic_type) {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Mnemonic shares have different mnemonic types"
+        )));
+    }
+    if decoded.iter().any(|(_, t, _)| *t != threshold) {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Mnemonic shares have different thresholds"
+        )));
+    }
+    if decoded.len() < threshold as usize {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Need at least {} mnemonic shares, got {}",
+            threshold,
+            decoded.len()
+        )));
+    }
+    let points = decoded
+        .iter()
+        .map(|(_, _, share)| share)
+        .collect::<Vec<_>>();
+    let secret_len = points[0].len() - 1;
+    for (index, share) in points.iter().enumerate() {
+        if share.len() != secret_len + 1 {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Invalid mnemonic shares: the shares have different lengths"
+            )));
+        }
+        if points[..index].iter().any(|other| other[0] == share[0]) {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Invalid mnemonic shares: share {} is given more than once",
+                share[0]
+            )));
+        }
+    }
+    let points = points
+        .iter()
+        .map(|share| Share::try_from(share.as_slice()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!("Invalid mnemonic shares: {}", err)))?;
+    let secret = Zeroizing::new(Sharks(threshold).recover(&points).map_err(|err| {
+        MystikoCliError::AnyhowError(anyhow!("Invalid mnemonic shares: {}", err))
+    })?);
+    if secret.len() < 4 {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Invalid mnemonic shares: recovered secret is too short"
+        )));
+    }
+    let (entropy, digest) = secret.split_at(secret.len() - 4);
+    if Sha256::digest(entropy)[..4] != *digest {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Invalid mnemonic shares: the shares do not belong to the same mnemonic phrase"
+        )));
+    }
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, entropy)
+        .map_err(|err| MystikoCliError::AnyhowError(anyhow!("Invalid mnemonic shares: {}", err)))?;
+    Ok((mnemonic, mnemonic_type))
+}
+
+fn encode_mnemonic_share(mnemonic_type: MnemonicType, threshold: u8, share: &[u8]) -> String {
+    let body = format!(
+        "{}:{}:{}",
+        mnemonic_type_name(mnemonic_type),
+        threshold,
+        hex::encode(share)
+    );
+    format!(
+        "{}:{}:{}",
+        MNEMONIC_SHARE_PREFIX,
+        body,
+        hex::encode(&Sha256::digest(body.as_bytes())[..4])
+    )
+}
+
+fn decode_mnemonic_share(share: &str) -> Result<(MnemonicType, u8, Vec<u8>), MystikoCliError> {
+    let invalid_share = || MystikoCliError::AnyhowError(anyhow!("Invalid mnemonic share"));
+    let parts = share.trim().split(':').collect::<Vec<_>>();
+    if parts.len() != 5 || parts[0] != MNEMONIC_SHARE_PREFIX {
+        return Err(invalid_share());
+    }
+    let body = parts[1..4].join(":");
+    if hex::encode(&Sha256::digest(body.as_bytes())[..4]) != parts[4] {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Mnemonic share checksum mismatch, check the share for typos"
+        )));
+    }
+    let mnemonic_type =
+        MnemonicType::from_str_name(&format!("MNEMONIC_TYPE_{}", parts[1].to_ascii_uppercase()))
+            .ok_or_else(invalid_share)?;
+    let threshold = parts[2].parse::<u8>().map_err(|_| invalid_share())?;
+    let share = hex::decode(parts[3]).map_err(|_| invalid_share())?;
+    if share.len() < 2 || share[0] == 0 {
+        return Err(invalid_share());
+    }
+    Ok((mnemonic_type, threshold, share))
+}
+
+fn mnemonic_type_name(mnemonic_type: MnemonicType) -> String {
+    mnemonic_type
+        .as_str_name()
+        .trim_start_matches("MNEMONIC_TYPE_")
+        .to_ascii_lowercase()
+}
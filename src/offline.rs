@@ -0,0 +1,166 @@
+use crate::{write_private_file, CapturedTransaction, MystikoCliError};
+use anyhow::anyhow;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, BlockNumber, Bytes, TxHash, U256};
+use ethers_core::utils::keccak256;
+use ethers_providers::{Http, Middleware, Provider};
+use ethers_signers::{LocalWallet, Signer};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+pub const OFFLINE_TRANSACTIONS_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfflineDocumentType {
+    Deposit,
+    Spend,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OfflineTransactionType {
+    AssetApprove,
+    Deposit,
+    Spend,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineTransaction {
+    pub transaction_type: OfflineTransactionType,
+    pub chain_id: u64,
+    pub from: Address,
+    pub transaction: TypedTransaction,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_transaction: Option<Bytes>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<TxHash>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineTransactions {
+    pub version: u32,
+    pub document_type: OfflineDocumentType,
+    pub document_id: String,
+    pub transactions: Vec<OfflineTransaction>,
+}
+
+impl OfflineTransaction {
+    pub fn new(transaction_type: OfflineTransactionType, captured: CapturedTransaction) -> Self {
+        let mut transaction = captured.transaction;
+        transaction.set_chain_id(captured.chain_id);
+        transaction.set_from(captured.from);
+        OfflineTransaction {
+            transaction_type,
+            chain_id: captured.chain_id,
+            from: captured.from,
+            transaction,
+            raw_transaction: None,
+            transaction_hash: None,
+        }
+    }
+}
+
+impl OfflineTransactions {
+    pub fn new(
+        document_type: OfflineDocumentType,
+        document_id: String,
+        transactions: Vec<OfflineTransaction>,
+    ) -> Self {
+        OfflineTransactions {
+            version: OFFLINE_TRANSACTIONS_VERSION,
+            document_type,
+            document_id,
+            transactions,
+        }
+    }
+
+    pub async fn read<P: AsRef<Path>>(path: P) -> Result<Self, MystikoCliError> {
+        let content = tokio::fs::read_to_string(path).await?;
+        let transactions: OfflineTransactions = serde_json::from_str(&content)?;
+        if transactions.version != OFFLINE_TRANSACTIONS_VERSION {
+            return Err(MystikoCliError::AnyhowError(anyhow!(
+                "Unsupported transactions file version {}",
+                transactions.version
+            )));
+        }
+        Ok(transactions)
+    }
+
+    pub async fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), MystikoCliError> {
+        write_private_file(path, &serde_json::to_string_pretty(self)?).await
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.transactions
+            .iter()
+            .all(|transaction| transaction.raw_transaction.is_some())
+    }
+
+    pub async fn assign_nonces(
+        &mut self,
+        nonce: Option<u64>,
+        provider: Option<&str>,
+    ) -> Result<(), MystikoCliError> {
+        let from = match self
+            .transactions
+            .iter()
+            .find(|transaction| transaction.transaction.nonce().is_none())
+        {
+            Some(transaction) => transaction.from,
+            None => return Ok(()),
+        };
+        let mut next_nonce = match nonce {
+            Some(nonce) => U256::from(nonce),
+            None => {
+                let provider = provider.ok_or_else(|| {
+                    MystikoCliError::AnyhowError(anyhow!(
+                        "Transaction nonce is unknown, pass --nonce or --signer-provider"
+                    ))
+                })?;
+                Provider::<Http>::try_from(provider)
+                    .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+                    .get_transaction_count(from, Some(BlockNumber::Pending.into()))
+                    .await
+                    .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?
+            }
+        };
+        for transaction in self.transactions.iter_mut() {
+            if transaction.transaction.nonce().is_none() {
+                transaction.transaction.set_nonce(next_nonce);
+                next_nonce += U256::one();
+            }
+        }
+        Ok(())
+    }
+
+    pub fn sign(&mut self, wallet: &LocalWallet) -> Result<(), MystikoCliError> {
+        for (index, transaction) in self.transactions.iter_mut().enumerate() {
+            if transaction.from != wallet.address() {
+                return Err(MystikoCliError::AnyhowError(anyhow!(
+                    "Transaction {} is from {:?}, which does not match the signing key {:?}",
+                    index,
+                    transaction.from,
+                    wallet.address()
+                )));
+            }
+            if transaction.transaction.nonce().is_none() || transaction.transaction.gas().is_none()
+            {
+                return Err(MystikoCliError::AnyhowError(anyhow!(
+                    "Transaction {} has no nonce or gas limit, prepare it again",
+                    index
+                )));
+            }
+            transaction.transaction.set_chain_id(transaction.chain_id);
+            let signature = wallet
+                .clone()
+                .with_chain_id(transaction.chain_id)
+                .sign_transaction_sync(&transaction.transaction)
+                .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?;
+            let raw_transaction = transaction.transaction.rlp_signed(&signature);
+            transaction.transaction_hash = Some(TxHash::from(keccak256(&raw_transaction)));
+            transaction.raw_transaction = Some(raw_transaction);
+        }
+        Ok(())
+    }
+}
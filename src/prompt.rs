@@ -0,0 +1,27 @@
+use crate::MystikoCliError;
+use anyhow::anyhow;
+use serde::Serialize;
+use std::io::{BufRead, IsTerminal, Write};
+
+pub fn confirm_execution<T: Serialize>(summary: &T) -> Result<(), MystikoCliError> {
+    if !std::io::stdin().is_terminal() {
+        return Err(MystikoCliError::AnyhowError(anyhow!(
+            "Refusing to run non-interactively without confirmation, pass --yes to proceed"
+        )));
+    }
+    let mut stderr = std::io::stderr();
+    writeln!(stderr, "{}", serde_json::to_string_pretty(summary)?)?;
+    write!(stderr, "Proceed? [y/N] ")?;
+    stderr.flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    if is_confirmed(&answer) {
+        Ok(())
+    } else {
+        Err(MystikoCliError::AnyhowError(anyhow!("Aborted by the user")))
+    }
+}
+
+pub fn is_confirmed(answer: &str) -> bool {
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
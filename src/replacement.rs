@@ -0,0 +1,111 @@
+use crate::{connect_sqlite, connect_sqlite_memory, MystikoCliError};
+use anyhow::anyhow;
+use ethers_core::types::{Address, TxHash, U256};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionReplacementRecord {
+    pub document_id: String,
+    pub chain_id: u64,
+    pub from: Address,
+    pub nonce: U256,
+    pub replaced_transaction_hash: TxHash,
+    pub transaction_hash: TxHash,
+    pub cancel: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplacementStore {
+    pool: SqlitePool,
+}
+
+impl TransactionReplacementRecord {
+    fn from_row(row: &SqliteRow) -> Result<Self, MystikoCliError> {
+        Ok(TransactionReplacementRecord {
+            document_id: row.try_get("document_id")?,
+            chain_id: row.try_get::<i64, _>("chain_id")? as u64,
+            from: parse_column(row, "from_address")?,
+            nonce: U256::from_dec_str(row.try_get("nonce")?)
+                .map_err(|err| MystikoCliError::AnyhowError(anyhow!(err)))?,
+            replaced_transaction_hash: parse_column(row, "replaced_transaction_hash")?,
+            transaction_hash: parse_column(row, "transaction_hash")?,
+            cancel: row.try_get("cancel")?,
+        })
+    }
+}
+
+impl ReplacementStore {
+    pub async fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, MystikoCliError> {
+        ReplacementStore::from_pool(connect_sqlite(path).await?).await
+    }
+
+    pub async fn from_memory() -> Result<Self, MystikoCliError> {
+        ReplacementStore::from_pool(connect_sqlite_memory().await?).await
+    }
+
+    pub async fn from_pool(pool: SqlitePool) -> Result<Self, MystikoCliError> {
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS transaction_replacement (\
+             id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL, \
+             document_id TEXT NOT NULL, \
+             chain_id INTEGER NOT NULL, \
+             from_address TEXT NOT NULL, \
+             nonce TEXT NOT NULL, \
+             replaced_transaction_hash TEXT NOT NULL, \
+             transaction_hash TEXT NOT NULL UNIQUE, \
+             cancel INTEGER NOT NULL)",
+        )
+        .execute(&pool)
+        .await?;
+        Ok(ReplacementStore { pool })
+    }
+
+    pub async fn records(&self) -> Result<Vec<TransactionReplacementRecord>, MystikoCliError> {
+        sqlx::query("SELECT * FROM transaction_replacement ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(TransactionReplacementRecord::from_row)
+            .collect()
+    }
+
+    pub async fn document_records(
+        &self,
+        document_id: &str,
+    ) -> Result<Vec<TransactionReplacementRecord>, MystikoCliError> {
+        sqlx::query("SELECT * FROM transaction_replacement WHERE document_id = ? ORDER BY id")
+            .bind(document_id)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(TransactionReplacementRecord::from_row)
+            .collect()
+    }
+
+    pub async fn add(&self, record: TransactionReplacementRecord) -> Result<(), MystikoCliError> {
+        sqlx::query(
+            "INSERT INTO transaction_replacement (document_id, chain_id, from_address, nonce, \
+             replaced_transaction_hash, transaction_hash, cancel) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&record.document_id)
+        .bind(record.chain_id as i64)
+        .bind(format!("{:?}", record.from))
+        .bind(record.nonce.to_string())
+        .bind(format!("{:?}", record.replaced_transaction_hash))
+        .bind(format!("{:?}", record.transaction_hash))
+        .bind(record.cancel)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+fn parse_column<T: std::str::FromStr>(row: &SqliteRow, column: &str) -> Result<T, MystikoCliError> {
+    let value: String = row.try_get(column)?;
+    value.parse().map_err(|_| {
+        MystikoCliError::AnyhowError(anyhow!("Invalid {} {} in the replacements", column, value))
+    })
+}
@@ -0,0 +1,86 @@
+use crate::MystikoCliError;
+use anyhow::anyhow;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Display, Formatter};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+pub(crate) const WALLET_PASSWORD_REQUIRED: &str =
+    "Wallet password is required, pass --password or run mystiko unlock first";
+
+pub(crate) fn expose_wallet_password(
+    password: &Option<SecretString>,
+) -> Result<&str, MystikoCliError> {
+    password
+        .as_ref()
+        .map(SecretString::expose_secret)
+        .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!(WALLET_PASSWORD_REQUIRED)))
+}
+
+pub(crate) fn expose_required_secret<'a>(
+    secret: &'a Option<SecretString>,
+    name: &str,
+) -> Result<&'a str, MystikoCliError> {
+    secret
+        .as_ref()
+        .map(SecretString::expose_secret)
+        .ok_or_else(|| MystikoCliError::AnyhowError(anyhow!("{} is required", name)))
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> Self {
+        SecretString(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> Self {
+        SecretString(secret.to_string())
+    }
+}
+
+impl Debug for SecretString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+impl Display for SecretString {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[REDACTED]")
+    }
+}
+
+impl Zeroize for SecretString {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretString {}
+
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("[REDACTED]")
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(SecretString)
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{request_signer_address, MystikoCliError, SecretString};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, TxHash};
+use ethers_signers::{LocalWallet, Signer};
+use mystiko_core::TransactionSigner;
+use serde::Serialize;
+use std::sync::Mutex;
+
+pub const TRANSACTION_CAPTURED_MESSAGE: &str = "transaction captured without broadcasting";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CapturedTransaction {
+    pub chain_id: u64,
+    pub from: Address,
+    pub transaction: TypedTransaction,
+}
+
+#[derive(Debug, Default)]
+pub struct CapturingSigner {
+    address: Address,
+    transactions: Mutex<Vec<CapturedTransaction>>,
+}
+
+impl CapturingSigner {
+    pub fn new(address: Address) -> Self {
+        CapturingSigner {
+            address,
+            transactions: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn from_private_key(private_key: Option<&SecretString>) -> Result<Self, MystikoCliError> {
+        match private_key {
+            Some(private_key) => Ok(CapturingSigner::new(
+                parse_private_key(private_key)?.address(),
+            )),
+            None => Ok(CapturingSigner::default()),
+        }
+    }
+
+    pub fn transactions(&self) -> Vec<CapturedTransaction> {
+        self.transactions
+            .lock()
+            .map(|transactions| transactions.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for CapturingSigner {
+    async fn address(&self) -> anyhow::Result<Address> {
+        Ok(self.address)
+    }
+
+    async fn send_transaction(
+        &self,
+        chain_id: u64,
+        tx: TypedTransaction,
+    ) -> anyhow::Result<TxHash> {
+        self.transactions
+            .lock()
+            .map_err(|_| anyhow!("capturing signer is poisoned"))?
+            .push(CapturedTransaction {
+                chain_id,
+                from: self.address,
+                transaction: tx,
+            });
+        Err(anyhow!(TRANSACTION_CAPTURED_MESSAGE))
+    }
+}
+
+pub async fn create_capturing_signer(
+    private_key: Option<&SecretString>,
+    signer_command: Option<&str>,
+    signer_timeout_ms: Option<u64>,
+) -> Result<CapturingSigner, MystikoCliError> {
+    match signer_command {
+        Some(signer_command) if private_key.is_none() => Ok(CapturingSigner::new(
+            request_signer_address(signer_command, signer_timeout_ms).await?,
+        )),
+        _ => CapturingSigner::from_private_key(private_key),
+    }
+}
+
+pub fn parse_private_key(private_key: &SecretString) -> Result<LocalWallet, MystikoCliError> {
+    private_key
+        .expose_secret()
+        .trim_start_matches("0x")
+        .parse::<LocalWallet>()
+        .map_err(|_| MystikoCliError::AnyhowError(anyhow!("Invalid private key")))
+}
@@ -0,0 +1,31 @@
+use crate::MystikoCliError;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub const SQLITE_BUSY_TIMEOUT_MS: u64 = 30_000;
+
+pub async fn connect_sqlite<P: AsRef<Path>>(path: P) -> Result<SqlitePool, MystikoCliError> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() && !tokio::fs::try_exists(parent).await? {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+    }
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .busy_timeout(Duration::from_millis(SQLITE_BUSY_TIMEOUT_MS));
+    Ok(SqlitePoolOptions::new().connect_with(options).await?)
+}
+
+pub async fn connect_sqlite_memory() -> Result<SqlitePool, MystikoCliError> {
+    let options = SqliteConnectOptions::from_str("sqlite::memory:")?;
+    Ok(SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect_with(options)
+        .await?)
+}
@@ -1,5 +1,8 @@
 use clap::Parser;
-use mystiko::MystikoCliArgs;
+use mystiko::{execute_with_mystiko, MystikoCliArgs};
+use mystiko_core::AccountHandler;
+use mystiko_protos::core::document::v1::Account;
+use mystiko_protos::core::v1::AccountStatus;
 
 #[allow(dead_code)]
 mod common;
@@ -25,6 +28,94 @@ async fn test_account_create() {
     mystiko::execute(args).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_account_create_without_password() {
+    let mystiko = common::mock_mystiko(common::MockDeposits::new()).await;
+    import_wallet(&mystiko).await;
+    let args = MystikoCliArgs::parse_from(["mystiko", "account", "create"]);
+    let err = execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Wallet password is required"));
+    assert!(mystiko.accounts.find_all().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_account_create_batch() {
+    let mystiko = common::mock_mystiko(common::MockDeposits::new()).await;
+    import_wallet(&mystiko).await;
+    create_accounts(&mystiko, 3).await;
+    let mut accounts = mystiko.accounts.find_all().await.unwrap();
+    accounts.sort_by(|a, b| a.name.cmp(&b.name));
+    let names = accounts
+        .iter()
+        .map(|account| account.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["cust-0", "cust-1", "cust-2"]);
+    assert!(MystikoCliArgs::try_parse_from([
+        "mystiko",
+        "account",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--name",
+        "Test Account #1",
+        "--name-prefix",
+        "cust-",
+    ])
+    .is_err());
+}
+
+#[tokio::test]
+async fn test_account_derive() {
+    let mystiko = common::mock_mystiko(common::MockDeposits::new()).await;
+    import_wallet(&mystiko).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "account",
+        "derive",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--index",
+        "2",
+        "--name",
+        "Test Account #2",
+    ]);
+    execute_with_mystiko(&mystiko, args.commands.clone(), false)
+        .await
+        .unwrap();
+    assert!(execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .is_err());
+    create_accounts(&mystiko, 3).await;
+    let mut accounts = mystiko.accounts.find_all().await.unwrap();
+    accounts.sort_by(|a, b| a.name.cmp(&b.name));
+    let names = accounts
+        .iter()
+        .map(|account| account.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["Test Account #2", "cust-0", "cust-1", "cust-3"]);
+
+    let expected = common::mock_mystiko(common::MockDeposits::new()).await;
+    import_wallet(&expected).await;
+    create_accounts(&expected, 4).await;
+    let mut expected_accounts = expected.accounts.find_all().await.unwrap();
+    expected_accounts.sort_by(|a, b| a.name.cmp(&b.name));
+    let addresses = |accounts: &[Account]| {
+        let mut addresses = accounts
+            .iter()
+            .map(|account| account.shielded_address.clone())
+            .collect::<Vec<_>>();
+        addresses.sort();
+        addresses
+    };
+    assert_eq!(addresses(&accounts), addresses(&expected_accounts));
+    assert_eq!(
+        accounts[0].shielded_address,
+        expected_accounts[2].shielded_address
+    );
+}
+
 #[tokio::test]
 async fn test_account_import() {
     let (_, db_path) = common::temp_db_path();
@@ -87,6 +178,175 @@ async fn test_account_export_secret_key() {
     mystiko::execute(args).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_account_export_keystore() {
+    let (_, db_path) = common::temp_db_path();
+    create_wallet(&db_path).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "account",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--name",
+        "Test Account #1",
+        "--secret-key",
+        "a26dcc48d3731b3c2fcffae05d6b4999d864d02a74\
+        59b25f6b8546b3d54b87c6e9941f4bbe39552ed83f0\
+        d89e00ce4ebb69f75febf771dc5b554f3463710ab0a",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let folder = tempfile::tempdir().unwrap();
+    let keystore_path = folder
+        .path()
+        .join("account.json")
+        .to_string_lossy()
+        .to_string();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "account",
+        "export",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--shielded-address",
+        "7y5fEqG5ynEJYP6oPkriPJpBvFWvvGJzhReDSe9sHkmR1fWBWjm1qQLMNwpPNFnMFq3r9AudDMrS7CwetAx6ptzJH",
+        "--export-passphrase",
+        "An0therP@ssphrase",
+        "--out",
+        &keystore_path,
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let content = std::fs::read_to_string(&keystore_path).unwrap();
+    assert!(!content.contains("a26dcc48d3731b3c2fcffae05d6b4999d864d02a74"));
+
+    let (_, new_db_path) = common::temp_db_path();
+    create_wallet(&new_db_path).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &new_db_path,
+        "account",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--keystore",
+        &keystore_path,
+        "--export-passphrase",
+        "Wr0ngP@ssphrase",
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &new_db_path,
+        "account",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--keystore",
+        &keystore_path,
+        "--export-passphrase",
+        "An0therP@ssphrase",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &new_db_path,
+        "account",
+        "get",
+        "--name",
+        "Test Account #1",
+    ]);
+    mystiko::execute(args).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_account_update() {
+    let mystiko = common::mock_mystiko(common::MockDeposits::new()).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "wallet",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "account",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--name",
+        "Test Acount #1",
+        "--secret-key",
+        "a26dcc48d3731b3c2fcffae05d6b4999d864d02a74\
+        59b25f6b8546b3d54b87c6e9941f4bbe39552ed83f0\
+        d89e00ce4ebb69f75febf771dc5b554f3463710ab0a",
+    ]);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let shielded_address =
+        "7y5fEqG5ynEJYP6oPkriPJpBvFWvvGJzhReDSe9sHkmR1fWBWjm1qQLMNwpPNFnMFq3r9AudDMrS7CwetAx6ptzJH";
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "account",
+        "update",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--shielded-address",
+        shielded_address,
+        "--name",
+        "Test Account #1",
+        "--scan-size",
+        "5000",
+        "--status",
+        "scanned",
+    ]);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let account = mystiko
+        .accounts
+        .find_by_shielded_address(shielded_address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.name, "Test Account #1");
+    assert_eq!(account.scan_size, 5000);
+    assert_eq!(account.status, AccountStatus::Scanned as i32);
+    assert!(MystikoCliArgs::try_parse_from([
+        "mystiko",
+        "account",
+        "update",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--shielded-address",
+        shielded_address,
+        "--status",
+        "unknown",
+    ])
+    .is_err());
+}
+
 #[tokio::test]
 async fn test_account_list() {
     let (_, db_path) = common::temp_db_path();
@@ -117,6 +377,114 @@ async fn test_account_list() {
     mystiko::execute(args).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_account_get() {
+    let (_, db_path) = common::temp_db_path();
+    create_wallet(&db_path).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "account",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--name",
+        "Test Account #1",
+        "--secret-key",
+        "a26dcc48d3731b3c2fcffae05d6b4999d864d02a74\
+        59b25f6b8546b3d54b87c6e9941f4bbe39552ed83f0\
+        d89e00ce4ebb69f75febf771dc5b554f3463710ab0a",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "account",
+        "get",
+        "--shielded-address",
+        "7y5fEqG5ynEJYP6oPkriPJpBvFWvvGJzhReDSe9sHkmR1fWBWjm1qQLMNwpPNFnMFq3r9AudDMrS7CwetAx6ptzJH",
+        "--with-balance",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "account",
+        "get",
+        "--name",
+        "Test Account #1",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "account",
+        "get",
+        "--name",
+        "Test Account #2",
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+    assert!(MystikoCliArgs::try_parse_from(["mystiko", "account", "get"]).is_err());
+}
+
+#[tokio::test]
+async fn test_account_list_with_filters() {
+    let (_, db_path) = common::temp_db_path();
+    create_wallet(&db_path).await;
+    for name in ["Customer #1", "Customer #2", "Treasury"] {
+        let args = MystikoCliArgs::parse_from([
+            "mystiko",
+            "--config-path",
+            "tests/files/config.json",
+            "--db-path",
+            &db_path,
+            "account",
+            "create",
+            "--password",
+            "VeryAwes0meP@ssw0rd",
+            "--name",
+            name,
+        ]);
+        mystiko::execute(args).await.unwrap();
+    }
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "account",
+        "list",
+        "--name",
+        "customer",
+        "--status",
+        "created",
+        "--with-balance",
+        "--limit",
+        "1",
+        "--page",
+        "2",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    assert!(
+        MystikoCliArgs::try_parse_from(["mystiko", "account", "list", "--status", "unknown"])
+            .is_err()
+    );
+    assert!(MystikoCliArgs::try_parse_from(["mystiko", "account", "list", "--page", "2"]).is_err());
+}
+
 async fn create_wallet(db_path: &str) {
     let args = MystikoCliArgs::parse_from([
         "mystiko",
@@ -131,3 +499,35 @@ async fn create_wallet(db_path: &str) {
     ]);
     mystiko::execute(args).await.unwrap();
 }
+
+async fn import_wallet(mystiko: &common::MockMystiko) {
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "wallet",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--mnemonic",
+        "legal winner thank year wave sausage worth useful legal winner thank yellow",
+    ]);
+    execute_with_mystiko(mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+async fn create_accounts(mystiko: &common::MockMystiko, count: u32) {
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "account",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--count",
+        &count.to_string(),
+        "--name-prefix",
+        "cust-",
+    ]);
+    execute_with_mystiko(mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,118 @@
+#[allow(dead_code)]
+mod common;
+
+use clap::Parser;
+use mystiko::MystikoCliArgs;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_agent_unlock_and_lock() {
+    let (folder, db_path) = common::temp_db_path();
+    let socket_path = folder
+        .path()
+        .join("agent.sock")
+        .to_string_lossy()
+        .to_string();
+    let agent = tokio::spawn(mystiko::run_agent(socket_path.clone()));
+    while !std::path::Path::new(&socket_path).exists() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    assert!(
+        mystiko::execute(create_account_args(&db_path, &socket_path))
+            .await
+            .is_err()
+    );
+    assert!(
+        mystiko::execute(unlock_args(&db_path, &socket_path, "Wr0ngP@ssw0rd", "15m"))
+            .await
+            .is_err()
+    );
+    mystiko::execute(unlock_args(
+        &db_path,
+        &socket_path,
+        "VeryAwes0meP@ssw0rd",
+        "15m",
+    ))
+    .await
+    .unwrap();
+    mystiko::execute(create_account_args(&db_path, &socket_path))
+        .await
+        .unwrap();
+    let args = MystikoCliArgs::parse_from(["mystiko", "--agent-socket", &socket_path, "lock"]);
+    mystiko::execute(args).await.unwrap();
+    assert!(
+        mystiko::execute(create_account_args(&db_path, &socket_path))
+            .await
+            .is_err()
+    );
+    mystiko::execute(unlock_args(
+        &db_path,
+        &socket_path,
+        "VeryAwes0meP@ssw0rd",
+        "1s",
+    ))
+    .await
+    .unwrap();
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    assert!(
+        mystiko::execute(create_account_args(&db_path, &socket_path))
+            .await
+            .is_err()
+    );
+    agent.abort();
+}
+
+#[test]
+fn test_wait_password_is_optional() {
+    for document in ["deposit", "spend"] {
+        let mut args = MystikoCliArgs::parse_from(["mystiko", document, "wait", "--id", "1234"]);
+        assert!(args.commands.wallet_password_mut().is_none());
+        assert!(args
+            .commands
+            .optional_wallet_password_mut()
+            .is_some_and(|password| password.is_none()));
+    }
+}
+
+fn unlock_args(db_path: &str, socket_path: &str, password: &str, ttl: &str) -> MystikoCliArgs {
+    MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        db_path,
+        "--agent-socket",
+        socket_path,
+        "unlock",
+        "--password",
+        password,
+        "--ttl",
+        ttl,
+    ])
+}
+
+fn create_account_args(db_path: &str, socket_path: &str) -> MystikoCliArgs {
+    MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        db_path,
+        "--agent-socket",
+        socket_path,
+        "account",
+        "create",
+    ])
+}
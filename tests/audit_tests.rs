@@ -0,0 +1,180 @@
+#[allow(dead_code)]
+mod common;
+
+use clap::Parser;
+use mystiko::{audit_command, connect_sqlite, AuditLog, MystikoCliArgs, AUDIT_GENESIS_HASH};
+
+#[tokio::test]
+async fn test_audit_sensitive_commands() {
+    let (_, db_path) = common::temp_db_path();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "export-mnemonic",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "account",
+        "export-secret-key",
+        "--password",
+        "Wr0ngP@ssw0rd",
+        "--shielded-address",
+        "7y5fEqG5ynEJYP6oPkriPJpBvFWvvGJzhReDSe9sHkmR1fWBWjm1qQLMNwpPNFnMFq3r9AudDMrS7CwetAx6ptzJH",
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+
+    let audit_log = AuditLog::from_path(&db_path).await.unwrap();
+    let entries = audit_log.entries().await.unwrap();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(entries[0].command, "wallet export-mnemonic");
+    assert_eq!(entries[0].result, "started");
+    assert_eq!(entries[1].command, "wallet export-mnemonic");
+    assert_eq!(entries[1].result, "success");
+    assert_eq!(entries[2].command, "account export-secret-key");
+    assert_eq!(entries[2].result, "started");
+    assert_eq!(entries[3].command, "account export-secret-key");
+    assert_eq!(entries[3].result, "failure");
+    assert!(entries.iter().all(|entry| !serde_json::to_string(entry)
+        .unwrap()
+        .contains("VeryAwes0meP@ssw0rd")));
+
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--db-path",
+        &db_path,
+        "audit",
+        "list",
+        "--command",
+        "wallet export-mnemonic",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from(["mystiko", "--db-path", &db_path, "audit", "verify"]);
+    mystiko::execute(args).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_audit_log_hash_chain() {
+    let (_db_folder, db_path) = common::temp_db_path();
+    let audit_log = AuditLog::from_path(&db_path).await.unwrap();
+    let first = audit_log
+        .append_started("scanner reset", vec![String::from("to_id=1")])
+        .await
+        .unwrap();
+    assert_eq!(first.sequence, 1);
+    assert_eq!(first.result, "started");
+    assert_eq!(first.previous_hash, AUDIT_GENESIS_HASH);
+    let second = audit_log
+        .append(
+            "scanner reset",
+            vec![String::from("to_id=1")],
+            Some(String::from("failed")),
+        )
+        .await
+        .unwrap();
+    assert_eq!(second.sequence, 2);
+    assert_eq!(second.result, "failure");
+    assert_eq!(second.previous_hash, first.hash);
+    assert!(audit_log.verify().await.unwrap().valid);
+
+    let pool = connect_sqlite(&db_path).await.unwrap();
+    assert!(
+        sqlx::query("UPDATE audit_entry SET targets = '[\"to_id=2\"]' WHERE sequence = 1")
+            .execute(&pool)
+            .await
+            .is_err()
+    );
+    assert!(sqlx::query("DELETE FROM audit_entry WHERE sequence = 1")
+        .execute(&pool)
+        .await
+        .is_err());
+    assert!(audit_log.verify().await.unwrap().valid);
+
+    sqlx::query("DROP TRIGGER audit_entry_no_update")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE audit_entry SET targets = '[\"to_id=2\"]' WHERE sequence = 1")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let report = audit_log.verify().await.unwrap();
+    assert!(!report.valid);
+    assert_eq!(report.broken_at, Some(1));
+}
+
+#[tokio::test]
+async fn test_audit_log_concurrent_append() {
+    let (_db_folder, db_path) = common::temp_db_path();
+    let appends = (0..16).map(|index| {
+        let db_path = db_path.clone();
+        tokio::spawn(async move {
+            let audit_log = AuditLog::from_path(&db_path).await.unwrap();
+            audit_log
+                .append("scanner reset", vec![format!("to_id={}", index)], None)
+                .await
+                .unwrap()
+        })
+    });
+    for append in futures::future::join_all(appends).await {
+        append.unwrap();
+    }
+    let audit_log = AuditLog::from_path(&db_path).await.unwrap();
+    assert_eq!(audit_log.entries().await.unwrap().len(), 16);
+    assert!(audit_log.verify().await.unwrap().valid);
+}
+
+#[test]
+fn test_audit_command_skips_dry_run() {
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "spend",
+        "create",
+        "--chain-id",
+        "5",
+        "--asset-symbol",
+        "MTT",
+        "--amount",
+        "1",
+        "--recipient",
+        "0x0000000000000000000000000000000000000001",
+        "--dry-run",
+    ]);
+    assert!(audit_command(&args.commands).is_none());
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "spend",
+        "create",
+        "--chain-id",
+        "5",
+        "--asset-symbol",
+        "MTT",
+        "--amount",
+        "1",
+        "--recipient",
+        "0x0000000000000000000000000000000000000001",
+    ]);
+    assert_eq!(audit_command(&args.commands).unwrap().0, "spend create");
+}
@@ -0,0 +1,559 @@
+#[allow(dead_code)]
+mod common;
+
+use crate::common::{
+    mock_mystiko, mock_rpc_server, MockDeposits, MockMystikoOptions, MockScanner, MockSpends,
+};
+use clap::Parser;
+use mystiko::{
+    batch_row_status, deposit_row_key, execute_with_mystiko, parse_csv_manifest, read_manifest,
+    spend_batch_row_status, spend_row_key, BatchResults, BatchRowStatus, DepositManifestRow,
+    MystikoCliArgs, OfflineDocumentType, SpendManifestRow,
+};
+use mystiko_protos::core::document::v1::{Deposit, Spend};
+use mystiko_protos::core::handler::v1::{DepositQuote, DepositSummary, SpendQuote};
+use mystiko_protos::core::scanner::v1::{Balance, BalanceResult};
+use mystiko_protos::core::v1::transaction::Transaction as EnumTransaction;
+use mystiko_protos::core::v1::{DepositStatus, SpendStatus};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+#[test]
+fn test_parse_csv_manifest() {
+    let content =
+        "chain,asset,amount,shielded_address,bridge,dst_chain,rollup_fee,bridge_fee,executor_fee\n\
+        5,ETH,0.1,\"shielded,1\",,,0.01,,\n\
+        \n\
+        97, MTT ,10,shielded2,tbridge,5,1,0.02,0.5\n";
+    let rows: Vec<DepositManifestRow> = parse_csv_manifest(content).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        rows[0],
+        DepositManifestRow {
+            chain_id: 5,
+            asset_symbol: String::from("ETH"),
+            amount: 0.1,
+            shielded_address: String::from("shielded,1"),
+            bridge_type: None,
+            dst_chain_id: None,
+            rollup_fee: Some(0.01),
+            bridge_fee: None,
+            executor_fee: None,
+        }
+    );
+    assert_eq!(rows[1].asset_symbol, "MTT");
+    assert_eq!(rows[1].bridge_type.as_deref(), Some("tbridge"));
+    assert_eq!(rows[1].dst_chain_id, Some(5));
+    assert_eq!(rows[1].executor_fee, Some(0.5));
+
+    let missing_amount = "chain,asset,shielded_address\n5,ETH,shielded1\n";
+    let err = parse_csv_manifest::<DepositManifestRow>(missing_amount).unwrap_err();
+    assert!(err.to_string().contains("Line 2"));
+    let extra_columns = "chain,asset\n5,ETH,0.1\n";
+    assert!(parse_csv_manifest::<DepositManifestRow>(extra_columns).is_err());
+}
+
+#[tokio::test]
+async fn test_read_json_manifest_and_results() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = dir.path().join("deposits.json");
+    tokio::fs::write(
+        &manifest,
+        r#"[{"chain":5,"asset":"ETH","amount":0.1,"shielded_address":"shielded1"}]"#,
+    )
+    .await
+    .unwrap();
+    let rows: Vec<DepositManifestRow> = read_manifest(&manifest).await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].chain_id, 5);
+
+    let results_path = dir.path().join("deposits.json.results.json");
+    let manifest = manifest.to_string_lossy().to_string();
+    let mut results =
+        BatchResults::read_or_create(&results_path, OfflineDocumentType::Deposit, &manifest, 1)
+            .await
+            .unwrap();
+    assert_eq!(results.rows[0].row, 1);
+    assert_eq!(results.rows[0].status, BatchRowStatus::Pending);
+    results.rows[0].status = BatchRowStatus::Sent;
+    results.rows[0].document_id = Some(String::from("deposit1"));
+    results.write(&results_path).await.unwrap();
+    assert!(!dir.path().join("deposits.json.results.json.tmp").exists());
+
+    let results =
+        BatchResults::read_or_create(&results_path, OfflineDocumentType::Deposit, &manifest, 1)
+            .await
+            .unwrap();
+    assert_eq!(results.rows[0].status, BatchRowStatus::Sent);
+    assert_eq!(results.rows[0].document_id.as_deref(), Some("deposit1"));
+    assert!(BatchResults::read_or_create(
+        &results_path,
+        OfflineDocumentType::Deposit,
+        &manifest,
+        2
+    )
+    .await
+    .is_err());
+    assert!(
+        BatchResults::read_or_create(&results_path, OfflineDocumentType::Spend, &manifest, 1)
+            .await
+            .is_err()
+    );
+}
+
+#[test]
+fn test_batch_row_status() {
+    assert_eq!(
+        batch_row_status(DepositStatus::Unspecified as i32),
+        BatchRowStatus::Created
+    );
+    assert_eq!(
+        batch_row_status(DepositStatus::SrcPending as i32),
+        BatchRowStatus::Sending
+    );
+    assert_eq!(
+        batch_row_status(DepositStatus::Queued as i32),
+        BatchRowStatus::Sent
+    );
+    assert_eq!(
+        batch_row_status(DepositStatus::Failed as i32),
+        BatchRowStatus::Failed
+    );
+}
+
+#[tokio::test]
+async fn test_spend_batch_results_keyed() {
+    let content = "chain,asset,amount,recipient,type,key\n\
+        5,ETH,1,0xAbC,withdraw,march\n\
+        5,ETH,1,0xabc,withdraw,april\n\
+        5,ETH,2,0xdef,transfer,\n";
+    let rows: Vec<SpendManifestRow> = parse_csv_manifest(content).unwrap();
+    let keys = rows.iter().map(spend_row_key).collect::<Vec<_>>();
+    assert_eq!(
+        keys,
+        vec![
+            "5:ETH:0xabc:1:march",
+            "5:ETH:0xabc:1:april",
+            "5:ETH:0xdef:2:"
+        ]
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let results_path = dir.path().join("payouts.csv.results.json");
+    let mut results = BatchResults::read_or_create_keyed(
+        &results_path,
+        OfflineDocumentType::Spend,
+        "payouts.csv",
+        &keys,
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.rows.len(), 3);
+    results.rows[0].status = BatchRowStatus::Sent;
+    results.rows[0].document_id = Some(String::from("spend1"));
+    results.write(&results_path).await.unwrap();
+
+    let reordered = vec![keys[2].clone(), keys[0].clone()];
+    let results = BatchResults::read_or_create_keyed(
+        &results_path,
+        OfflineDocumentType::Spend,
+        "payouts.csv",
+        &reordered,
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.rows.len(), 3);
+    assert_eq!(results.rows[0].row, 1);
+    assert_eq!(results.rows[0].status, BatchRowStatus::Pending);
+    assert_eq!(results.rows[1].row, 2);
+    assert_eq!(results.rows[1].key.as_deref(), Some("5:ETH:0xabc:1:march"));
+    assert_eq!(results.rows[1].status, BatchRowStatus::Sent);
+    assert_eq!(results.rows[1].document_id.as_deref(), Some("spend1"));
+    assert_eq!(results.rows[2].key.as_deref(), Some("5:ETH:0xabc:1:april"));
+    assert!(BatchResults::read_or_create_keyed(
+        &results_path,
+        OfflineDocumentType::Deposit,
+        "payouts.csv",
+        &reordered,
+    )
+    .await
+    .is_err());
+}
+
+#[test]
+fn test_spend_batch_row_status() {
+    assert_eq!(
+        spend_batch_row_status(SpendStatus::Unspecified as i32),
+        BatchRowStatus::Created
+    );
+    assert_eq!(
+        spend_batch_row_status(SpendStatus::Pending as i32),
+        BatchRowStatus::Sending
+    );
+    assert_eq!(
+        spend_batch_row_status(SpendStatus::Succeeded as i32),
+        BatchRowStatus::Sent
+    );
+    assert_eq!(
+        spend_batch_row_status(SpendStatus::Failed as i32),
+        BatchRowStatus::Failed
+    );
+}
+
+#[tokio::test]
+async fn test_deposit_batch_results_fingerprint() {
+    let rows: Vec<DepositManifestRow> = parse_csv_manifest(
+        "chain,asset,amount,shielded_address\n5,eth,0.1,shielded1\n5,ETH,0.2,shielded2\n",
+    )
+    .unwrap();
+    let keys = rows.iter().map(deposit_row_key).collect::<Vec<_>>();
+    assert_eq!(keys, vec!["5:ETH:0.1:shielded1", "5:ETH:0.2:shielded2"]);
+
+    let dir = tempfile::tempdir().unwrap();
+    let results_path = dir.path().join("deposits.csv.results.json");
+    let mut results = BatchResults::read_or_create_matching(
+        &results_path,
+        OfflineDocumentType::Deposit,
+        "deposits.csv",
+        &keys,
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.rows[1].key.as_deref(), Some("5:ETH:0.2:shielded2"));
+    results.rows[0].status = BatchRowStatus::Sent;
+    results.write(&results_path).await.unwrap();
+
+    let swapped = vec![keys[1].clone(), keys[0].clone()];
+    let err = BatchResults::read_or_create_matching(
+        &results_path,
+        OfflineDocumentType::Deposit,
+        "deposits.csv",
+        &swapped,
+    )
+    .await
+    .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Row 1 of the results file does not match the manifest"));
+}
+
+#[tokio::test]
+async fn test_deposit_batch_reports_quote_failure() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = write_deposit_manifest(dir.path(), "5,ETH,0.1,shielded1\n").await;
+    let mut deposits = MockDeposits::new();
+    deposits
+        .expect_quote()
+        .returning(|_| Err(anyhow::anyhow!("quote unavailable")));
+    deposits.expect_create().never();
+    let mystiko = mock_mystiko(deposits).await;
+    let provider = mock_rpc_server(batch_rpc(Arc::new(AtomicU64::new(0)), "0x0")).await;
+    let err = execute_with_mystiko(&mystiko, batch_args(&manifest, &provider).commands, false)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Row 1: quote unavailable"));
+}
+
+#[tokio::test]
+async fn test_deposit_batch_checks_balance_before_create() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = write_deposit_manifest(dir.path(), "5,MTT,0.5,shielded1\n").await;
+    let mut deposits = MockDeposits::new();
+    deposits.expect_quote().returning(|_| Ok(mtt_quote()));
+    deposits
+        .expect_summary()
+        .returning(|_| Ok(summary("MTT", 0.5)));
+    deposits.expect_create().never();
+    let mystiko = mock_mystiko(deposits).await;
+    let provider = mock_rpc_server(batch_rpc(
+        Arc::new(AtomicU64::new(0)),
+        "0x00000000000000000000000000000000000000000000000000038d7ea4c68000",
+    ))
+    .await;
+    let err = execute_with_mystiko(&mystiko, batch_args(&manifest, &provider).commands, false)
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Insufficient balance of MTT for the batch: required 0.5"));
+}
+
+#[tokio::test]
+async fn test_deposit_batch_resumes_with_signer_nonce() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest =
+        write_deposit_manifest(dir.path(), "5,MTT,0.1,shielded1\n5,MTT,0.2,shielded2\n").await;
+    let rows: Vec<DepositManifestRow> = read_manifest(&manifest).await.unwrap();
+    let keys = rows.iter().map(deposit_row_key).collect::<Vec<_>>();
+    let results_path = format!("{}.results.json", manifest);
+    let mut results = BatchResults::read_or_create_matching(
+        &results_path,
+        OfflineDocumentType::Deposit,
+        &manifest,
+        &keys,
+    )
+    .await
+    .unwrap();
+    results.rows[0].status = BatchRowStatus::Sent;
+    results.rows[0].document_id = Some(String::from("deposit1"));
+    results.write(&results_path).await.unwrap();
+
+    let nonce = Arc::new(AtomicU64::new(7));
+    let sent_nonce = nonce.clone();
+    let mut deposits = MockDeposits::new();
+    deposits
+        .expect_find_by_id()
+        .withf(|id| id == "deposit1")
+        .returning(|id| {
+            Ok(Some(Deposit {
+                id,
+                status: DepositStatus::Queued as i32,
+                ..Default::default()
+            }))
+        });
+    deposits
+        .expect_quote()
+        .times(1)
+        .returning(|_| Ok(mtt_quote()));
+    deposits
+        .expect_summary()
+        .times(1)
+        .returning(|_| Ok(summary("MTT", 0.2)));
+    deposits.expect_create().times(1).returning(|options| {
+        Ok(Deposit {
+            id: String::from("deposit2"),
+            chain_id: options.chain_id,
+            asset_symbol: options.asset_symbol,
+            asset_address: Some(String::from("0xEC1d5CfB0bf18925aB722EeeBCB53Dc636834e8a")),
+            amount: options.amount,
+            ..Default::default()
+        })
+    });
+    deposits
+        .expect_send()
+        .times(1)
+        .withf(|options| {
+            options.deposit_id == "deposit2"
+                && transaction_nonce(&options.asset_approve_tx) == Some("7")
+        })
+        .returning(move |options| {
+            sent_nonce.fetch_add(2, Ordering::SeqCst);
+            Ok(Deposit {
+                id: options.deposit_id,
+                status: DepositStatus::SrcPending as i32,
+                transaction_hash: Some(String::from("0x1234")),
+                ..Default::default()
+            })
+        });
+    let mystiko = mock_mystiko(deposits).await;
+    let provider = mock_rpc_server(batch_rpc(
+        nonce.clone(),
+        "0x000000000000000000000000000000000000000000000000ffffffffffffffff",
+    ))
+    .await;
+    execute_with_mystiko(&mystiko, batch_args(&manifest, &provider).commands, false)
+        .await
+        .unwrap();
+    assert_eq!(nonce.load(Ordering::SeqCst), 9);
+
+    let results = BatchResults::read_or_create_matching(
+        &results_path,
+        OfflineDocumentType::Deposit,
+        &manifest,
+        &keys,
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.rows[0].status, BatchRowStatus::Sent);
+    assert_eq!(results.rows[0].nonce, None);
+    assert_eq!(results.rows[1].status, BatchRowStatus::Sending);
+    assert_eq!(results.rows[1].document_id.as_deref(), Some("deposit2"));
+    assert_eq!(results.rows[1].nonce, Some(7));
+}
+
+async fn write_deposit_manifest(dir: &Path, rows: &str) -> String {
+    let manifest = dir.join("deposits.csv");
+    tokio::fs::write(
+        &manifest,
+        format!("chain,asset,amount,shielded_address\n{}", rows),
+    )
+    .await
+    .unwrap();
+    manifest.to_string_lossy().to_string()
+}
+
+fn batch_args(manifest: &str, provider: &str) -> MystikoCliArgs {
+    MystikoCliArgs::parse_from([
+        "mystiko",
+        "deposit",
+        "batch",
+        "--manifest",
+        manifest,
+        "--private-key",
+        PRIVATE_KEY,
+        "--signer-provider",
+        provider,
+        "--yes",
+    ])
+}
+
+fn batch_rpc(
+    nonce: Arc<AtomicU64>,
+    token_balance: &'static str,
+) -> impl Fn(&str, &Value) -> Value + Send + Sync + 'static {
+    move |method, _| match method {
+        "eth_chainId" => json!("0x5"),
+        "eth_getTransactionCount" => json!(format!("{:#x}", nonce.load(Ordering::SeqCst))),
+        "eth_getBalance" => json!("0xde0b6b3a7640000"),
+        "eth_call" => json!(token_balance),
+        _ => Value::Null,
+    }
+}
+
+fn mtt_quote() -> DepositQuote {
+    DepositQuote {
+        asset_symbol: String::from("MTT"),
+        asset_decimals: 16,
+        min_amount: 0.01,
+        max_amount: 1.0,
+        ..Default::default()
+    }
+}
+
+fn summary(asset_symbol: &str, amount: f64) -> DepositSummary {
+    DepositSummary {
+        total_amounts: HashMap::from([(asset_symbol.to_string(), amount)]),
+        ..Default::default()
+    }
+}
+
+fn transaction_nonce(transaction: &Option<mystiko_protos::core::v1::Transaction>) -> Option<&str> {
+    match transaction.as_ref()?.transaction.as_ref()? {
+        EnumTransaction::Eip1559Transaction(transaction) => transaction.nonce.as_deref(),
+        EnumTransaction::LegacyTransaction(transaction) => transaction.nonce.as_deref(),
+    }
+}
+
+#[tokio::test]
+async fn test_spend_batch_resume_keeps_sent_row() {
+    let dir = tempfile::tempdir().unwrap();
+    let manifest = dir.path().join("payouts.csv");
+    tokio::fs::write(
+        &manifest,
+        "chain,asset,amount,recipient,type\n5,ETH,1,0xabc,withdraw\n5,ETH,2,0xdef,withdraw\n",
+    )
+    .await
+    .unwrap();
+    let manifest = manifest.to_string_lossy().to_string();
+    let rows: Vec<SpendManifestRow> = read_manifest(&manifest).await.unwrap();
+    let keys = rows.iter().map(spend_row_key).collect::<Vec<_>>();
+    let results_path = format!("{}.results.json", manifest);
+    let mut results = BatchResults::read_or_create_keyed(
+        &results_path,
+        OfflineDocumentType::Spend,
+        &manifest,
+        &keys,
+    )
+    .await
+    .unwrap();
+    results.rows[0].status = BatchRowStatus::Sent;
+    results.rows[0].document_id = Some(String::from("spend1"));
+    results.write(&results_path).await.unwrap();
+
+    let mut spends = MockSpends::new();
+    spends
+        .expect_find_by_id()
+        .withf(|id| id == "spend1")
+        .returning(|id| {
+            Ok(Some(Spend {
+                id,
+                status: SpendStatus::Succeeded as i32,
+                ..Default::default()
+            }))
+        });
+    spends.expect_quote().times(1).returning(|_| {
+        Ok(SpendQuote {
+            asset_symbol: String::from("ETH"),
+            min_amount: 0.1,
+            max_amount: 10.0,
+            valid: true,
+            ..Default::default()
+        })
+    });
+    spends
+        .expect_create()
+        .times(1)
+        .withf(|options| options.recipient == "0xdef")
+        .returning(|options| {
+            Ok(Spend {
+                id: String::from("spend2"),
+                chain_id: options.chain_id,
+                amount: options.amount,
+                recipient: options.recipient,
+                ..Default::default()
+            })
+        });
+    spends
+        .expect_send()
+        .times(1)
+        .withf(|options| options.spend_id == "spend2")
+        .returning(|options| {
+            Ok(Spend {
+                id: options.spend_id,
+                status: SpendStatus::Succeeded as i32,
+                transaction_hash: Some(String::from("0x5678")),
+                ..Default::default()
+            })
+        });
+    let mut scanner = MockScanner::new();
+    scanner.expect_balance().returning(|_| {
+        Ok(BalanceResult {
+            balances: vec![Balance {
+                asset_symbol: String::from("ETH"),
+                unspent: 5.0,
+                ..Default::default()
+            }],
+        })
+    });
+    let mystiko = mock_mystiko(
+        MockMystikoOptions::builder()
+            .spends(spends)
+            .scanner(scanner)
+            .build(),
+    )
+    .await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "spend",
+        "batch",
+        "--manifest",
+        &manifest,
+        "--password",
+        "P@ssw0rd",
+        "--private-key",
+        PRIVATE_KEY,
+        "--yes",
+    ]);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+
+    let results = BatchResults::read_or_create_keyed(
+        &results_path,
+        OfflineDocumentType::Spend,
+        &manifest,
+        &keys,
+    )
+    .await
+    .unwrap();
+    assert_eq!(results.rows[0].status, BatchRowStatus::Sent);
+    assert_eq!(results.rows[0].document_id.as_deref(), Some("spend1"));
+    assert_eq!(results.rows[1].status, BatchRowStatus::Sent);
+    assert_eq!(results.rows[1].document_id.as_deref(), Some("spend2"));
+    assert_eq!(results.rows[1].transaction_hash.as_deref(), Some("0x5678"));
+}
@@ -22,7 +22,10 @@ use mystiko_protos::core::synchronizer::v1::{
 use mystiko_protos::storage::v1::QueryFilter;
 use mystiko_storage::{ColumnValues, SqlStatementFormatter, StatementFormatter, Storage};
 use mystiko_storage_sqlite::SqliteStorage;
+use serde_json::{json, Value};
 use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
 use typed_builder::TypedBuilder;
 
 pub fn temp_db_path() -> (tempfile::TempDir, String) {
@@ -35,6 +38,66 @@ pub fn temp_db_path() -> (tempfile::TempDir, String) {
     (db_folder, db_path)
 }
 
+pub async fn mock_rpc_server<H>(handler: H) -> String
+where
+    H: Fn(&str, &Value) -> Value + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handler = Arc::new(handler);
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let handler = handler.clone();
+            tokio::spawn(async move {
+                let _ = serve_rpc_connection(stream, handler).await;
+            });
+        }
+    });
+    url
+}
+
+async fn serve_rpc_connection<H>(stream: TcpStream, handler: Arc<H>) -> std::io::Result<()>
+where
+    H: Fn(&str, &Value) -> Value,
+{
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+            let line = line.trim_end();
+            if line.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or_default();
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).await?;
+        let request: Value = serde_json::from_slice(&body)?;
+        let method = request["method"].as_str().unwrap_or_default();
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": handler(method, &request["params"]),
+        })
+        .to_string();
+        let head = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n",
+            response.len()
+        );
+        let stream = reader.get_mut();
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(response.as_bytes()).await?;
+    }
+}
+
 pub async fn mock_mystiko<O>(mock_options: O) -> MockMystiko
 where
     O: Into<MockMystikoOptions>,
@@ -0,0 +1,74 @@
+use clap::Parser;
+use mystiko::{diff_config_files, execute, validate_config_file, MystikoCliArgs};
+use serde_json::{json, Value};
+
+#[tokio::test]
+async fn test_config_validate() {
+    let args =
+        MystikoCliArgs::parse_from(["mystiko", "config", "validate", "tests/files/config.json"]);
+    execute(args).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_config_validate_invalid() {
+    let (_folder, file) = write_config(|config| {
+        config["chains"][1]["providers"] = json!([]);
+        config["chains"][0]["depositContracts"][0]["peerContractAddress"] = json!("0x1234");
+    });
+    let report = validate_config_file(&file).await.unwrap();
+    assert!(!report.valid);
+    assert!(report
+        .errors
+        .contains(&String::from("chain 97 has no providers")));
+    let args = MystikoCliArgs::parse_from(["mystiko", "config", "validate", &file]);
+    assert!(execute(args).await.is_err());
+}
+
+#[tokio::test]
+async fn test_config_diff() {
+    let (_folder, file) = write_config(|config| {
+        config["chains"][0]["name"] = json!("Ethereum Goerli Testnet");
+        config["chains"][1]["poolContracts"] = json!([]);
+        config["chains"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ "chainId": 56 }));
+    });
+    let report = diff_config_files("tests/files/config.json", &file)
+        .await
+        .unwrap();
+    assert_eq!(report.added_chains, vec![56_u64]);
+    assert!(report.removed_chains.is_empty());
+    assert_eq!(report.removed_contracts.len(), 1);
+    assert_eq!(
+        report.removed_contracts[0].address,
+        "0xbe2c9c8a00951662df3a978b25f448968f0595ae"
+    );
+    assert_eq!(report.changed_parameters.len(), 1);
+    assert_eq!(report.changed_parameters[0].path, "chains[5].name");
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "config",
+        "diff",
+        "tests/files/config.json",
+        &file,
+    ]);
+    execute(args).await.unwrap();
+}
+
+fn write_config<M>(modify: M) -> (tempfile::TempDir, String)
+where
+    M: FnOnce(&mut Value),
+{
+    let content = std::fs::read_to_string("tests/files/config.json").unwrap();
+    let mut config: Value = serde_json::from_str(&content).unwrap();
+    modify(&mut config);
+    let folder = tempfile::tempdir().unwrap();
+    let file = folder
+        .path()
+        .join("config.json")
+        .to_string_lossy()
+        .to_string();
+    std::fs::write(&file, serde_json::to_string_pretty(&config).unwrap()).unwrap();
+    (folder, file)
+}
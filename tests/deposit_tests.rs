@@ -3,10 +3,16 @@ mod common;
 
 use crate::common::{mock_mystiko, MockDeposits};
 use clap::Parser;
-use mystiko::{execute_with_mystiko, MystikoCliArgs};
-use mystiko_core::DepositColumn;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::Eip1559TransactionRequest;
+use mystiko::{
+    execute_with_mystiko, read_manifest, split_deposit_amount, BatchResults, BatchRowStatus,
+    CapturingSigner, DepositManifestRow, DepositSplitPiece, MystikoCliArgs, OfflineDocumentType,
+};
+use mystiko_core::{DepositColumn, TransactionSigner};
 use mystiko_protos::common::v1::BridgeType;
 use mystiko_protos::core::document::v1::Deposit;
+use mystiko_protos::core::handler::v1::DepositQuote;
 use mystiko_protos::core::v1::DepositStatus;
 use mystiko_protos::storage::v1::{
     Condition, ConditionOperator, Order, OrderBy, QueryFilter, SubFilter,
@@ -88,6 +94,7 @@ async fn test_deposit_create() {
         "mystiko",
         "deposit",
         "create",
+        "--yes",
         "--chain-id",
         "97",
         "--asset-symbol",
@@ -126,6 +133,333 @@ async fn test_deposit_create() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_deposit_create_dry_run() {
+    let mut deposits = MockDeposits::new();
+    deposits
+        .expect_quote()
+        .withf(|options| options.chain_id == 97_u64 && options.asset_symbol == "MTT")
+        .returning(|_| {
+            Ok(DepositQuote {
+                asset_symbol: "MTT".to_string(),
+                min_amount: 1.0,
+                max_amount: 100.0,
+                ..Default::default()
+            })
+        });
+    deposits.expect_summary().never();
+    deposits.expect_create().never();
+    let mystiko = mock_mystiko(deposits).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "deposit",
+        "create",
+        "--dry-run",
+        "--chain-id",
+        "97",
+        "--asset-symbol",
+        "MTT",
+        "--shielded-address",
+        "Shielded_Address",
+        "--amount",
+        "123",
+        "--private-key",
+        "private_key",
+    ]);
+    assert!(execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_deposit_create_dry_run_captures_transactions() {
+    let mut deposits = MockDeposits::new();
+    deposits.expect_quote().returning(|_| {
+        Ok(DepositQuote {
+            asset_symbol: "MTT".to_string(),
+            min_amount: 1.0,
+            max_amount: 100.0,
+            ..Default::default()
+        })
+    });
+    deposits
+        .expect_summary()
+        .withf(|options| options.amount == 10.0_f64)
+        .returning(|_| Ok(Default::default()));
+    deposits.expect_create().times(1).returning(|_| {
+        Ok(Deposit {
+            id: "1234".to_string(),
+            ..Default::default()
+        })
+    });
+    deposits
+        .expect_send_with_signer::<CapturingSigner>()
+        .times(1)
+        .withf(|options, _| options.deposit_id == "1234")
+        .returning(|_, signer| {
+            let sent = futures::executor::block_on(signer.send_transaction(
+                97,
+                TypedTransaction::Eip1559(Eip1559TransactionRequest::new()),
+            ));
+            assert_eq!(signer.transactions().len(), 1);
+            sent.map(|_| Default::default())
+        });
+    deposits.expect_send().never();
+    let mystiko = mock_mystiko(deposits).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "deposit",
+        "create",
+        "--dry-run",
+        "--chain-id",
+        "97",
+        "--asset-symbol",
+        "MTT",
+        "--shielded-address",
+        "Shielded_Address",
+        "--amount",
+        "10",
+    ]);
+    assert!(args.commands.is_dry_run());
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_deposit_create_split() {
+    let mut deposits = MockDeposits::new();
+    deposits.expect_quote().returning(|_| {
+        Ok(DepositQuote {
+            asset_symbol: "MTT".to_string(),
+            asset_decimals: 18,
+            min_amount: 0.1,
+            max_amount: 100.0,
+            recommended_amounts: vec![1.0, 10.0],
+            ..Default::default()
+        })
+    });
+    deposits
+        .expect_create()
+        .times(3)
+        .withf(|options| options.amount == 10.0_f64 || options.amount == 1.0_f64)
+        .returning(|options| {
+            Ok(Deposit {
+                id: options.amount.to_string(),
+                ..Default::default()
+            })
+        });
+    deposits
+        .expect_send()
+        .times(3)
+        .returning(|_| Ok(Default::default()));
+    let mystiko = mock_mystiko(deposits).await;
+    let dir = tempfile::tempdir().unwrap();
+    let plan_path = dir.path().join("split.json");
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "deposit",
+        "create",
+        "--yes",
+        "--split",
+        "standard",
+        "--split-plan",
+        plan_path.to_str().unwrap(),
+        "--chain-id",
+        "97",
+        "--asset-symbol",
+        "MTT",
+        "--shielded-address",
+        "Shielded_Address",
+        "--amount",
+        "12",
+        "--private-key",
+        "private_key",
+    ]);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let rows: Vec<DepositManifestRow> = read_manifest(&plan_path).await.unwrap();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows.iter().map(|row| row.amount).sum::<f64>(), 12.0);
+    let results_path = dir.path().join("split.json.results.json");
+    let results = BatchResults::read_or_create(
+        &results_path,
+        OfflineDocumentType::Deposit,
+        plan_path.to_str().unwrap(),
+        3,
+    )
+    .await
+    .unwrap();
+    assert!(results
+        .rows
+        .iter()
+        .all(|row| row.document_id.is_some() && row.status != BatchRowStatus::Pending));
+}
+
+#[tokio::test]
+async fn test_deposit_create_split_resumes_plan() {
+    let dir = tempfile::tempdir().unwrap();
+    let plan_path = dir.path().join("split.json");
+    let plan = [10.0, 1.0, 1.0]
+        .into_iter()
+        .map(|amount| DepositManifestRow {
+            chain_id: 97,
+            asset_symbol: "MTT".to_string(),
+            amount,
+            shielded_address: "Shielded_Address".to_string(),
+            bridge_type: None,
+            dst_chain_id: None,
+            rollup_fee: None,
+            bridge_fee: None,
+            executor_fee: None,
+        })
+        .collect::<Vec<_>>();
+    tokio::fs::write(&plan_path, serde_json::to_string(&plan).unwrap())
+        .await
+        .unwrap();
+    let mut results =
+        BatchResults::new(OfflineDocumentType::Deposit, plan_path.to_str().unwrap(), 3);
+    results.rows[0].status = BatchRowStatus::Sent;
+    results.rows[0].document_id = Some("sent".to_string());
+    results.rows[1].status = BatchRowStatus::Created;
+    results.rows[1].document_id = Some("created".to_string());
+    let results_path = dir.path().join("split.json.results.json");
+    results.write(&results_path).await.unwrap();
+
+    let mut deposits = MockDeposits::new();
+    deposits.expect_quote().returning(|_| {
+        Ok(DepositQuote {
+            asset_symbol: "MTT".to_string(),
+            asset_decimals: 18,
+            min_amount: 0.1,
+            max_amount: 100.0,
+            recommended_amounts: vec![1.0, 10.0],
+            ..Default::default()
+        })
+    });
+    deposits.expect_find_by_id().returning(|id| {
+        let status = if id == "sent" {
+            DepositStatus::Executed
+        } else {
+            DepositStatus::Unspecified
+        };
+        Ok(Some(Deposit {
+            id,
+            status: status as i32,
+            ..Default::default()
+        }))
+    });
+    deposits
+        .expect_create()
+        .times(1)
+        .withf(|options| options.amount == 1.0_f64)
+        .returning(|_| {
+            Ok(Deposit {
+                id: "new".to_string(),
+                ..Default::default()
+            })
+        });
+    deposits
+        .expect_send()
+        .times(2)
+        .withf(|options| options.deposit_id == "created" || options.deposit_id == "new")
+        .returning(|options| {
+            Ok(Deposit {
+                id: options.deposit_id,
+                status: DepositStatus::Executed as i32,
+                ..Default::default()
+            })
+        });
+    let mystiko = mock_mystiko(deposits).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "deposit",
+        "create",
+        "--yes",
+        "--split",
+        "standard",
+        "--split-plan",
+        plan_path.to_str().unwrap(),
+        "--chain-id",
+        "97",
+        "--asset-symbol",
+        "MTT",
+        "--shielded-address",
+        "Shielded_Address",
+        "--amount",
+        "12",
+        "--private-key",
+        "private_key",
+    ]);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let results = BatchResults::read_or_create(
+        &results_path,
+        OfflineDocumentType::Deposit,
+        plan_path.to_str().unwrap(),
+        3,
+    )
+    .await
+    .unwrap();
+    assert!(results
+        .rows
+        .iter()
+        .all(|row| row.status == BatchRowStatus::Sent));
+    assert_eq!(results.rows[2].document_id.as_deref(), Some("new"));
+}
+
+#[test]
+fn test_split_deposit_amount() {
+    let quote = DepositQuote {
+        asset_symbol: "ETH".to_string(),
+        asset_decimals: 18,
+        min_amount: 0.1,
+        max_amount: 100.0,
+        recommended_amounts: vec![0.1, 1.0, 10.0, 1000.0],
+        ..Default::default()
+    };
+    let amounts = |pieces: Vec<DepositSplitPiece>| {
+        pieces
+            .into_iter()
+            .map(|piece| (piece.amount, piece.standard))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        amounts(split_deposit_amount(13.7, &quote).unwrap()),
+        vec![
+            (10.0, true),
+            (1.0, true),
+            (1.0, true),
+            (1.0, true),
+            (0.1, true),
+            (0.1, true),
+            (0.1, true),
+            (0.1, true),
+            (0.1, true),
+            (0.1, true),
+            (0.1, true),
+        ]
+    );
+    assert!(split_deposit_amount(1.25, &quote).is_err());
+    assert!(split_deposit_amount(0.0, &quote).is_err());
+    let quote = DepositQuote {
+        recommended_amounts: vec![1.0],
+        min_amount: 0.5,
+        ..quote
+    };
+    assert_eq!(
+        amounts(split_deposit_amount(2.7, &quote).unwrap()),
+        vec![(1.0, true), (1.0, true), (0.7, false)]
+    );
+    let quote = DepositQuote {
+        recommended_amounts: vec![],
+        ..quote
+    };
+    assert!(split_deposit_amount(2.0, &quote).is_err());
+}
+
 #[tokio::test]
 async fn test_deposit_list() {
     let sub_filters = vec![
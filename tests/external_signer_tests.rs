@@ -0,0 +1,48 @@
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, TransactionRequest};
+use mystiko::{request_signer_address, sign_with_command};
+use std::time::{Duration, Instant};
+
+const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+
+#[tokio::test]
+async fn test_reference_signer() {
+    let command = format!(
+        "MYSTIKO_SIGNER_PRIVATE_KEY={} {} reference-signer",
+        PRIVATE_KEY,
+        env!("CARGO_BIN_EXE_mystiko")
+    );
+    let address = request_signer_address(&command, None).await.unwrap();
+    assert_eq!(address, ADDRESS.parse::<Address>().unwrap());
+
+    let mut transaction: TypedTransaction = TransactionRequest::new()
+        .to(Address::zero())
+        .value(1000)
+        .gas(21000)
+        .gas_price(1000000000)
+        .nonce(3)
+        .into();
+    let signature = sign_with_command(&command, 5, address, &transaction, None)
+        .await
+        .unwrap();
+    transaction.set_chain_id(5);
+    assert_eq!(signature.recover(transaction.sighash()).unwrap(), address);
+
+    assert!(
+        sign_with_command(&command, 5, Address::zero(), &transaction, None)
+            .await
+            .is_err()
+    );
+    let command = format!("{} reference-signer", env!("CARGO_BIN_EXE_mystiko"));
+    assert!(request_signer_address(&command, None).await.is_err());
+    assert!(request_signer_address("exit 1", None).await.is_err());
+}
+
+#[tokio::test]
+async fn test_signer_command_timeout() {
+    let started = Instant::now();
+    let result = request_signer_address("sleep 30", Some(200)).await;
+    assert!(result.unwrap_err().to_string().contains("timed out"));
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
@@ -0,0 +1,192 @@
+#[allow(dead_code)]
+mod common;
+
+use clap::Parser;
+use mystiko::{
+    execute_spend_schedule_command, schedule_timestamp, JobStore, MystikoCliArgs, MystikoCommands,
+    SpendCommand, SpendCommands, SpendJob, SpendJobStatus, SpendScheduleCommand,
+};
+use std::time::{Duration, UNIX_EPOCH};
+
+#[tokio::test]
+async fn test_spend_schedule() {
+    let (_db_folder, db_path) = common::temp_db_path();
+    let job_store = JobStore::from_path(&db_path).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "spend",
+        "schedule",
+        "--chain-id",
+        "97",
+        "--asset-symbol",
+        "MTT",
+        "--recipient",
+        "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23",
+        "--amount",
+        "max",
+        "--delay",
+        "1h",
+        "--delay-max",
+        "2h",
+    ]);
+    let schedule_args = match args.commands {
+        MystikoCommands::Spend(SpendCommand {
+            commands: SpendCommands::Schedule(schedule_args),
+        }) => SpendScheduleCommand {
+            jobs_path: Some(db_path.clone().into()),
+            ..schedule_args
+        },
+        _ => panic!("unexpected command"),
+    };
+    execute_spend_schedule_command(schedule_args.clone(), false)
+        .await
+        .unwrap();
+    execute_spend_schedule_command(schedule_args, false)
+        .await
+        .unwrap();
+    let jobs = job_store.jobs().await.unwrap();
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0].id, 1);
+    assert_eq!(jobs[1].id, 2);
+    assert_eq!(jobs[0].status, SpendJobStatus::Scheduled);
+    assert_eq!(jobs[0].chain_id, 97);
+    assert_eq!(jobs[0].amount, "max");
+    assert!(jobs[0].execute_at >= jobs[0].created_at + 3600);
+    assert!(jobs[0].execute_at <= jobs[0].created_at + 7200);
+
+    let cancelled = job_store
+        .transition(1, SpendJobStatus::Scheduled, SpendJobStatus::Cancelled)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(cancelled.status, SpendJobStatus::Cancelled);
+    assert!(job_store
+        .transition(1, SpendJobStatus::Scheduled, SpendJobStatus::Running)
+        .await
+        .unwrap()
+        .is_none());
+    let mut job = job_store.jobs().await.unwrap().remove(1);
+    job.spend_id = Some("spend_id".to_string());
+    job_store.update(&job).await.unwrap();
+    let jobs = job_store.jobs().await.unwrap();
+    assert_eq!(jobs[0].status, SpendJobStatus::Cancelled);
+    assert_eq!(jobs[1].spend_id.as_deref(), Some("spend_id"));
+}
+
+#[tokio::test]
+async fn test_spend_schedule_concurrent() {
+    let (_db_folder, db_path) = common::temp_db_path();
+    let job_store = JobStore::from_path(&db_path).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "spend",
+        "schedule",
+        "--recipient",
+        "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23",
+        "--amount",
+        "1",
+        "--delay",
+        "1h",
+    ]);
+    let schedule_args = match args.commands {
+        MystikoCommands::Spend(SpendCommand {
+            commands: SpendCommands::Schedule(schedule_args),
+        }) => SpendScheduleCommand {
+            jobs_path: Some(db_path.clone().into()),
+            ..schedule_args
+        },
+        _ => panic!("unexpected command"),
+    };
+    let schedules = (0..8).map(|_| {
+        let schedule_args = schedule_args.clone();
+        tokio::spawn(async move { execute_spend_schedule_command(schedule_args, true).await })
+    });
+    for schedule in futures::future::join_all(schedules).await {
+        schedule.unwrap().unwrap();
+    }
+    let mut ids = job_store
+        .jobs()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|job| job.id)
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    assert_eq!(ids, (1..=8).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_jobs_fail_abandoned() {
+    let job_store = JobStore::from_memory().await.unwrap();
+    let job = SpendJob {
+        id: 0,
+        status: SpendJobStatus::Scheduled,
+        owner_pid: None,
+        created_at: 100,
+        execute_at: 100,
+        chain_id: 97,
+        asset_symbol: "MTT".to_string(),
+        spend_type: None,
+        bridge_type: None,
+        version: None,
+        recipient: "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23".to_string(),
+        amount: "1".to_string(),
+        rollup_fee: None,
+        relayer: None,
+        query_timeout_ms: None,
+        confirmations: None,
+        executed_at: None,
+        spend_id: None,
+        transaction_hash: None,
+        error: None,
+    };
+    let running = job_store.add(job.clone()).await.unwrap();
+    let running = job_store
+        .transition(
+            running.id,
+            SpendJobStatus::Scheduled,
+            SpendJobStatus::Running,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(running.owner_pid, Some(std::process::id()));
+    let mut abandoned = job_store.add(job).await.unwrap();
+    abandoned.status = SpendJobStatus::Running;
+    abandoned.owner_pid = Some(i32::MAX as u32);
+    job_store.update(&abandoned).await.unwrap();
+
+    let failed = job_store.fail_abandoned("interrupted").await.unwrap();
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0].id, abandoned.id);
+    assert_eq!(failed[0].status, SpendJobStatus::Failed);
+    assert_eq!(failed[0].error.as_deref(), Some("interrupted"));
+    let jobs = job_store.jobs().await.unwrap();
+    assert_eq!(jobs[0].status, SpendJobStatus::Running);
+}
+
+#[test]
+fn test_schedule_timestamp() {
+    assert_eq!(
+        schedule_timestamp(100, None, Some(Duration::from_secs(60)), None).unwrap(),
+        160
+    );
+    let timestamp = schedule_timestamp(
+        100,
+        None,
+        Some(Duration::from_secs(60)),
+        Some(Duration::from_secs(120)),
+    )
+    .unwrap();
+    assert!((160..=220).contains(&timestamp));
+    assert!(schedule_timestamp(
+        100,
+        None,
+        Some(Duration::from_secs(60)),
+        Some(Duration::from_secs(30)),
+    )
+    .is_err());
+    let at = UNIX_EPOCH + Duration::from_secs(1000);
+    assert_eq!(schedule_timestamp(100, Some(at), None, None).unwrap(), 1000);
+    assert!(schedule_timestamp(2000, Some(at), None, None).is_err());
+}
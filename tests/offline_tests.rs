@@ -0,0 +1,224 @@
+#[allow(dead_code)]
+mod common;
+
+use crate::common::{mock_mystiko, mock_rpc_server, MockDeposits};
+use clap::Parser;
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, NameOrAddress, TransactionRequest, U256};
+use ethers_core::utils::{keccak256, rlp};
+use mystiko::{
+    execute_with_mystiko, CapturedTransaction, MystikoCliArgs, OfflineDocumentType,
+    OfflineTransaction, OfflineTransactionType, OfflineTransactions,
+};
+use mystiko_protos::common::v1::BridgeType;
+use mystiko_protos::core::document::v1::Deposit;
+use serde_json::{json, Value};
+
+const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const ADDRESS: &str = "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23";
+
+#[tokio::test]
+async fn test_sign_offline_transactions() {
+    let (folder, db_path) = common::temp_db_path();
+    let file = folder
+        .path()
+        .join("deposit.json")
+        .to_string_lossy()
+        .to_string();
+    let from = ADDRESS.parse::<Address>().unwrap();
+    let transaction: TypedTransaction = TransactionRequest::new()
+        .to(Address::zero())
+        .value(1000)
+        .gas(21000)
+        .gas_price(1000000000)
+        .into();
+    let captured = CapturedTransaction {
+        chain_id: 5,
+        from,
+        transaction,
+    };
+    let mut transactions = OfflineTransactions::new(
+        OfflineDocumentType::Deposit,
+        String::from("deposit_id"),
+        vec![OfflineTransaction::new(
+            OfflineTransactionType::Deposit,
+            captured,
+        )],
+    );
+    assert!(transactions.assign_nonces(None, None).await.is_err());
+    transactions.assign_nonces(Some(7), None).await.unwrap();
+    transactions.write(&file).await.unwrap();
+
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "broadcast",
+        &file,
+        "--provider",
+        "http://localhost:8545",
+        "--yes",
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "sign",
+        &file,
+        "--private-key",
+        "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d",
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+    let args = MystikoCliArgs::parse_from(["mystiko", "sign", &file, "--private-key", PRIVATE_KEY]);
+    mystiko::execute(args).await.unwrap();
+
+    let transactions = OfflineTransactions::read(&file).await.unwrap();
+    assert!(transactions.is_signed());
+    let signed = &transactions.transactions[0];
+    assert_eq!(signed.transaction.nonce().unwrap().as_u64(), 7);
+    let raw_transaction = signed.raw_transaction.clone().unwrap();
+    assert_eq!(
+        signed.transaction_hash.unwrap().as_bytes(),
+        keccak256(&raw_transaction)
+    );
+    let (decoded, signature) =
+        TypedTransaction::decode_signed(&rlp::Rlp::new(&raw_transaction)).unwrap();
+    assert_eq!(signature.recover(decoded.sighash()).unwrap(), from);
+    assert_eq!(decoded.chain_id().unwrap().as_u64(), 5);
+}
+
+#[tokio::test]
+async fn test_deposit_prepare_builds_approve_and_deposit() {
+    let folder = tempfile::tempdir().unwrap();
+    let file = folder
+        .path()
+        .join("deposit.json")
+        .to_string_lossy()
+        .to_string();
+    let mut deposits = MockDeposits::new();
+    deposits.expect_create().times(2).returning(|options| {
+        Ok(Deposit {
+            id: String::from("deposit_id"),
+            chain_id: options.chain_id,
+            contract_address: String::from("0x961f315a836542e603a3df2e0dd9d4ecd06ebc67"),
+            asset_symbol: options.asset_symbol,
+            asset_decimals: 16,
+            asset_address: Some(String::from(MTT_ADDRESS)),
+            bridge_type: BridgeType::Loop as i32,
+            amount: options.amount,
+            rollup_fee_amount: options.rollup_fee_amount.unwrap_or_default(),
+            commitment_hash: String::from("123"),
+            hash_k: String::from("456"),
+            random_s: String::from("789"),
+            encrypted_note: String::from("0xabcd"),
+            ..Default::default()
+        })
+    });
+    deposits.expect_update().never();
+    deposits
+        .expect_send_with_signer::<mystiko::CapturingSigner>()
+        .never();
+    let mystiko = mock_mystiko(deposits).await;
+    let provider = mock_rpc_server(prepare_rpc).await;
+    let prepare_args = |gas_limit: Option<&str>| {
+        let mut args = vec![
+            "mystiko",
+            "deposit",
+            "prepare",
+            "--chain-id",
+            "5",
+            "--asset-symbol",
+            "MTT",
+            "--amount",
+            "0.1",
+            "--rollup-fee",
+            "0.01",
+            "--shielded-address",
+            "shielded1",
+            "--from",
+            ADDRESS,
+            "--signer-provider",
+            &provider,
+            "--out",
+            &file,
+        ];
+        if let Some(gas_limit) = gas_limit {
+            args.extend(["--deposit-gas-limit", gas_limit]);
+        }
+        MystikoCliArgs::parse_from(args)
+    };
+
+    let err = execute_with_mystiko(&mystiko, prepare_args(None).commands, false)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("pass --deposit-gas-limit"));
+    execute_with_mystiko(&mystiko, prepare_args(Some("500000")).commands, false)
+        .await
+        .unwrap();
+
+    let transactions = OfflineTransactions::read(&file).await.unwrap();
+    assert_eq!(transactions.document_id, "deposit_id");
+    assert_eq!(transactions.transactions.len(), 2);
+    let approve = &transactions.transactions[0];
+    assert_eq!(
+        approve.transaction_type,
+        OfflineTransactionType::AssetApprove
+    );
+    assert_eq!(
+        approve.transaction.to(),
+        Some(&NameOrAddress::Address(MTT_ADDRESS.parse().unwrap()))
+    );
+    assert_eq!(
+        &approve.transaction.data().unwrap()[..4],
+        &[0x09, 0x5e, 0xa7, 0xb3]
+    );
+    assert_eq!(approve.transaction.gas(), Some(&U256::from(60000)));
+    assert_eq!(approve.transaction.nonce(), Some(&U256::from(3)));
+    let deposit = &transactions.transactions[1];
+    assert_eq!(deposit.transaction_type, OfflineTransactionType::Deposit);
+    assert_eq!(deposit.transaction.gas(), Some(&U256::from(500000)));
+    assert_eq!(deposit.transaction.nonce(), Some(&U256::from(4)));
+    assert_eq!(deposit.transaction.value(), Some(&U256::zero()));
+}
+
+const MTT_ADDRESS: &str = "0xEC1d5CfB0bf18925aB722EeeBCB53Dc636834e8a";
+
+fn prepare_rpc(method: &str, _params: &Value) -> Value {
+    match method {
+        "eth_chainId" => json!("0x5"),
+        "eth_call" => json!(format!("0x{}", "0".repeat(64))),
+        "eth_estimateGas" => json!("0xea60"),
+        "eth_getTransactionCount" => json!("0x3"),
+        "eth_getBlockByNumber" => json!({
+            "hash": "0x3333333333333333333333333333333333333333333333333333333333333333",
+            "parentHash": "0x4444444444444444444444444444444444444444444444444444444444444444",
+            "sha3Uncles": "0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+            "miner": "0x0000000000000000000000000000000000000000",
+            "stateRoot": "0x5555555555555555555555555555555555555555555555555555555555555555",
+            "transactionsRoot": "0x6666666666666666666666666666666666666666666666666666666666666666",
+            "receiptsRoot": "0x7777777777777777777777777777777777777777777777777777777777777777",
+            "number": "0x10",
+            "gasUsed": "0x0",
+            "gasLimit": "0x1c9c380",
+            "extraData": "0x",
+            "logsBloom": format!("0x{}", "0".repeat(512)),
+            "timestamp": "0x64",
+            "difficulty": "0x0",
+            "totalDifficulty": "0x0",
+            "uncles": [],
+            "transactions": [],
+            "size": "0x220",
+            "mixHash": "0x8888888888888888888888888888888888888888888888888888888888888888",
+            "nonce": "0x0000000000000000",
+            "baseFeePerGas": "0x3b9aca00",
+        }),
+        "eth_feeHistory" => json!({
+            "oldestBlock": "0x10",
+            "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00"],
+            "gasUsedRatio": [0.5],
+            "reward": [["0x59682f00"]],
+        }),
+        _ => Value::Null,
+    }
+}
@@ -0,0 +1,14 @@
+#[allow(dead_code)]
+mod common;
+
+use mystiko::is_confirmed;
+
+#[test]
+fn test_is_confirmed() {
+    assert!(is_confirmed("y\n"));
+    assert!(is_confirmed("Yes"));
+    assert!(is_confirmed("  YES  \n"));
+    assert!(!is_confirmed("\n"));
+    assert!(!is_confirmed("n"));
+    assert!(!is_confirmed("yep"));
+}
@@ -0,0 +1,330 @@
+#[allow(dead_code)]
+mod common;
+
+use crate::common::{mock_mystiko, mock_rpc_server, MockDeposits, MockSpends};
+use clap::Parser;
+use ethers_core::types::U256;
+use mystiko::{
+    execute_with_mystiko, MystikoCliArgs, MystikoCommands, ReplacementStore, SpendCommand,
+    SpendCommands, TransactionOutcome, TransactionReplacementRecord,
+};
+use mystiko_protos::core::document::v1::{Deposit, Spend};
+use mystiko_protos::core::v1::{DepositStatus, SpendStatus};
+use mystiko_protos::storage::v1::QueryFilter;
+use serde_json::{json, Value};
+
+const SENDER: &str = "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+const PENDING_HASH: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const CANCEL_HASH: &str = "0x2222222222222222222222222222222222222222222222222222222222222222";
+
+#[test]
+fn test_transaction_outcome_failure_reason() {
+    let hash = "0x9f8a9d1bd1d9b3f7a5cdb3b9dcb0e0c3d6f1b1a6c2e3f4a5b6c7d8e9f0a1b2c3";
+    assert_eq!(
+        TransactionOutcome::Missing.failure_reason("deposit", None),
+        Some(String::from("deposit transaction was never recorded"))
+    );
+    assert_eq!(
+        TransactionOutcome::Reverted.failure_reason("asset approve", Some(hash)),
+        Some(format!("asset approve transaction {} reverted", hash))
+    );
+    assert_eq!(
+        TransactionOutcome::Dropped.failure_reason("spend", Some(hash)),
+        Some(format!(
+            "spend transaction {} was dropped, its nonce was used by another transaction",
+            hash
+        ))
+    );
+    assert_eq!(
+        TransactionOutcome::Cancelled.failure_reason("deposit", Some(hash)),
+        Some(format!("deposit transaction {} was cancelled", hash))
+    );
+    assert_eq!(
+        TransactionOutcome::Unknown.failure_reason("spend", Some(hash)),
+        None
+    );
+    assert_eq!(
+        TransactionOutcome::Unknown.pending_reason("spend", Some(hash)),
+        Some(format!(
+            "spend transaction {} is unknown to the provider and its nonce is not recorded",
+            hash
+        ))
+    );
+    assert_eq!(
+        TransactionOutcome::Pending.failure_reason("spend", Some(hash)),
+        None
+    );
+    assert_eq!(
+        TransactionOutcome::Succeeded.failure_reason("deposit", Some(hash)),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_spend_recover_keeps_unconfirmed_nonce_pending() {
+    let mut spends = pending_spends();
+    spends.expect_fix_status().never();
+    let mystiko = mock_mystiko(spends).await;
+    let provider = mock_rpc_server(recover_rpc(vec![], true, 3)).await;
+    let args = recover_args("5", &provider, None);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_spend_recover_marks_dropped_after_nonce_passed() {
+    let mut spends = pending_spends();
+    spends
+        .expect_fix_status()
+        .times(1)
+        .withf(|options| {
+            options.status == SpendStatus::Failed as i32
+                && options
+                    .error_message
+                    .as_ref()
+                    .is_some_and(|message| message.contains("was dropped"))
+        })
+        .returning(|options| {
+            Ok(Spend {
+                id: options.spend_id,
+                status: options.status,
+                ..Default::default()
+            })
+        });
+    let mystiko = mock_mystiko(spends).await;
+    let provider = mock_rpc_server(recover_rpc(vec![], true, 4)).await;
+    let args = recover_args("5", &provider, None);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_spend_recover_keeps_unknown_transaction_pending() {
+    let mut spends = pending_spends();
+    spends.expect_fix_status().never();
+    let mystiko = mock_mystiko(spends).await;
+    let provider = mock_rpc_server(recover_rpc(vec![], false, 4)).await;
+    let args = recover_args("5", &provider, None);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_spend_recover_settles_cancellation() {
+    let (_db_folder, db_path) = common::temp_db_path();
+    ReplacementStore::from_path(&db_path)
+        .await
+        .unwrap()
+        .add(TransactionReplacementRecord {
+            document_id: String::from("1234"),
+            chain_id: 5,
+            from: SENDER.parse().unwrap(),
+            nonce: U256::from(3),
+            replaced_transaction_hash: PENDING_HASH.parse().unwrap(),
+            transaction_hash: CANCEL_HASH.parse().unwrap(),
+            cancel: true,
+        })
+        .await
+        .unwrap();
+    let mut spends = pending_spends();
+    spends
+        .expect_fix_status()
+        .times(1)
+        .withf(|options| {
+            options.status == SpendStatus::Failed as i32
+                && options.error_message
+                    == Some(format!("spend transaction {} was cancelled", PENDING_HASH))
+        })
+        .returning(|options| {
+            Ok(Spend {
+                id: options.spend_id,
+                status: options.status,
+                ..Default::default()
+            })
+        });
+    let mystiko = mock_mystiko(spends).await;
+    let provider = mock_rpc_server(recover_rpc(vec![CANCEL_HASH], false, 4)).await;
+    let args = recover_args("5", &provider, Some(db_path.into()));
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_spend_recover_queries_nothing_for_relayer_without_hash() {
+    let mut spends = MockSpends::new();
+    spends.expect_find::<QueryFilter>().returning(|_| {
+        Ok(vec![Spend {
+            id: String::from("1234"),
+            status: SpendStatus::Pending as i32,
+            gas_relayer_url: Some(String::from("https://relayer.example")),
+            ..Default::default()
+        }])
+    });
+    spends.expect_fix_status().never();
+    let mystiko = mock_mystiko(spends).await;
+    let provider = mock_rpc_server(recover_rpc(vec![], false, 4)).await;
+    let args = recover_args("5", &provider, None);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_spend_recover_rejects_chain_mismatch() {
+    let mut spends = MockSpends::new();
+    spends.expect_find::<QueryFilter>().never();
+    let mystiko = mock_mystiko(spends).await;
+    let provider = mock_rpc_server(recover_rpc(vec![], true, 3)).await;
+    let args = recover_args("97", &provider, None);
+    let err = execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Provider is connected to chain 5 instead of 97"));
+}
+
+#[tokio::test]
+async fn test_deposit_recover_skips_resend_after_later_nonce() {
+    let mut deposits = approved_deposits();
+    deposits.expect_send().never();
+    let mystiko = mock_mystiko(deposits).await;
+    let provider = mock_rpc_server(recover_rpc(vec![], true, 5)).await;
+    let args = deposit_recover_args(&provider);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_deposit_recover_resends_after_approve_nonce() {
+    let mut deposits = approved_deposits();
+    deposits.expect_send().times(1).returning(|options| {
+        Ok(Deposit {
+            id: options.deposit_id,
+            status: DepositStatus::SrcPending as i32,
+            transaction_hash: Some(CANCEL_HASH.to_string()),
+            ..Default::default()
+        })
+    });
+    let mystiko = mock_mystiko(deposits).await;
+    let provider = mock_rpc_server(recover_rpc(vec![], true, 4)).await;
+    let args = deposit_recover_args(&provider);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+fn approved_deposits() -> MockDeposits {
+    let mut deposits = MockDeposits::new();
+    deposits.expect_find::<QueryFilter>().returning(|_| {
+        Ok(vec![Deposit {
+            id: String::from("1234"),
+            chain_id: 5,
+            status: DepositStatus::AssetApproved as i32,
+            asset_approve_transaction_hash: Some(PENDING_HASH.to_string()),
+            ..Default::default()
+        }])
+    });
+    deposits.expect_fix_status().never();
+    deposits
+}
+
+fn deposit_recover_args(provider: &str) -> MystikoCliArgs {
+    MystikoCliArgs::parse_from([
+        "mystiko",
+        "deposit",
+        "recover",
+        "--chain-id",
+        "5",
+        "--provider",
+        provider,
+        "--private-key",
+        "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        "--yes",
+    ])
+}
+
+fn pending_spends() -> MockSpends {
+    let mut spends = MockSpends::new();
+    spends.expect_find::<QueryFilter>().returning(|_| {
+        Ok(vec![Spend {
+            id: String::from("1234"),
+            status: SpendStatus::Pending as i32,
+            transaction_hash: Some(PENDING_HASH.to_string()),
+            ..Default::default()
+        }])
+    });
+    spends
+}
+
+fn recover_args(
+    chain_id: &str,
+    provider: &str,
+    replacements_path: Option<std::path::PathBuf>,
+) -> MystikoCliArgs {
+    let mut args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "spend",
+        "recover",
+        "--chain-id",
+        chain_id,
+        "--provider",
+        provider,
+    ]);
+    if let MystikoCommands::Spend(SpendCommand {
+        commands: SpendCommands::Recover(recover_args),
+    }) = &mut args.commands
+    {
+        recover_args.replacements_path = replacements_path;
+    }
+    args
+}
+
+fn recover_rpc(
+    mined: Vec<&'static str>,
+    known: bool,
+    confirmed_nonce: u64,
+) -> impl Fn(&str, &Value) -> Value + Send + Sync + 'static {
+    move |method, params| match method {
+        "eth_chainId" => json!("0x5"),
+        "eth_getTransactionReceipt" if mined.contains(&params[0].as_str().unwrap_or_default()) => {
+            json!({
+                "transactionHash": params[0],
+                "transactionIndex": "0x0",
+                "blockHash": "0x3333333333333333333333333333333333333333333333333333333333333333",
+                "blockNumber": "0x10",
+                "from": SENDER,
+                "to": SENDER,
+                "cumulativeGasUsed": "0x5208",
+                "gasUsed": "0x5208",
+                "contractAddress": null,
+                "logs": [],
+                "logsBloom": format!("0x{}", "0".repeat(512)),
+                "status": "0x1",
+            })
+        }
+        "eth_getTransactionByHash" if known => json!({
+            "hash": PENDING_HASH,
+            "nonce": "0x3",
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionIndex": null,
+            "from": SENDER,
+            "to": "0x0000000000000000000000000000000000000001",
+            "value": "0x0",
+            "gasPrice": "0x3b9aca00",
+            "gas": "0x30d40",
+            "input": "0x",
+            "v": "0x1b",
+            "r": "0x1",
+            "s": "0x1",
+        }),
+        "eth_getTransactionCount" => json!(format!("{:#x}", confirmed_nonce)),
+        _ => Value::Null,
+    }
+}
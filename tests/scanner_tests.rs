@@ -75,6 +75,7 @@ async fn test_scanner_reset() {
         "mystiko",
         "scanner",
         "reset",
+        "--yes",
         "--to-id",
         "test_to_id",
         "--shielded-address",
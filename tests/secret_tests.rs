@@ -0,0 +1,54 @@
+use clap::Parser;
+use mystiko::{AgentRequest, AgentSecret, MystikoCliArgs, SecretString};
+
+#[test]
+fn test_secret_string_redacted() {
+    let secret = SecretString::from("VeryAwes0meP@ssw0rd");
+    assert_eq!(secret.expose_secret(), "VeryAwes0meP@ssw0rd");
+    assert_eq!(format!("{:?}", secret), "[REDACTED]");
+    assert_eq!(format!("{}", secret), "[REDACTED]");
+    assert_eq!(serde_json::to_string(&secret).unwrap(), "\"[REDACTED]\"");
+    let request = AgentRequest::Unlock {
+        password: AgentSecret(secret.clone()),
+        ttl_secs: 60,
+    };
+    let wire = serde_json::to_string(&request).unwrap();
+    assert!(wire.contains("VeryAwes0meP@ssw0rd"));
+    assert!(!format!("{:?}", request).contains("VeryAwes0meP@ssw0rd"));
+    match serde_json::from_str::<AgentRequest>(&wire).unwrap() {
+        AgentRequest::Unlock { password, .. } => assert_eq!(password.0, secret),
+        _ => panic!("unexpected agent request"),
+    }
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "spend",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--private-key",
+        "0xdeadbeef",
+        "--chain-id",
+        "5",
+        "--asset-symbol",
+        "MTT",
+        "--amount",
+        "1",
+        "--recipient",
+        "0x0000000000000000000000000000000000000001",
+    ]);
+    let debug = format!("{:?}", args);
+    assert!(!debug.contains("VeryAwes0meP@ssw0rd"));
+    assert!(!debug.contains("0xdeadbeef"));
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "wallet",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--mnemonic",
+        "legal winner thank year wave sausage worth useful legal winner thank yellow",
+    ]);
+    let debug = format!("{:?}", args);
+    assert!(!debug.contains("VeryAwes0meP@ssw0rd"));
+    assert!(!debug.contains("legal winner"));
+}
@@ -0,0 +1,31 @@
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{Address, TransactionRequest};
+use mystiko::{CapturingSigner, SecretString, TRANSACTION_CAPTURED_MESSAGE};
+use mystiko_core::TransactionSigner;
+
+#[tokio::test]
+async fn test_capturing_signer() {
+    let private_key =
+        SecretString::from("0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318");
+    let signer = CapturingSigner::from_private_key(Some(&private_key)).unwrap();
+    let address = signer.address().await.unwrap();
+    assert_eq!(
+        address,
+        "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23"
+            .parse::<Address>()
+            .unwrap()
+    );
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(Address::zero())
+        .value(1000)
+        .gas(21000)
+        .into();
+    let err = signer.send_transaction(5, tx.clone()).await.unwrap_err();
+    assert_eq!(err.to_string(), TRANSACTION_CAPTURED_MESSAGE);
+    let transactions = signer.transactions();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].chain_id, 5);
+    assert_eq!(transactions[0].from, address);
+    assert_eq!(transactions[0].transaction, tx);
+    assert!(CapturingSigner::from_private_key(Some(&SecretString::from("0x1234"))).is_err());
+}
@@ -3,10 +3,11 @@ mod common;
 
 use crate::common::{mock_mystiko, MockSpends};
 use clap::Parser;
-use mystiko::{execute_with_mystiko, MystikoCliArgs};
+use mystiko::{execute_with_mystiko, max_spend_amount, MystikoCliArgs, SpendAmount};
 use mystiko_core::SpendColumn;
 use mystiko_protos::common::v1::BridgeType;
 use mystiko_protos::core::document::v1::Spend;
+use mystiko_protos::core::handler::v1::{GasRelayer, SpendQuote};
 use mystiko_protos::core::v1::{SpendStatus, SpendType};
 use mystiko_protos::storage::v1::{
     Condition, ConditionOperator, Order, OrderBy, QueryFilter, SubFilter,
@@ -104,6 +105,7 @@ async fn test_spend_create() {
         "mystiko",
         "spend",
         "create",
+        "--yes",
         "--chain-id",
         "5",
         "--asset-symbol",
@@ -213,3 +215,70 @@ async fn test_spend_list() {
         .await
         .unwrap();
 }
+
+#[test]
+fn test_spend_amount() {
+    assert_eq!("max".parse::<SpendAmount>().unwrap(), SpendAmount::Max);
+    assert_eq!("MAX".parse::<SpendAmount>().unwrap(), SpendAmount::Max);
+    assert_eq!(
+        "1.5".parse::<SpendAmount>().unwrap(),
+        SpendAmount::Exact(1.5)
+    );
+    assert!("all".parse::<SpendAmount>().is_err());
+    assert!("inf".parse::<SpendAmount>().is_err());
+    assert_eq!(SpendAmount::Max.to_string(), "max");
+    assert_eq!(SpendAmount::Exact(2.0).exact(), Some(2.0));
+}
+
+#[test]
+fn test_max_spend_amount() {
+    let quote = SpendQuote {
+        asset_symbol: "MTT".to_string(),
+        asset_decimals: 18,
+        current_balance: 10.0,
+        min_amount: 0.1,
+        max_amount: 100.0,
+        min_rollup_fee_amount: 0.25,
+        rollup_fee_asset_symbol: "MTT".to_string(),
+        max_gas_relayer_fee_amount: 2.0,
+        gas_relayers: vec![GasRelayer {
+            name: "relayer_01".to_string(),
+            url: "https://relayer.example".to_string(),
+            min_gas_fee_amount: 0.5,
+            ..Default::default()
+        }],
+        valid: true,
+        ..Default::default()
+    };
+    assert_eq!(max_spend_amount(&quote, None, None).unwrap(), 9.75);
+    assert_eq!(max_spend_amount(&quote, Some(1.0), None).unwrap(), 9.0);
+    assert_eq!(
+        max_spend_amount(&quote, None, Some("relayer_01")).unwrap(),
+        9.25
+    );
+    assert!(max_spend_amount(&quote, None, Some("unknown")).is_err());
+
+    let capped = SpendQuote {
+        max_amount: 5.0,
+        ..quote.clone()
+    };
+    assert_eq!(max_spend_amount(&capped, None, None).unwrap(), 5.0);
+    let fixed = SpendQuote {
+        fixed_amounts: vec![1.0, 5.0, 10.0],
+        ..quote.clone()
+    };
+    assert_eq!(max_spend_amount(&fixed, None, None).unwrap(), 5.0);
+    let other_fee_asset = SpendQuote {
+        rollup_fee_asset_symbol: "ETH".to_string(),
+        ..quote.clone()
+    };
+    assert_eq!(
+        max_spend_amount(&other_fee_asset, None, None).unwrap(),
+        10.0
+    );
+    let empty = SpendQuote {
+        current_balance: 0.2,
+        ..quote
+    };
+    assert!(max_spend_amount(&empty, None, None).is_err());
+}
@@ -120,6 +120,7 @@ async fn test_synchronizer_reset() {
         "mystiko",
         "synchronizer",
         "reset",
+        "--yes",
         "--chain-id",
         "1",
         "--contract-address",
@@ -0,0 +1,164 @@
+#[allow(dead_code)]
+mod common;
+
+use crate::common::{mock_mystiko, mock_rpc_server, MockDeposits, MockSpends};
+use clap::Parser;
+use ethers_core::types::U256;
+use mystiko::{bump_fee, execute_with_mystiko, MystikoCliArgs, ReplacementStore};
+use mystiko_protos::core::document::v1::{Deposit, Spend};
+use mystiko_protos::core::v1::{DepositStatus, SpendStatus};
+use serde_json::{json, Value};
+
+const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const SENDER: &str = "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+const PENDING_HASH: &str = "0x1111111111111111111111111111111111111111111111111111111111111111";
+const REPLACEMENT_HASH: &str = "0x2222222222222222222222222222222222222222222222222222222222222222";
+
+#[test]
+fn test_bump_fee() {
+    assert_eq!(bump_fee(U256::from(100), 12), U256::from(112));
+    assert_eq!(bump_fee(U256::from(101), 12), U256::from(114));
+    assert_eq!(
+        bump_fee(U256::from(30_000_000_000u64), 10),
+        U256::from(33_000_000_000u64)
+    );
+    assert_eq!(bump_fee(U256::zero(), 12), U256::zero());
+}
+
+#[tokio::test]
+async fn test_deposit_cancel_keeps_pending() {
+    let mut deposits = MockDeposits::new();
+    deposits.expect_find_by_id().returning(|id| {
+        Ok(Some(Deposit {
+            id,
+            status: DepositStatus::SrcPending as i32,
+            transaction_hash: Some(PENDING_HASH.to_string()),
+            ..Default::default()
+        }))
+    });
+    deposits.expect_update().times(0);
+    let mystiko = mock_mystiko(deposits).await;
+    let provider = mock_rpc_server(legacy_rpc).await;
+    let (_db_folder, db_path) = common::temp_db_path();
+    let args = replace_args("deposit", "cancel", &provider, &db_path);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let records = ReplacementStore::from_path(&db_path)
+        .await
+        .unwrap()
+        .document_records("1234")
+        .await
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    assert!(records[0].cancel);
+    assert_eq!(records[0].nonce, U256::from(3));
+    assert_eq!(
+        records[0].replaced_transaction_hash,
+        PENDING_HASH.parse().unwrap()
+    );
+    assert_eq!(
+        records[0].transaction_hash,
+        REPLACEMENT_HASH.parse().unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_spend_cancel_keeps_pending() {
+    let mut spends = MockSpends::new();
+    spends.expect_find_by_id().returning(|id| {
+        Ok(Some(Spend {
+            id,
+            status: SpendStatus::Pending as i32,
+            transaction_hash: Some(PENDING_HASH.to_string()),
+            ..Default::default()
+        }))
+    });
+    spends.expect_update().times(0);
+    let mystiko = mock_mystiko(spends).await;
+    let provider = mock_rpc_server(legacy_rpc).await;
+    let (_db_folder, db_path) = common::temp_db_path();
+    let args = replace_args("spend", "cancel", &provider, &db_path);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_spend_speed_up_replaces_hash() {
+    let mut spends = MockSpends::new();
+    spends.expect_find_by_id().returning(|id| {
+        Ok(Some(Spend {
+            id,
+            status: SpendStatus::Pending as i32,
+            transaction_hash: Some(PENDING_HASH.to_string()),
+            ..Default::default()
+        }))
+    });
+    spends
+        .expect_update()
+        .times(1)
+        .withf(|spend| {
+            spend.status == SpendStatus::Pending as i32
+                && spend.transaction_hash.as_deref() == Some(REPLACEMENT_HASH)
+                && spend.error_message.is_none()
+        })
+        .returning(Ok);
+    let mystiko = mock_mystiko(spends).await;
+    let provider = mock_rpc_server(legacy_rpc).await;
+    let (_db_folder, db_path) = common::temp_db_path();
+    let args = replace_args("spend", "speed-up", &provider, &db_path);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let records = ReplacementStore::from_path(&db_path)
+        .await
+        .unwrap()
+        .records()
+        .await
+        .unwrap();
+    assert_eq!(records.len(), 1);
+    assert!(!records[0].cancel);
+}
+
+fn replace_args(document: &str, command: &str, provider: &str, db_path: &str) -> MystikoCliArgs {
+    let mut args = MystikoCliArgs::parse_from([
+        "mystiko",
+        document,
+        command,
+        "--id",
+        "1234",
+        "--provider",
+        provider,
+        "--private-key",
+        PRIVATE_KEY,
+        "--yes",
+    ]);
+    *args.commands.replacements_path_mut().unwrap() = Some(db_path.into());
+    args
+}
+
+fn legacy_rpc(method: &str, _params: &Value) -> Value {
+    match method {
+        "eth_getTransactionByHash" => json!({
+            "hash": PENDING_HASH,
+            "nonce": "0x3",
+            "blockHash": null,
+            "blockNumber": null,
+            "transactionIndex": null,
+            "from": SENDER,
+            "to": "0x0000000000000000000000000000000000000001",
+            "value": "0x0",
+            "gasPrice": "0x3b9aca00",
+            "gas": "0x30d40",
+            "input": "0x",
+            "v": "0x1b",
+            "r": "0x1",
+            "s": "0x1",
+        }),
+        "eth_chainId" => json!("0x5"),
+        "eth_gasPrice" => json!("0x3b9aca00"),
+        "eth_sendRawTransaction" => json!(REPLACEMENT_HASH),
+        _ => Value::Null,
+    }
+}
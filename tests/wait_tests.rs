@@ -0,0 +1,44 @@
+use mystiko::{status_name, status_reached, DEPOSIT_STATUS_PROGRESSION, SPEND_STATUS_PROGRESSION};
+use mystiko_protos::core::v1::{DepositStatus, SpendStatus};
+
+#[test]
+fn test_status_reached() {
+    let progression = DEPOSIT_STATUS_PROGRESSION.map(|status| status as i32);
+    let failed = DepositStatus::Failed as i32;
+    let executed = DepositStatus::Executed as i32;
+    let queued = DepositStatus::Queued as i32;
+    assert!(status_reached(executed, executed, &progression));
+    assert!(status_reached(executed, queued, &progression));
+    assert!(!status_reached(queued, executed, &progression));
+    assert!(!status_reached(failed, executed, &progression));
+    assert!(status_reached(failed, failed, &progression));
+    assert!(!status_reached(executed, failed, &progression));
+    assert!(!status_reached(
+        DepositStatus::Unspecified as i32,
+        queued,
+        &progression
+    ));
+    let progression = SPEND_STATUS_PROGRESSION.map(|status| status as i32);
+    assert!(status_reached(
+        SpendStatus::Succeeded as i32,
+        SpendStatus::Pending as i32,
+        &progression
+    ));
+    assert!(!status_reached(
+        SpendStatus::Failed as i32,
+        SpendStatus::Succeeded as i32,
+        &progression
+    ));
+}
+
+#[test]
+fn test_status_name() {
+    assert_eq!(
+        status_name("DEPOSIT_STATUS_EXECUTED", "DEPOSIT_STATUS_"),
+        "executed"
+    );
+    assert_eq!(
+        status_name("SPEND_STATUS_SUCCEEDED", "SPEND_STATUS_"),
+        "succeeded"
+    );
+}
@@ -1,8 +1,16 @@
 #[allow(dead_code)]
 mod common;
 
+use crate::common::{mock_mystiko, MockDeposits, MockSynchronizer};
 use clap::Parser;
-use mystiko::MystikoCliArgs;
+use mystiko::{
+    combine_mnemonic_shares, execute_wallet_update_password_command, execute_with_mystiko,
+    split_mnemonic_phrase, validate_mnemonic_phrase, MystikoCliArgs, MystikoCommands,
+    PasswordRotationJournal, WalletCommand, WalletCommands,
+};
+use mystiko_core::{AccountHandler, WalletHandler};
+use mystiko_protos::core::synchronizer::v1::{ChainStatus, SynchronizerStatus};
+use mystiko_protos::core::v1::{AccountStatus, MnemonicType};
 
 #[tokio::test]
 async fn test_wallet_create() {
@@ -46,6 +54,107 @@ async fn test_wallet_import() {
     mystiko::execute(args).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_wallet_import_with_mnemonic_type() {
+    let (_, db_path) = common::temp_db_path();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--mnemonic-type",
+        "rust",
+        "--mnemonic",
+        "legal winner thank year wave sausage worth useful legal winner thank yellow",
+    ]);
+    mystiko::execute(args).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_wallet_import_preview() {
+    let (_, db_path) = common::temp_db_path();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--preview",
+        "--mnemonic",
+        "legal winner thank year wave sausage worth useful legal \
+        winner thank year wave sausage worth useful legal will",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "verify-password",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+
+    let mystiko = mock_mystiko(MockDeposits::new()).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "wallet",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--preview",
+        "--mnemonic",
+        "legal winner thank year wave sausage worth useful legal winner thank yellow",
+    ]);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    assert!(mystiko.wallets.current().await.unwrap().is_none());
+    assert!(mystiko.accounts.find_all().await.unwrap().is_empty());
+}
+
+#[test]
+fn test_validate_mnemonic_phrase() {
+    assert_eq!(
+        validate_mnemonic_phrase(
+            "letter advice cage absurd amount doctor acoustic avoid letter advice cage above"
+        )
+        .unwrap()
+        .word_count(),
+        12
+    );
+    let err = validate_mnemonic_phrase("abandon abandon abandon").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid mnemonic phrase: expected 12, 15, 18, 21 or 24 words, got 3"
+    );
+    let err = validate_mnemonic_phrase(
+        "abandon abandon abandon abandon abandon abandon abandn abandon abandon abandon abandon about",
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid mnemonic phrase: word 7 'abandn' not in wordlist, did you mean 'abandon'"
+    );
+    let err = validate_mnemonic_phrase(
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("checksum mismatch"));
+}
+
 #[tokio::test]
 async fn test_wallet_export_mnemonic_phrase() {
     let (_, db_path) = common::temp_db_path();
@@ -75,6 +184,133 @@ async fn test_wallet_export_mnemonic_phrase() {
     mystiko::execute(args).await.unwrap();
 }
 
+#[tokio::test]
+async fn test_wallet_export_mnemonic_shares() {
+    let (_, db_path) = common::temp_db_path();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let folder = tempfile::tempdir().unwrap();
+    let out_dir = folder.path().to_string_lossy().to_string();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "export-mnemonic",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--shares",
+        "3",
+        "--threshold",
+        "2",
+        "--out-dir",
+        &out_dir,
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let share_file = |index: usize| {
+        folder
+            .path()
+            .join(format!("share-{}.txt", index))
+            .to_string_lossy()
+            .to_string()
+    };
+
+    let (_, new_db_path) = common::temp_db_path();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &new_db_path,
+        "wallet",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--shares",
+        &share_file(1),
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &new_db_path,
+        "wallet",
+        "import",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--shares",
+        &share_file(1),
+        &share_file(3),
+    ]);
+    mystiko::execute(args).await.unwrap();
+}
+
+#[test]
+fn test_split_mnemonic_phrase() {
+    let phrase = "legal winner thank year wave sausage worth useful legal winner thank yellow";
+    let shares = split_mnemonic_phrase(phrase, MnemonicType::Web, 5, 3).unwrap();
+    assert_eq!(shares.len(), 5);
+    assert!(shares.iter().all(|share| !share.contains("legal")));
+    assert!(shares
+        .iter()
+        .all(|share| share.starts_with("mystiko-share-v1:web:3:")));
+    let (mnemonic, mnemonic_type) = combine_mnemonic_shares(&shares[1..4]).unwrap();
+    assert_eq!(mnemonic.to_string(), phrase);
+    assert_eq!(mnemonic_type, MnemonicType::Web);
+    let (mnemonic, _) =
+        combine_mnemonic_shares(&[shares[4].clone(), shares[0].clone(), shares[2].clone()])
+            .unwrap();
+    assert_eq!(mnemonic.to_string(), phrase);
+    let (mnemonic, _) = combine_mnemonic_shares(&shares).unwrap();
+    assert_eq!(mnemonic.to_string(), phrase);
+    assert!(combine_mnemonic_shares(&shares[..2]).is_err());
+    assert!(
+        combine_mnemonic_shares(&[shares[0].clone(), shares[0].clone(), shares[1].clone()])
+            .is_err()
+    );
+    let other_shares = split_mnemonic_phrase(phrase, MnemonicType::Web, 5, 3).unwrap();
+    assert_ne!(shares, other_shares);
+    assert!(combine_mnemonic_shares(&[
+        shares[0].clone(),
+        shares[1].clone(),
+        other_shares[2].clone()
+    ])
+    .is_err());
+    let rust_shares = split_mnemonic_phrase(phrase, MnemonicType::Rust, 3, 2).unwrap();
+    let (_, mnemonic_type) = combine_mnemonic_shares(&rust_shares[..2]).unwrap();
+    assert_eq!(mnemonic_type, MnemonicType::Rust);
+    let mut tampered = shares[0].clone();
+    tampered.replace_range(24..25, if &tampered[24..25] == "0" { "1" } else { "0" });
+    assert!(combine_mnemonic_shares(&[tampered, shares[1].clone(), shares[2].clone()]).is_err());
+    assert!(split_mnemonic_phrase(phrase, MnemonicType::Web, 2, 3).is_err());
+    assert!(MystikoCliArgs::try_parse_from([
+        "mystiko",
+        "wallet",
+        "export-mnemonic",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--shares",
+        "3",
+        "--threshold",
+        "2",
+    ])
+    .is_err());
+}
+
 #[tokio::test]
 async fn test_wallet_update_password() {
     let (_, db_path) = common::temp_db_path();
@@ -104,4 +340,394 @@ async fn test_wallet_update_password() {
         "VeryAwes0meP@ssw0rd2",
     ]);
     mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "update-password",
+        "--old",
+        "VeryAwes0meP@ssw0rd",
+        "--new",
+        "VeryAwes0meP@ssw0rd3",
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "verify-password",
+        "--password",
+        "VeryAwes0meP@ssw0rd2",
+    ]);
+    mystiko::execute(args).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_wallet_verify_password() {
+    let (_, db_path) = common::temp_db_path();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "verify-password",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "verify-password",
+        "--password",
+        "Wr0ngP@ssw0rd",
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+}
+
+#[tokio::test]
+async fn test_wallet_backup_restore() {
+    let (_, db_path) = common::temp_db_path();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "account",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--name",
+        "Test Account #1",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let folder = tempfile::tempdir().unwrap();
+    let backup_path = folder
+        .path()
+        .join("backup.json")
+        .to_string_lossy()
+        .to_string();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "backup",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--backup-passphrase",
+        "B@ckupP@ssphrase",
+        "--with-scanner-progress",
+        "--out",
+        &backup_path,
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "wallet",
+        "restore",
+        &backup_path,
+        "--backup-passphrase",
+        "B@ckupP@ssphrase",
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+
+    let (_, new_db_path) = common::temp_db_path();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &new_db_path,
+        "wallet",
+        "restore",
+        &backup_path,
+        "--backup-passphrase",
+        "Wr0ngP@ssphrase",
+    ]);
+    assert!(mystiko::execute(args).await.is_err());
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &new_db_path,
+        "wallet",
+        "restore",
+        &backup_path,
+        "--backup-passphrase",
+        "B@ckupP@ssphrase",
+    ]);
+    mystiko::execute(args).await.unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &new_db_path,
+        "account",
+        "get",
+        "--name",
+        "Test Account #1",
+    ]);
+    mystiko::execute(args).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_wallet_info() {
+    let mut synchronizer = MockSynchronizer::new();
+    synchronizer
+        .expect_status()
+        .withf(|with_contracts| !*with_contracts)
+        .times(2)
+        .returning(|_| {
+            Ok(SynchronizerStatus::builder()
+                .chains(vec![ChainStatus::builder()
+                    .chain_id(5_u64)
+                    .synced_block(10000001_u64)
+                    .build()])
+                .build())
+        });
+    let mystiko = mock_mystiko(synchronizer).await;
+    let args = MystikoCliArgs::parse_from(["mystiko", "wallet", "info"]);
+    mystiko::execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "wallet",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    mystiko::execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "account",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--name",
+        "Test Account #1",
+    ]);
+    mystiko::execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let args = MystikoCliArgs::parse_from(["mystiko", "wallet", "info"]);
+    mystiko::execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_wallet_restore_without_scanner_progress() {
+    let mystiko = mock_mystiko(MockDeposits::new()).await;
+    let commands = [
+        vec!["wallet", "create", "--password", "VeryAwes0meP@ssw0rd"],
+        vec![
+            "account",
+            "create",
+            "--password",
+            "VeryAwes0meP@ssw0rd",
+            "--name",
+            "Test Account #1",
+        ],
+    ];
+    for command in commands {
+        let args = MystikoCliArgs::parse_from(std::iter::once("mystiko").chain(command));
+        execute_with_mystiko(&mystiko, args.commands, false)
+            .await
+            .unwrap();
+    }
+    let account = mystiko.accounts.find_all().await.unwrap().remove(0);
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "account",
+        "update",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--shielded-address",
+        &account.shielded_address,
+        "--status",
+        "scanned",
+    ]);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let folder = tempfile::tempdir().unwrap();
+    let backup_path = folder
+        .path()
+        .join("backup.json")
+        .to_string_lossy()
+        .to_string();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "wallet",
+        "backup",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+        "--backup-passphrase",
+        "B@ckupP@ssphrase",
+        "--out",
+        &backup_path,
+    ]);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+
+    let restored = mock_mystiko(MockDeposits::new()).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "wallet",
+        "restore",
+        &backup_path,
+        "--backup-passphrase",
+        "B@ckupP@ssphrase",
+    ]);
+    execute_with_mystiko(&restored, args.commands, false)
+        .await
+        .unwrap();
+    let account = restored.accounts.find_all().await.unwrap().remove(0);
+    assert_eq!(account.name, "Test Account #1");
+    assert_eq!(account.status, AccountStatus::Created as i32);
+    assert!(account.scanned_to_id.is_none());
+}
+
+#[tokio::test]
+async fn test_wallet_update_password_journal() {
+    let mystiko = mock_mystiko(MockDeposits::new()).await;
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "wallet",
+        "create",
+        "--password",
+        "VeryAwes0meP@ssw0rd",
+    ]);
+    execute_with_mystiko(&mystiko, args.commands, false)
+        .await
+        .unwrap();
+    let folder = tempfile::tempdir().unwrap();
+    let journal_path = folder.path().join("mystiko.db.password-rotation.json");
+    let update_password = |old: &str, new: &str| match MystikoCliArgs::parse_from([
+        "mystiko",
+        "wallet",
+        "update-password",
+        "--old",
+        old,
+        "--new",
+        new,
+    ])
+    .commands
+    {
+        MystikoCommands::Wallet(WalletCommand {
+            commands: WalletCommands::UpdatePassword(args),
+        }) => args,
+        _ => panic!("unexpected command"),
+    };
+    let journal = PasswordRotationJournal {
+        wallet: mystiko.wallets.current().await.unwrap().unwrap(),
+        accounts: vec![],
+    };
+    execute_wallet_update_password_command(
+        &mystiko,
+        update_password("VeryAwes0meP@ssw0rd", "N3wAwes0meP@ssw0rd"),
+        Some(&journal_path),
+        false,
+    )
+    .await
+    .unwrap();
+    assert!(!journal_path.exists());
+    mystiko
+        .wallets
+        .check_password("N3wAwes0meP@ssw0rd")
+        .await
+        .unwrap();
+
+    journal.write(&journal_path).await.unwrap();
+    execute_wallet_update_password_command(
+        &mystiko,
+        update_password("VeryAwes0meP@ssw0rd", "An0therP@ssw0rd"),
+        Some(&journal_path),
+        false,
+    )
+    .await
+    .unwrap();
+    assert!(!journal_path.exists());
+    mystiko
+        .wallets
+        .check_password("An0therP@ssw0rd")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_wallet_pending_password_rotation_blocks_commands() {
+    let (db_folder, db_path) = common::temp_db_path();
+    let journal_path = db_folder.path().join("test.db.password-rotation.json");
+    PasswordRotationJournal {
+        wallet: Default::default(),
+        accounts: vec![],
+    }
+    .write(&journal_path)
+    .await
+    .unwrap();
+    let args = MystikoCliArgs::parse_from([
+        "mystiko",
+        "--config-path",
+        "tests/files/config.json",
+        "--db-path",
+        &db_path,
+        "account",
+        "list",
+    ]);
+    let err = mystiko::execute(args).await.unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("An interrupted wallet password rotation is pending"));
+    assert!(journal_path.exists());
 }